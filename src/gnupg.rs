@@ -1,19 +1,32 @@
 use std::collections::HashMap;
 use std::env;
 use std::fs::File;
-use std::io::Read;
-use std::path::PathBuf;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 use chrono::Local;
 
-use crate::process::handle_cmd_io;
-use crate::utils::enums::{Operation, TrustLevel};
+use crate::passphrase::{PassphraseContext, PassphraseProvider};
+use crate::process::{
+    handle_cmd_io, handle_cmd_io_output, handle_cmd_pipe, handle_cmd_session_key, handle_cmd_stream,
+    handle_cmd_verify_stream, handle_tar_create, handle_tar_extract, PipeCmd,
+};
+use crate::stream::{GpgDecryptReader, GpgEncryptWriter};
+use crate::utils::enums::{
+    AeadAlgo, CipherAlgo, Compliance, CompressAlgo, DigestAlgo, LocateMechanism, Operation,
+    PolicyRejection, SignMode, TrustLevel,
+};
+use crate::utils::archive::{read_tar, write_tar};
 use crate::utils::utils::get_file_obj;
 use crate::utils::{
     errors::{GPGError, GPGErrorType},
-    response::{CmdResult, ListKeyResult},
+    response::{
+        ArchiveEntry, CmdResult, CopyKeyResult, DecryptResult, GroupResult, ReencryptResult, ListKeyResult, LocateKeyResult, MessageInfo,
+        PolicyResult, PolicyViolation, SearchKeyResult, SignResult, StreamVerifyResult, VerifyResult,
+    },
     utils::{
-        check_is_dir, decode_list_key_result, get_file_extension, get_gpg_version,
+        check_is_dir, decode_archive_list, decode_decrypt_result, decode_group_config, decode_import_ok_result, decode_list_config_result, decode_list_key_result, decode_message_info, decode_search_key_result, decode_sign_result, decode_verify_result, get_file_extension, get_gpg_version, is_key_not_found, list_files_in_dir,
         get_or_create_gpg_homedir, get_or_create_gpg_output_dir, is_passphrase_valid,
         set_output_without_confirmation,
     },
@@ -45,6 +58,9 @@ pub struct GPG {
     pub version: f32,
     // the full version of gpg, should only be set by system, user should not set this ex) 2.4.6
     pub full_version: String,
+    // recipient group(s) ( name -> member keyid(s), fingerprint(s) or email(s) ),
+    // loaded from the group option of gpg.conf and can be extended with add_group
+    pub groups: HashMap<String, Vec<String>>,
 }
 
 impl GPG {
@@ -92,6 +108,7 @@ impl GPG {
                     armor: armor,
                     version: version.0,
                     full_version: version.1,
+                    groups: decode_group_config(&result),
                 });
             }
             Err(e) => {
@@ -267,6 +284,162 @@ impl GPG {
 
     //*******************************************************
 
+    //                   LIST CONFIG
+
+    //*******************************************************
+    // list the configuration of gpg, including the algorithms it support
+    pub fn list_config(&self) -> Result<HashMap<String, Vec<String>>, GPGError> {
+        // ex) "ciphername" -> ["IDEA", "3DES", "CAST5", ...], "digestname" -> ["SHA1", "RIPEMD160", ...]
+
+        let result: Result<CmdResult, GPGError> = handle_cmd_io(
+            Some(vec![
+                "--list-config".to_string(),
+                "--with-colons".to_string(),
+            ]),
+            None,
+            self.version,
+            self.homedir.clone(),
+            self.options.clone(),
+            self.env.clone(),
+            None,
+            None,
+            None,
+            false,
+            false,
+            Operation::ListConfig,
+        );
+        match result {
+            Ok(result) => {
+                return Ok(decode_list_config_result(&result));
+            }
+            Err(e) => {
+                return Err(e);
+            }
+        }
+    }
+
+    //*******************************************************
+
+    //                 RECIPIENT GROUP
+
+    //*******************************************************
+    // add ( or replace ) a recipient group, the name of the group can then be used as recipient when encrypting
+    pub fn add_group(&mut self, name: String, members: Vec<String>) {
+        // name: name of the group ex) ops-team
+        // members: keyid(s), fingerprint(s) or email(s) of the member(s)
+
+        self.groups.insert(name, members);
+    }
+
+    // remove a recipient group, the members of the removed group will be returned
+    pub fn remove_group(&mut self, name: String) -> Option<Vec<String>> {
+        // name: name of the group
+
+        return self.groups.remove(&name);
+    }
+
+    // expand a recipient group into the fingerprint(s) of its member(s)
+    // and report the member(s) that was missing, expired or revoked
+    pub fn expand_group(&self, name: String) -> Result<GroupResult, GPGError> {
+        // name: name of the group
+
+        let members: Vec<String> = match self.groups.get(&name) {
+            Some(members) => members.clone(),
+            None => {
+                return Err(GPGError::new(
+                    GPGErrorType::RecipientGroupError(format!("group {} do not exist", name)),
+                    None,
+                ));
+            }
+        };
+        let mut group: GroupResult = GroupResult {
+            name: name,
+            fingerprints: vec![],
+            missing: vec![],
+            expired: vec![],
+            revoked: vec![],
+        };
+        for member in members {
+            // the member must match exactly, gpg would otherwise match any user id containing it
+            let keys: Vec<ListKeyResult> =
+                match self.list_keys(false, Some(vec![gen_member_selector(&member)]), false) {
+                    Ok(keys) => keys,
+                    Err(e) => {
+                        if is_key_not_found(&e) {
+                            vec![]
+                        } else {
+                            return Err(e);
+                        }
+                    }
+                };
+            if keys.len() == 0 {
+                group.missing.push(member);
+                continue;
+            }
+            if keys.len() > 1 {
+                return Err(GPGError::new(
+                    GPGErrorType::RecipientGroupError(format!(
+                        "member {} of group {} matches {} keys, use the fingerprint of the key instead",
+                        member,
+                        group.name,
+                        keys.len()
+                    )),
+                    None,
+                ));
+            }
+            for key in keys {
+                if key.validity == "e" {
+                    group.expired.push(member.clone());
+                } else if key.validity == "r" {
+                    group.revoked.push(member.clone());
+                } else if !group.fingerprints.contains(&key.fingerprint) {
+                    group.fingerprints.push(key.fingerprint);
+                }
+            }
+        }
+        return Ok(group);
+    }
+
+    // replace the group name(s) in the recipients with the fingerprint(s) of their member(s)
+    fn expand_recipients(
+        &self,
+        recipients: Option<Vec<String>>,
+    ) -> Result<Option<Vec<String>>, GPGError> {
+        if recipients.is_none() || self.groups.len() == 0 {
+            return Ok(recipients);
+        }
+        let mut expanded: Vec<String> = vec![];
+        for recipient in recipients.unwrap() {
+            if !self.groups.contains_key(&recipient) {
+                expanded.push(recipient);
+                continue;
+            }
+            let group: Result<GroupResult, GPGError> = self.expand_group(recipient);
+            if let Err(e) = group {
+                return Err(e);
+            }
+            let group: GroupResult = group.unwrap();
+            // the encryption should not start if any member of the group would be left out
+            if group.missing.len() > 0 || group.expired.len() > 0 || group.revoked.len() > 0 {
+                return Err(GPGError::new(
+                    GPGErrorType::RecipientGroupError(format!(
+                        "group {} has unusable member(s), missing: {:?}, expired: {:?}, revoked: {:?}",
+                        group.name, group.missing, group.expired, group.revoked
+                    )),
+                    None,
+                ));
+            }
+            for fingerprint in group.fingerprints {
+                if !expanded.contains(&fingerprint) {
+                    expanded.push(fingerprint);
+                }
+            }
+        }
+        return Ok(Some(expanded));
+    }
+
+    //*******************************************************
+
     //                   DELETE KEY
 
     //*******************************************************
@@ -321,6 +494,50 @@ impl GPG {
         return result;
     }
 
+    pub fn delete_primary_secret(
+        &self,
+        fingerprint: String,
+        passphrase: Option<String>,
+    ) -> Result<CmdResult, GPGError> {
+        // fingerprint: fingerprint of the primary key which secret part will be removed
+        // passphrase: passphrase for passphrase protected secret keys
+
+        // NOTE: only the secret part of the primary key will be removed, the secret subkeys will be kept
+        //       and list_keys(true, ..) will show the primary key as a stub ( sec# )
+
+        let key_list: Result<Vec<ListKeyResult>, GPGError> =
+            self.list_keys(true, Some(vec![fingerprint.clone()]), false);
+        match key_list {
+            Ok(key_list) => {
+                if key_list.len() == 0 || key_list[0].fingerprint != fingerprint {
+                    return Err(GPGError::new(
+                        GPGErrorType::InvalidArgumentError(
+                            "fingerprint provided is not a primary secret key".to_string(),
+                        ),
+                        None,
+                    ));
+                }
+                if !key_list[0].subkeys.iter().any(|x| !x.secret_stub) {
+                    // without any secret subkey left, removing the primary secret will leave no usable secret key
+                    return Err(GPGError::new(
+                        GPGErrorType::InvalidArgumentError(
+                            "key has no secret subkeys to keep".to_string(),
+                        ),
+                        None,
+                    ));
+                }
+            }
+            Err(e) => {
+                return Err(e);
+            }
+        }
+
+        // the "!" suffix used for subkeys restrict the deletion to the exact (primary) key only
+        let result: Result<CmdResult, GPGError> =
+            self.delete_keys(vec![fingerprint], true, true, passphrase);
+        return result;
+    }
+
     //*******************************************************
 
     //                   ADD SUBKEY
@@ -530,16 +747,22 @@ impl GPG {
         &self,
         key_id: Option<Vec<String>>,
         output: Option<String>,
+        armor: Option<bool>,
     ) -> Result<CmdResult, GPGError> {
         // key_id: list of keyid(s) to export, if not provided, all public keys will be exported
         // output: path that the exported key file will be saved to
+        // armor: whether to ascii armor the exported key, will use the armor set in GPG if not provided
 
+        let armor: bool = armor.unwrap_or(self.armor);
         let mut args: Vec<String> = vec!["--export".to_string()];
+        if armor {
+            args.push("--armor".to_string());
+        }
         if output.is_some() {
             set_output_without_confirmation(&mut args, &output.unwrap());
         } else {
             // if output folder not specified, system will create a exported_public_key folder in the set output dir when initalizling the gpg
-            // all exported public key will be saved to there with filename as public_key_<timestamp>.asc
+            // all exported public key will be saved to there with filename as public_key_<timestamp>.<asc or gpg>
             let gpg_p_key_output_dir = PathBuf::from(self.output_dir.clone())
                 .join("exported_public_key")
                 .to_string_lossy()
@@ -549,7 +772,11 @@ impl GPG {
             }
             let time_stamp: String = Local::now().format("%Y%m%d-%H:%M:%S:%9f").to_string();
             let gpg_p_key_output = PathBuf::from(gpg_p_key_output_dir)
-                .join(format!("public_key_{}.asc", time_stamp))
+                .join(format!(
+                    "public_key_{}.{}",
+                    time_stamp,
+                    if armor { "asc" } else { "gpg" }
+                ))
                 .to_string_lossy()
                 .to_string();
             set_output_without_confirmation(&mut args, &gpg_p_key_output);
@@ -558,7 +785,7 @@ impl GPG {
             args.append(&mut key_id.unwrap());
         }
         let result: Result<CmdResult, GPGError> =
-            self.export_key(args, None, Operation::ExportPublicKey);
+            self.export_key(args, None, None, Operation::ExportPublicKey);
         return result;
     }
 
@@ -567,17 +794,20 @@ impl GPG {
         key_id: Option<Vec<String>>,
         passphrase: Option<String>,
         output: Option<String>,
+        armor: Option<bool>,
     ) -> Result<CmdResult, GPGError> {
         // key_id: list of keyid(s) to export, if not provided, all secret keys will be exported
         // passphrase: for gpg version > 2.1, passphrase for passphrase proctected secret keys are required
         // output: path that the exported key file will be saved to
+        // armor: whether to ascii armor the exported key, will use the armor set in GPG if not provided
 
         //*****************************************************************************
         //  NOTE: If there are 2 or more secret key that are
         //        passphrase proctected ( but different passphrase )
         //        are being exported, keys that are protected by the provided passphrase
         //        and keys that aren't passphrase protected will be exported
-        //        ( as gpg can only read 1 passphrase at a time from STDIN)
+        //        ( as gpg can only read 1 passphrase at a time from the passphrase fd )
+        //        use export_secret_key_with_provider to provide the passphrase of each key
         //*****************************************************************************
 
         if passphrase.is_some() {
@@ -588,13 +818,50 @@ impl GPG {
                 ));
             }
         }
+        let args: Vec<String> =
+            self.gen_export_secret_key_args(key_id, output, armor.unwrap_or(self.armor));
+        let result: Result<CmdResult, GPGError> =
+            self.export_key(args, passphrase, None, Operation::ExportSecretKey);
+        return result;
+    }
+
+    // to export secret keys that are protected by different passphrase,
+    // the provider will be called for the passphrase of each key that gpg need
+    pub fn export_secret_key_with_provider(
+        &self,
+        key_id: Option<Vec<String>>,
+        provider: Arc<dyn PassphraseProvider>,
+        output: Option<String>,
+        armor: Option<bool>,
+    ) -> Result<CmdResult, GPGError> {
+        // key_id: list of keyid(s) to export, if not provided, all secret keys will be exported
+        // provider: passphrase provider that will be called for the passphrase of each passphrase protected key
+        // output: path that the exported key file will be saved to
+        // armor: whether to ascii armor the exported key, will use the armor set in GPG if not provided
 
+        let args: Vec<String> =
+            self.gen_export_secret_key_args(key_id, output, armor.unwrap_or(self.armor));
+        let provider: Option<PassphraseContext> = self.gen_passphrase_context(Some(provider), None);
+        let result: Result<CmdResult, GPGError> =
+            self.export_key(args, None, provider, Operation::ExportSecretKey);
+        return result;
+    }
+
+    fn gen_export_secret_key_args(
+        &self,
+        key_id: Option<Vec<String>>,
+        output: Option<String>,
+        armor: bool,
+    ) -> Vec<String> {
         let mut args: Vec<String> = vec!["--export-secret-key".to_string()];
+        if armor {
+            args.push("--armor".to_string());
+        }
         if output.is_some() {
             set_output_without_confirmation(&mut args, &output.unwrap());
         } else {
             // if output folder not specified, system will create a exported_secret_key folder in the set output dir when initalizling the gpg
-            // all exported secret key will be saved to there with filename as secret_key_<timestamp>.sec.asc
+            // all exported secret key will be saved to there with filename as secret_key_<timestamp>.sec.<asc or gpg>
             let gpg_s_key_output_dir = PathBuf::from(self.output_dir.clone())
                 .join("exported_secret_key")
                 .to_string_lossy()
@@ -604,7 +871,69 @@ impl GPG {
             }
             let time_stamp: String = Local::now().format("%Y%m%d-%H:%M:%S:%9f").to_string();
             let gpg_s_key_output = PathBuf::from(gpg_s_key_output_dir)
-                .join(format!("secret_key_{}.sec.asc", time_stamp))
+                .join(format!(
+                    "secret_key_{}.sec.{}",
+                    time_stamp,
+                    if armor { "asc" } else { "gpg" }
+                ))
+                .to_string_lossy()
+                .to_string();
+            set_output_without_confirmation(&mut args, &gpg_s_key_output);
+        }
+        if key_id.is_some() {
+            args.append(&mut key_id.unwrap());
+        }
+        return args;
+    }
+
+    pub fn export_secret_subkeys(
+        &self,
+        key_id: Option<Vec<String>>,
+        passphrase: Option<String>,
+        output: Option<String>,
+        armor: Option<bool>,
+    ) -> Result<CmdResult, GPGError> {
+        // key_id: list of keyid(s) to export the secret subkeys of, if not provided, all secret subkeys will be exported
+        // passphrase: for gpg version > 2.1, passphrase for passphrase proctected secret keys are required
+        // output: path that the exported key file will be saved to
+        // armor: whether to ascii armor the exported key, will use the armor set in GPG if not provided
+
+        // NOTE: the primary key will be exported as a stub ( without its secret part ),
+        //       this is meant for machine that should only hold the signing and encryption subkeys
+
+        if passphrase.is_some() {
+            if !is_passphrase_valid(&mut passphrase.as_ref().unwrap()) {
+                return Err(GPGError::new(
+                    GPGErrorType::PassphraseError("passphrase invalid".to_string()),
+                    None,
+                ));
+            }
+        }
+
+        let armor: bool = armor.unwrap_or(self.armor);
+        let mut args: Vec<String> = vec!["--export-secret-subkeys".to_string()];
+        if armor {
+            args.push("--armor".to_string());
+        }
+        if output.is_some() {
+            set_output_without_confirmation(&mut args, &output.unwrap());
+        } else {
+            // if output folder not specified, system will create a exported_secret_subkeys folder in the set output dir when initalizling the gpg
+            // all exported secret subkeys will be saved to there with filename as secret_subkeys_<timestamp>.sec.<asc or gpg>
+            let gpg_s_key_output_dir = PathBuf::from(self.output_dir.clone())
+                .join("exported_secret_subkeys")
+                .to_string_lossy()
+                .to_string();
+            if !check_is_dir(gpg_s_key_output_dir.clone()) {
+                std::fs::create_dir_all(gpg_s_key_output_dir.clone()).unwrap();
+            }
+            let time_stamp: String = Local::now().format("%Y%m%d-%H:%M:%S:%9f").to_string();
+            let gpg_s_key_output = PathBuf::from(gpg_s_key_output_dir)
+                .join(format!(
+                    "secret_subkeys_{}.sec.{}",
+                    time_stamp,
+                    if armor { "asc" } else { "gpg" }
+                ))
                 .to_string_lossy()
                 .to_string();
             set_output_without_confirmation(&mut args, &gpg_s_key_output);
@@ -614,7 +943,7 @@ impl GPG {
         }
 
         let result: Result<CmdResult, GPGError> =
-            self.export_key(args, passphrase, Operation::ExportSecretKey);
+            self.export_key(args, passphrase, None, Operation::ExportSecretSubkey);
         return result;
     }
 
@@ -622,9 +951,10 @@ impl GPG {
         &self,
         args: Vec<String>,
         passphrase: Option<String>,
+        provider: Option<PassphraseContext>,
         ops: Operation,
     ) -> Result<CmdResult, GPGError> {
-        let result: Result<CmdResult, GPGError> = handle_cmd_io(
+        let result: Result<CmdResult, GPGError> = handle_cmd_io_output(
             Some(args),
             passphrase,
             self.version,
@@ -637,6 +967,8 @@ impl GPG {
             false,
             false,
             ops,
+            provider,
+            None,
         );
         return result;
     }
@@ -730,79 +1062,37 @@ impl GPG {
 
     //*******************************************************
 
-    //                 FILE ENCRYPTION
+    //                   KEYSERVER
 
     //*******************************************************
-    // to encrypt file, use the EncryptionOption struct to create the encryption options
-    pub fn encrypt(&self, encrypt_option: EncryptOption) -> Result<CmdResult, GPGError> {
-        // encryption_option: struct that contains all the encryption options ( refer to the struct for more info )
-
-        //*****************************************************************************************
-        //    NOTE: If signing with a passphrase-protected key,
-        //          an error will occur.
-        //          Please sign separately after encryption if using
-        //          passphrase-protected key.
-        //
-        //    Reason:
-        //           We stream all input to GPG through STDIN.
-        //           When signing with a passphrase-protected key,
-        //           GPG expects the passphrase to be entered after the file content.
-        //           However, since we are streaming input through STDIN,
-        //           it's impossible to distinguish between file content and the passphrase input.
-        //           As a result, the passphrase is mistakenly treated as part of the file data,
-        //           causing the signing process to fail for passphrase protected key.
-        //******************************************************************************************
-
-        let p: Option<String> = encrypt_option.passphrase.clone();
-
-        if p.is_some() {
-            if !is_passphrase_valid(p.as_ref().unwrap()) {
-                return Err(GPGError::new(
-                    GPGErrorType::PassphraseError("passphrase invalid".to_string()),
-                    None,
-                ));
-            }
-        }
-
-        // generate encrypt operation arguments for gpg
-        let args: Result<Vec<String>, GPGError> = self.gen_encrypt_args(
-            encrypt_option.file_path.clone(),
-            encrypt_option.recipients,
-            encrypt_option.sign,
-            encrypt_option.sign_key,
-            encrypt_option.symmetric,
-            encrypt_option.symmetric_algo,
-            encrypt_option.always_trust,
-            encrypt_option.passphrase,
-            encrypt_option.output,
-            encrypt_option.extra_args,
-        );
+    pub fn search_keys(
+        &self,
+        query: String,
+        keyserver: Option<String>,
+    ) -> Result<Vec<SearchKeyResult>, GPGError> {
+        // query: a name, email or keyid to search for on the keyserver
+        // keyserver: url of the keyserver ( ex. hkp://keyserver.example.org ), if not provided, the keyserver set in gpg will be used
 
-        match args {
-            Ok(_) => {}
-            Err(e) => {
-                return Err(e);
-            }
-        }
+        let mut args: Vec<String> = self.gen_keyserver_args(keyserver);
+        args.append(&mut vec!["--search-keys".to_string(), query]);
 
         let result: Result<CmdResult, GPGError> = handle_cmd_io(
-            Some(args.unwrap()),
-            p,
+            Some(args),
+            None,
             self.version,
             self.homedir.clone(),
             self.options.clone(),
             self.env.clone(),
-            encrypt_option.file,
-            encrypt_option.file_path,
             None,
-            true,
-            true,
-            Operation::Encrypt,
+            None,
+            None,
+            false,
+            false,
+            Operation::SearchKey,
         );
-
         match result {
             Ok(result) => {
-                return Ok(result);
+                return Ok(decode_search_key_result(result));
             }
             Err(e) => {
                 return Err(e);
@@ -810,100 +1100,2227 @@ impl GPG {
         }
     }
 
-    fn gen_encrypt_args(
+    pub fn recv_keys(
         &self,
-        file_path: Option<String>,
-        recipients: Option<Vec<String>>,
-        sign: bool,
-        sign_key: Option<String>,
-        symmetric: bool,
-        symmetric_algo: Option<String>,
-        always_trust: bool,
-        passphrase: Option<String>,
-        output: Option<String>,
-        extra_args: Option<Vec<String>>,
-    ) -> Result<Vec<String>, GPGError> {
-        let mut args: Vec<String> = vec![];
-        let mut encrypt_type: String = "".to_string();
+        mut fingerprints: Vec<String>,
+        keyserver: Option<String>,
+    ) -> Result<CmdResult, GPGError> {
+        // fingerprints: list of fingerprint(s) to retrieve from the keyserver and import
+        // keyserver: url of the keyserver, if not provided, the keyserver set in gpg will be used
 
-        if symmetric {
-            args.append(&mut vec![
-                "--symmetric".to_string(),
-            ]);
-            if self.version>=2.1{
-                args.push("--no-symkey-cache".to_string());
-            }
-            if passphrase.is_none() {
-                return Err(GPGError::new(
-                    GPGErrorType::PassphraseError(
-                        "passphrase is required if encrypting symmetrically ".to_string(),
-                    ),
-                    None,
-                ));
-            }
-            if symmetric_algo.is_some() {
-                args.append(&mut vec![
-                    "--personal-cipher-preferences".to_string(),
-                    symmetric_algo.unwrap(),
-                ]);
-            }
-            encrypt_type.push_str("pass_");
-        }
-        if recipients.is_some() {
-            args.push("--encrypt".to_string());
-            for recipient in recipients.unwrap() {
-                args.append(&mut vec!["--recipient".to_string(), recipient]);
-            }
-            encrypt_type.push_str("keys_");
-        }
+        let mut args: Vec<String> = self.gen_keyserver_args(keyserver);
+        args.push("--recv-keys".to_string());
+        args.append(&mut fingerprints);
+        return self.keyserver_operation(args, Operation::ReceiveKey);
+    }
 
-        if args.len() == 0 {
-            return Err(GPGError::new(
-                GPGErrorType::InvalidArgumentError(
-                    "Please choose symmetric or keys to encrypt your file".to_string(),
+    pub fn send_keys(
+        &self,
+        mut fingerprints: Vec<String>,
+        keyserver: Option<String>,
+    ) -> Result<CmdResult, GPGError> {
+        // fingerprints: list of fingerprint(s) of the local public key to upload to the keyserver
+        // keyserver: url of the keyserver, if not provided, the keyserver set in gpg will be used
+
+        let mut args: Vec<String> = self.gen_keyserver_args(keyserver);
+        args.push("--send-keys".to_string());
+        args.append(&mut fingerprints);
+        return self.keyserver_operation(args, Operation::SendKey);
+    }
+
+    pub fn refresh_keys(&self, keyserver: Option<String>) -> Result<CmdResult, GPGError> {
+        // keyserver: url of the keyserver, if not provided, the keyserver set in gpg will be used
+
+        // NOTE: all the public keys in the local keyring will be refreshed
+
+        let mut args: Vec<String> = self.gen_keyserver_args(keyserver);
+        args.push("--refresh-keys".to_string());
+        return self.keyserver_operation(args, Operation::RefreshKey);
+    }
+
+    fn gen_keyserver_args(&self, keyserver: Option<String>) -> Vec<String> {
+        // the keyserver operation was carried out by dirmngr, which gpg will start on its own
+        let mut args: Vec<String> = vec![];
+        if keyserver.is_some() {
+            args.append(&mut vec!["--keyserver".to_string(), keyserver.unwrap()]);
+        }
+        return args;
+    }
+
+    fn keyserver_operation(
+        &self,
+        args: Vec<String>,
+        ops: Operation,
+    ) -> Result<CmdResult, GPGError> {
+        let result: Result<CmdResult, GPGError> = handle_cmd_io(
+            Some(args),
+            None,
+            self.version,
+            self.homedir.clone(),
+            self.options.clone(),
+            self.env.clone(),
+            None,
+            None,
+            None,
+            false,
+            false,
+            ops,
+        );
+        return result;
+    }
+
+    //*******************************************************
+
+    //                   LOCATE KEY
+
+    //*******************************************************
+    pub fn locate_keys(
+        &self,
+        emails: Vec<String>,
+        mechanisms: Vec<LocateMechanism>,
+    ) -> Result<Vec<LocateKeyResult>, GPGError> {
+        // emails: list of email address(es) to locate the key for
+        // mechanisms: list of mechanism(s) to try in sequence ( local, wkd, keyserver, dane ),
+        //             clear and nodefault behave as the --auto-key-locate option of gpg
+
+        //*****************************************************************************************
+        //    NOTE: each mechanism was tried on its own ( in the provided sequence ) for every email
+        //          so that the mechanism that found the key can be reported.
+        //          Unless NoDefault was provided, local will be tried first like gpg does.
+        //          The mechanisms configured in gpg.conf will always be cleared.
+        //******************************************************************************************
+
+        let mut lookup: Vec<LocateMechanism> = vec![];
+        if !mechanisms.contains(&LocateMechanism::NoDefault)
+            && !mechanisms.contains(&LocateMechanism::Local)
+        {
+            lookup.push(LocateMechanism::Local);
+        }
+        for mechanism in mechanisms {
+            if mechanism != LocateMechanism::Clear && mechanism != LocateMechanism::NoDefault {
+                lookup.push(mechanism);
+            }
+        }
+        if lookup.len() == 0 {
+            return Err(GPGError::new(
+                GPGErrorType::InvalidArgumentError(
+                    "Please provide at least 1 mechanism to locate the keys".to_string(),
+                ),
+                None,
+            ));
+        }
+
+        let mut locate_results: Vec<LocateKeyResult> = vec![];
+        for email in emails {
+            let mut locate_result: LocateKeyResult = LocateKeyResult {
+                email: email.clone(),
+                key: None,
+                mechanism: None,
+            };
+            for mechanism in lookup.iter() {
+                let key: Result<Option<ListKeyResult>, GPGError> =
+                    self.locate_key_with(email.clone(), mechanism.clone());
+                match key {
+                    Ok(Some(key)) => {
+                        locate_result.key = Some(key);
+                        locate_result.mechanism = Some(mechanism.clone());
+                        break;
+                    }
+                    Ok(None) => {}
+                    Err(e) => {
+                        return Err(e);
+                    }
+                }
+            }
+            locate_results.push(locate_result);
+        }
+        return Ok(locate_results);
+    }
+
+    fn locate_key_with(
+        &self,
+        email: String,
+        mechanism: LocateMechanism,
+    ) -> Result<Option<ListKeyResult>, GPGError> {
+        // local lookup use --locate-keys, the rest use --locate-external-keys to skip the local keyring
+        let locate: String = if mechanism == LocateMechanism::Local {
+            "--locate-keys".to_string()
+        } else {
+            "--locate-external-keys".to_string()
+        };
+        let mut args: Vec<String> = vec![
+            "--auto-key-locate".to_string(),
+            format!("clear,nodefault,{}", mechanism.value()),
+            "--fingerprint".to_string(),
+            "--fingerprint".to_string(),
+        ];
+        if self.version >= 2.1 {
+            args.push("--with-keygrip".to_string());
+        }
+        args.append(&mut vec![locate, email]);
+
+        let result: Result<CmdResult, GPGError> = handle_cmd_io(
+            Some(args),
+            None,
+            self.version,
+            self.homedir.clone(),
+            self.options.clone(),
+            self.env.clone(),
+            None,
+            None,
+            None,
+            false,
+            false,
+            Operation::LocateKey,
+        );
+        match result {
+            Ok(mut result) => {
+                // gpg does not emit a failure status when the lookup failed, check the return code
+                if result.return_code.unwrap_or(0) != 0 {
+                    if is_locate_miss(&result.get_raw_data().unwrap_or_default()) {
+                        return Ok(None);
+                    }
+                    return Err(GPGError::new(
+                        GPGErrorType::GPGProcessError(result.get_error_message()),
+                        Some(result),
+                    ));
+                }
+                return Ok(decode_list_key_result(result).into_iter().next());
+            }
+            Err(e) => {
+                if is_key_not_found(&e) {
+                    return Ok(None);
+                }
+                return Err(e);
+            }
+        }
+    }
+
+    //*******************************************************
+
+    //               IDENTITY BACKUP / RESTORE
+
+    //*******************************************************
+    pub fn backup_identity(
+        &self,
+        fingerprint: String,
+        passphrase: Option<String>,
+        dest: Option<String>,
+    ) -> Result<String, GPGError> {
+        // fingerprint: fingerprint of the primary key of the identity to backup
+        // passphrase: passphrase for passphrase protected secret key
+        // dest: path that the backup archive will be saved to
+
+        //*****************************************************************************************
+        //    NOTE: the archive ( tar ) will hold the following entries
+        //          public_key.gpg  -> the public key
+        //          secret_key.gpg  -> the secret key ( exported with the backup option )
+        //          ownertrust.txt  -> the ownertrust entry of the key
+        //          revocation.rev  -> a freshly generated revocation certificate
+        //          manifest.txt    -> fingerprint, creation time and gpg version of the backup
+        //          the secret key stay protected by its passphrase in the archive
+        //******************************************************************************************
+
+        if passphrase.is_some() {
+            if !is_passphrase_valid(passphrase.as_ref().unwrap()) {
+                return Err(GPGError::new(
+                    GPGErrorType::PassphraseError("passphrase invalid".to_string()),
+                    None,
+                ));
+            }
+        }
+
+        let key_list: Result<Vec<ListKeyResult>, GPGError> =
+            self.list_keys(true, Some(vec![fingerprint.clone()]), false);
+        match key_list {
+            Ok(key_list) => {
+                if !key_list.iter().any(|x| x.fingerprint == fingerprint) {
+                    return Err(GPGError::new(
+                        GPGErrorType::InvalidArgumentError(
+                            "fingerprint provided is not a primary secret key".to_string(),
+                        ),
+                        None,
+                    ));
+                }
+            }
+            Err(e) => {
+                return Err(e);
+            }
+        }
+
+        let time_stamp: String = Local::now().format("%Y%m%d-%H:%M:%S:%9f").to_string();
+        let archive: String = match dest {
+            Some(dest) => dest,
+            None => {
+                // if output not specified, system will create a identity_backup folder in the set output dir when initalizling the gpg
+                // all backup will be saved to there with filename as identity_<fingerprint>_<timestamp>.tar
+                let backup_dir = PathBuf::from(self.output_dir.clone())
+                    .join("identity_backup")
+                    .to_string_lossy()
+                    .to_string();
+                if !check_is_dir(backup_dir.clone()) {
+                    std::fs::create_dir_all(backup_dir.clone()).unwrap();
+                }
+                PathBuf::from(backup_dir)
+                    .join(format!("identity_{}_{}.tar", fingerprint, time_stamp))
+                    .to_string_lossy()
+                    .to_string()
+            }
+        };
+
+        // the pieces were staged inside the homedir ( which only the owner have access to )
+        // and will be removed once the archive was written
+        let staging_dir: PathBuf = PathBuf::from(self.homedir.clone())
+            .join(format!("identity-backup-{}", time_stamp.replace(":", "")));
+        if let Err(e) = std::fs::create_dir_all(&staging_dir) {
+            return Err(GPGError::new(
+                GPGErrorType::OutputDirError(e.to_string()),
+                None,
+            ));
+        }
+        let result: Result<Vec<(String, Vec<u8>)>, GPGError> = self.stage_identity_backup(
+            fingerprint.clone(),
+            passphrase,
+            staging_dir.clone(),
+            time_stamp,
+        );
+        let _ = std::fs::remove_dir_all(&staging_dir);
+
+        match result {
+            Ok(entries) => {
+                if let Err(e) = write_tar(&archive, &entries) {
+                    return Err(e);
+                }
+                return Ok(archive);
+            }
+            Err(e) => {
+                return Err(e);
+            }
+        }
+    }
+
+    fn stage_identity_backup(
+        &self,
+        fingerprint: String,
+        passphrase: Option<String>,
+        staging_dir: PathBuf,
+        time_stamp: String,
+    ) -> Result<Vec<(String, Vec<u8>)>, GPGError> {
+        let public_key: String = staging_dir.join("public_key.gpg").to_string_lossy().to_string();
+        let secret_key: String = staging_dir.join("secret_key.gpg").to_string_lossy().to_string();
+        let revocation: String = staging_dir.join("revocation.rev").to_string_lossy().to_string();
+
+        if let Err(e) = self.export_public_key(
+            Some(vec![fingerprint.clone()]),
+            Some(public_key.clone()),
+            Some(false),
+        ) {
+            return Err(e);
+        }
+
+        let mut args: Vec<String> = vec!["--export-secret-keys".to_string()];
+        if self.version >= 2.2 {
+            args.append(&mut vec!["--export-options".to_string(), "backup".to_string()]);
+        }
+        set_output_without_confirmation(&mut args, &secret_key);
+        args.push(fingerprint.clone());
+        if let Err(e) = self.export_key(args, passphrase.clone(), None, Operation::ExportSecretKey) {
+            return Err(e);
+        }
+
+        let ownertrust: Result<String, GPGError> = self.export_ownertrust(fingerprint.clone());
+        if let Err(e) = ownertrust {
+            return Err(e);
+        }
+
+        if let Err(e) = self.gen_revoke(fingerprint.clone(), passphrase, revocation.clone()) {
+            return Err(e);
+        }
+
+        let manifest: String = format!(
+            "fingerprint: {}\ncreated: {}\ngpg_version: {}\nentries: public_key.gpg,secret_key.gpg,ownertrust.txt,revocation.rev\n",
+            fingerprint, time_stamp, self.full_version
+        );
+        let mut entries: Vec<(String, Vec<u8>)> =
+            vec![("manifest.txt".to_string(), manifest.as_bytes().to_vec())];
+        for (name, path) in [
+            ("public_key.gpg", public_key),
+            ("secret_key.gpg", secret_key),
+            ("revocation.rev", revocation),
+        ] {
+            match std::fs::read(&path) {
+                Ok(content) => {
+                    if content.len() == 0 {
+                        return Err(GPGError::new(
+                            GPGErrorType::GPGProcessError(format!("nothing exported for {}", name)),
+                            None,
+                        ));
+                    }
+                    entries.push((name.to_string(), content));
+                }
+                Err(e) => {
+                    return Err(GPGError::new(
+                        GPGErrorType::ReadFailError(e.to_string()),
+                        None,
+                    ));
+                }
+            }
+        }
+        entries.push((
+            "ownertrust.txt".to_string(),
+            ownertrust.unwrap().as_bytes().to_vec(),
+        ));
+        return Ok(entries);
+    }
+
+    fn export_ownertrust(&self, fingerprint: String) -> Result<String, GPGError> {
+        // retrieve the ownertrust line(s) of the fingerprint ex) <FINGERPRINT>:6:
+        let result: Result<CmdResult, GPGError> = handle_cmd_io(
+            Some(vec!["--export-ownertrust".to_string()]),
+            None,
+            self.version,
+            self.homedir.clone(),
+            self.options.clone(),
+            self.env.clone(),
+            None,
+            None,
+            None,
+            false,
+            false,
+            Operation::ExportOwnerTrust,
+        );
+        match result {
+            Ok(result) => {
+                let mut ownertrust: String = String::new();
+                for line in result.get_raw_data().unwrap_or_default().split("\n") {
+                    if line.trim().starts_with(&format!("{}:", fingerprint)) {
+                        ownertrust.push_str(&format!("{}\n", line.trim()));
+                    }
+                }
+                return Ok(ownertrust);
+            }
+            Err(e) => {
+                return Err(e);
+            }
+        }
+    }
+
+    fn gen_revoke(
+        &self,
+        fingerprint: String,
+        passphrase: Option<String>,
+        output: String,
+    ) -> Result<CmdResult, GPGError> {
+        // gpg refuse to generate revocation certificate in batch mode, the answers were provided through --command-fd
+        // [create the certificate] [reason code 0 = No reason specified] [empty description] [confirm]
+        let mut args: Vec<String> = vec![
+            "--no-batch".to_string(),
+            "--command-fd".to_string(),
+            "0".to_string(),
+        ];
+        set_output_without_confirmation(&mut args, &output);
+        args.append(&mut vec!["--gen-revoke".to_string(), fingerprint]);
+
+        let result: Result<CmdResult, GPGError> = handle_cmd_io(
+            Some(args),
+            passphrase,
+            self.version,
+            self.homedir.clone(),
+            self.options.clone(),
+            self.env.clone(),
+            None,
+            None,
+            Some("y\n0\n\ny\n".as_bytes().to_vec()),
+            true,
+            false,
+            Operation::GenerateRevocation,
+        );
+        return result;
+    }
+
+    pub fn restore_identity(&self, archive: String) -> Result<ListKeyResult, GPGError> {
+        // archive: path to the backup archive created by backup_identity
+
+        // NOTE: the revocation certificate will not be imported ( that will revoke the key ),
+        //       it will be stored in <homedir>/openpgp-revocs.d/<FINGERPRINT>.rev like gpg does
+
+        let entries: Result<Vec<(String, Vec<u8>)>, GPGError> = read_tar(&archive);
+        if let Err(e) = entries {
+            return Err(e);
+        }
+        let entries: HashMap<String, Vec<u8>> = entries.unwrap().into_iter().collect();
+        for name in [
+            "manifest.txt",
+            "public_key.gpg",
+            "secret_key.gpg",
+            "ownertrust.txt",
+            "revocation.rev",
+        ] {
+            if !entries.contains_key(name) {
+                return Err(GPGError::new(
+                    GPGErrorType::InvalidArgumentError(format!(
+                        "{} missing from the backup archive",
+                        name
+                    )),
+                    None,
+                ));
+            }
+        }
+        let manifest: String = String::from_utf8_lossy(&entries["manifest.txt"]).to_string();
+        let fingerprint: String = manifest
+            .lines()
+            .find(|l| l.starts_with("fingerprint:"))
+            .map(|l| l["fingerprint:".len()..].trim().to_string())
+            .unwrap_or_default();
+        if fingerprint.is_empty() {
+            return Err(GPGError::new(
+                GPGErrorType::InvalidArgumentError(
+                    "fingerprint missing from the backup manifest".to_string(),
+                ),
+                None,
+            ));
+        }
+        // the fingerprint is used as the file name of the revocation certificate, only accept a v4 fingerprint
+        if fingerprint.len() != 40 || !fingerprint.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(GPGError::new(
+                GPGErrorType::InvalidArgumentError(format!(
+                    "invalid fingerprint in the backup manifest: {}",
+                    fingerprint
+                )),
+                None,
+            ));
+        }
+        let fingerprint: String = fingerprint.to_uppercase();
+
+        if let Err(e) =
+            self.import_key_file_buffer(entries["public_key.gpg"].clone(), false, None)
+        {
+            return Err(e);
+        }
+        let mut import_args: Option<Vec<String>> = None;
+        if self.version >= 2.2 {
+            import_args = Some(vec!["--import-options".to_string(), "restore".to_string()]);
+        }
+        let result: Result<CmdResult, GPGError> =
+            self.import_key_file_buffer(entries["secret_key.gpg"].clone(), false, import_args);
+        match result {
+            Ok(result) => {
+                // the secret key imported must be the key of the manifest
+                let imported: bool = decode_import_ok_result(&result)
+                    .iter()
+                    .any(|(fpr, reason)| fpr.to_uppercase() == fingerprint && reason & 16 != 0);
+                if !imported {
+                    return Err(GPGError::new(
+                        GPGErrorType::InvalidArgumentError(format!(
+                            "secret key of the backup archive do not match the fingerprint {} of the manifest",
+                            fingerprint
+                        )),
+                        Some(result),
+                    ));
+                }
+            }
+            Err(e) => {
+                return Err(e);
+            }
+        }
+        if entries["ownertrust.txt"].len() > 0 {
+            let result: Result<CmdResult, GPGError> = handle_cmd_io(
+                Some(vec!["--import-ownertrust".to_string()]),
+                None,
+                self.version,
+                self.homedir.clone(),
+                self.options.clone(),
+                self.env.clone(),
+                None,
+                None,
+                Some(entries["ownertrust.txt"].clone()),
+                true,
+                false,
+                Operation::TrustKey,
+            );
+            if let Err(e) = result {
+                return Err(e);
+            }
+        }
+
+        let revocs_dir: PathBuf = PathBuf::from(self.homedir.clone()).join("openpgp-revocs.d");
+        let _ = std::fs::create_dir_all(&revocs_dir);
+        if let Err(e) = std::fs::write(
+            revocs_dir.join(format!("{}.rev", fingerprint)),
+            &entries["revocation.rev"],
+        ) {
+            return Err(GPGError::new(
+                GPGErrorType::WriteFailError(e.to_string()),
+                None,
+            ));
+        }
+
+        // confirm the secret key was restored
+        let key_list: Result<Vec<ListKeyResult>, GPGError> =
+            self.list_keys(true, Some(vec![fingerprint.clone()]), false);
+        match key_list {
+            Ok(key_list) => {
+                let key: Option<ListKeyResult> =
+                    key_list.into_iter().find(|x| x.fingerprint == fingerprint);
+                if key.is_none() {
+                    return Err(GPGError::new(
+                        GPGErrorType::GPGProcessError(
+                            "secret key not found after restoring the backup".to_string(),
+                        ),
+                        None,
+                    ));
+                }
+                return Ok(key.unwrap());
+            }
+            Err(e) => {
+                return Err(e);
+            }
+        }
+    }
+
+    //*******************************************************
+
+    //                   COPY KEYS
+
+    //*******************************************************
+    pub fn copy_keys_to(
+        &self,
+        other: &GPG,
+        key_id: Option<Vec<String>>,
+        include_secret: bool,
+        include_ownertrust: bool,
+        passphrase: Option<String>,
+    ) -> Result<Vec<CopyKeyResult>, GPGError> {
+        // other: the GPG object ( with another homedir ) that the keys will be copied to
+        // key_id: list of keyid(s) to copy, if not provided, all public keys will be copied,
+        //         a keyid that matched no key was returned with found as false
+        // include_secret: if true, secret keys ( if any ) will be copied as well
+        // include_ownertrust: if true, ownertrust of the keys will be copied as well
+        // passphrase: passphrase for passphrase protected secret keys
+
+        //*****************************************************************************************
+        //    NOTE: the output of the export was piped directly into the import of the other
+        //          homedir, nothing will be written to the output_dir
+        //******************************************************************************************
+
+        if passphrase.is_some() {
+            if !is_passphrase_valid(passphrase.as_ref().unwrap()) {
+                return Err(GPGError::new(
+                    GPGErrorType::PassphraseError("passphrase invalid".to_string()),
+                    None,
+                ));
+            }
+        }
+
+        let mut fingerprints: Vec<String> = vec![];
+        let mut not_found: Vec<String> = vec![];
+        match key_id {
+            Some(key_id) => {
+                // each selector was looked up on its own, so the one(s) that matched no key can be reported
+                for selector in key_id {
+                    let key_list: Result<Vec<ListKeyResult>, GPGError> =
+                        self.list_keys(false, Some(vec![selector.clone()]), false);
+                    let key_list: Vec<ListKeyResult> = match key_list {
+                        Ok(key_list) => key_list,
+                        Err(e) => {
+                            if !is_key_not_found(&e) {
+                                return Err(e);
+                            }
+                            vec![]
+                        }
+                    };
+                    if key_list.len() == 0 {
+                        not_found.push(selector);
+                    }
+                    for key in key_list {
+                        if !fingerprints.contains(&key.fingerprint) {
+                            fingerprints.push(key.fingerprint);
+                        }
+                    }
+                }
+            }
+            None => {
+                let key_list: Result<Vec<ListKeyResult>, GPGError> = self.list_keys(false, None, false);
+                match key_list {
+                    Ok(key_list) => {
+                        fingerprints = key_list.into_iter().map(|x| x.fingerprint).collect();
+                    }
+                    Err(e) => {
+                        return Err(e);
+                    }
+                }
+                if fingerprints.len() == 0 {
+                    return Err(GPGError::new(
+                        GPGErrorType::InvalidArgumentError("no key found to copy".to_string()),
+                        None,
+                    ));
+                }
+            }
+        }
+        let mut copy_results: Vec<CopyKeyResult> = fingerprints
+            .iter()
+            .map(|fpr| CopyKeyResult {
+                fingerprint: fpr.clone(),
+                found: true,
+                public: false,
+                secret: false,
+                ownertrust: None,
+                unchanged: true,
+            })
+            .collect();
+        let not_found_results: Vec<CopyKeyResult> = not_found
+            .into_iter()
+            .map(|selector| CopyKeyResult {
+                fingerprint: selector,
+                found: false,
+                public: false,
+                secret: false,
+                ownertrust: None,
+                unchanged: false,
+            })
+            .collect();
+        if fingerprints.len() == 0 {
+            return Ok(not_found_results);
+        }
+
+        let mut args: Vec<String> = vec!["--export".to_string()];
+        args.append(&mut fingerprints.clone());
+        let result: Result<(CmdResult, CmdResult), GPGError> =
+            self.pipe_keys_to(other, args, None, Operation::ExportPublicKey);
+        match result {
+            Ok((_, import_result)) => {
+                for (fpr, reason) in decode_import_ok_result(&import_result) {
+                    if let Some(r) = copy_results.iter_mut().find(|x| x.fingerprint == fpr) {
+                        if reason & 15 != 0 {
+                            r.public = true;
+                            r.unchanged = false;
+                        }
+                    }
+                }
+            }
+            Err(e) => {
+                return Err(e);
+            }
+        }
+
+        if include_secret {
+            // only the keys with secret key in this homedir will be exported
+            let secret_list: Result<Vec<ListKeyResult>, GPGError> =
+                self.list_keys(true, Some(fingerprints.clone()), false);
+            let secret_fingerprints: Vec<String> = match secret_list {
+                Ok(secret_list) => secret_list.into_iter().map(|x| x.fingerprint).collect(),
+                Err(e) => {
+                    return Err(e);
+                }
+            };
+            if secret_fingerprints.len() > 0 {
+                let mut args: Vec<String> = vec!["--export-secret-keys".to_string()];
+                args.append(&mut secret_fingerprints.clone());
+                let result: Result<(CmdResult, CmdResult), GPGError> =
+                    self.pipe_keys_to(other, args, passphrase, Operation::ExportSecretKey);
+                match result {
+                    Ok((_, import_result)) => {
+                        for (fpr, reason) in decode_import_ok_result(&import_result) {
+                            if let Some(r) = copy_results.iter_mut().find(|x| x.fingerprint == fpr) {
+                                // 17 -> new key with private key, 16 -> private key already exist
+                                if reason & 16 != 0 && reason & 1 != 0 {
+                                    r.secret = true;
+                                    r.unchanged = false;
+                                }
+                            }
+                        }
+                    }
+                    Err(e) => {
+                        return Err(e);
+                    }
+                }
+            }
+        }
+
+        if include_ownertrust {
+            let mut ownertrust: String = String::new();
+            for r in copy_results.iter_mut() {
+                let trust: Result<String, GPGError> = self.export_ownertrust(r.fingerprint.clone());
+                match trust {
+                    Ok(trust) => {
+                        // <FINGERPRINT>:<VALUE>:
+                        if let Some(line) = trust.lines().next() {
+                            r.ownertrust = line.split(":").nth(1).map(|x| x.to_string());
+                            ownertrust.push_str(&trust);
+                        }
+                    }
+                    Err(e) => {
+                        return Err(e);
+                    }
+                }
+            }
+            if !ownertrust.is_empty() {
+                let result: Result<CmdResult, GPGError> = handle_cmd_io(
+                    Some(vec!["--import-ownertrust".to_string()]),
+                    None,
+                    other.version,
+                    other.homedir.clone(),
+                    other.options.clone(),
+                    other.env.clone(),
+                    None,
+                    None,
+                    Some(ownertrust.as_bytes().to_vec()),
+                    true,
+                    false,
+                    Operation::TrustKey,
+                );
+                if let Err(e) = result {
+                    return Err(e);
+                }
+            }
+        }
+        copy_results.extend(not_found_results);
+        return Ok(copy_results);
+    }
+
+    fn pipe_keys_to(
+        &self,
+        other: &GPG,
+        export_args: Vec<String>,
+        passphrase: Option<String>,
+        ops: Operation,
+    ) -> Result<(CmdResult, CmdResult), GPGError> {
+        let source: PipeCmd = PipeCmd {
+            args: Some(export_args),
+            passphrase: passphrase,
+            provider: None,
+            version: self.version,
+            homedir: self.homedir.clone(),
+            options: self.options.clone(),
+            env: self.env.clone(),
+            ops: ops,
+        };
+        // the import do not need any passphrase
+        let target: PipeCmd = PipeCmd {
+            args: Some(vec!["--import".to_string()]),
+            passphrase: None,
+            provider: None,
+            version: other.version,
+            homedir: other.homedir.clone(),
+            options: other.options.clone(),
+            env: other.env.clone(),
+            ops: Operation::ImportKey,
+        };
+        return handle_cmd_pipe(source, target);
+    }
+
+    //*******************************************************
+
+    //                 FILE ENCRYPTION
+
+    //*******************************************************
+    // to encrypt file, use the EncryptionOption struct to create the encryption options
+    pub fn encrypt(&self, encrypt_option: EncryptOption) -> Result<CmdResult, GPGError> {
+        // encryption_option: struct that contains all the encryption options ( refer to the struct for more info )
+
+        let p: Result<Option<String>, GPGError> = get_encrypt_passphrase(&encrypt_option);
+        if let Err(e) = p {
+            return Err(e);
+        }
+        let p: Option<String> = p.unwrap();
+
+        // the symmetric passphrase ( if provided ) will still be used when the passphrase provider was set
+        let provider: Option<PassphraseContext> = self.gen_passphrase_context(
+            encrypt_option.passphrase_provider.clone(),
+            encrypt_option.passphrase.clone(),
+        );
+
+        // generate encrypt operation arguments for gpg
+        let args: Result<Vec<String>, GPGError> = self.gen_encrypt_args(
+            &encrypt_option,
+            encrypt_option.file_path.clone(),
+            encrypt_option.passphrase.is_some() || provider.is_some(),
+            encrypt_option.output.clone(),
+        );
+
+        match args {
+            Ok(_) => {}
+            Err(e) => {
+                return Err(e);
+            }
+        }
+
+        let result: Result<CmdResult, GPGError> = handle_cmd_io_output(
+            Some(args.unwrap()),
+            p,
+            self.version,
+            self.homedir.clone(),
+            self.options.clone(),
+            self.env.clone(),
+            encrypt_option.file,
+            encrypt_option.file_path,
+            None,
+            true,
+            true,
+            Operation::Encrypt,
+            provider,
+            None,
+        );
+
+        match result {
+            Ok(result) => {
+                return Ok(result);
+            }
+            Err(e) => {
+                return Err(e);
+            }
+        }
+    }
+
+    fn gen_encrypt_args(
+        &self,
+        encrypt_option: &EncryptOption,
+        file_path: Option<String>,
+        has_passphrase: bool,
+        output: Option<String>,
+    ) -> Result<Vec<String>, GPGError> {
+        // encrypt_option: the encryption options, file, file_path and output of it will not be used
+        // file_path: path of the file to encrypt, only used for the extension of the default output
+        // has_passphrase: whether a passphrase or passphrase provider was given for symmetric encryption
+        // output: path to write the encrypted output, "-" for stdout, the default output dir if not provided
+
+        let mut algo_args: Vec<String> = match self.gen_algo_args(encrypt_option) {
+            Ok(algo_args) => algo_args,
+            Err(e) => {
+                return Err(e);
+            }
+        };
+        let armor: bool = encrypt_option.armor.unwrap_or(self.armor);
+        let symmetric_algo: Option<String> = encrypt_option.symmetric_algo.clone();
+        let recipient_files: Option<Vec<String>> = encrypt_option.recipient_files.clone();
+        let encrypt_to: Option<Vec<String>> = encrypt_option.encrypt_to.clone();
+        let sign_key: Option<String> = encrypt_option.sign_key.clone();
+        let extra_args: Option<Vec<String>> = encrypt_option.extra_args.clone();
+
+        let mut args: Vec<String> = vec![];
+        let mut encrypt_type: String = "".to_string();
+
+        // recipient group(s) will be expanded into the fingerprint(s) of their member(s)
+        let recipients: Option<Vec<String>> = match self.expand_recipients(encrypt_option.recipients.clone()) {
+            Ok(recipients) => recipients,
+            Err(e) => {
+                return Err(e);
+            }
+        };
+        let hidden_recipients: Option<Vec<String>> = match self.expand_recipients(encrypt_option.hidden_recipients.clone()) {
+            Ok(recipients) => recipients,
+            Err(e) => {
+                return Err(e);
+            }
+        };
+
+        if encrypt_option.symmetric {
+            args.append(&mut vec![
+                "--symmetric".to_string(),
+            ]);
+            if self.version>=2.1{
+                args.push("--no-symkey-cache".to_string());
+            }
+            if !has_passphrase {
+                return Err(GPGError::new(
+                    GPGErrorType::PassphraseError(
+                        "passphrase is required if encrypting symmetrically ".to_string(),
+                    ),
+                    None,
+                ));
+            }
+            if symmetric_algo.is_some() {
+                args.append(&mut vec![
+                    "--personal-cipher-preferences".to_string(),
+                    symmetric_algo.unwrap(),
+                ]);
+            }
+            encrypt_type.push_str("pass_");
+        }
+        if recipients.is_some() || hidden_recipients.is_some() || recipient_files.is_some() {
+            args.push("--encrypt".to_string());
+            if recipients.is_some() {
+                for recipient in recipients.unwrap() {
+                    args.append(&mut vec!["--recipient".to_string(), recipient]);
+                }
+            }
+            if hidden_recipients.is_some() {
+                for recipient in hidden_recipients.unwrap() {
+                    args.append(&mut vec!["--hidden-recipient".to_string(), recipient]);
+                }
+            }
+            if recipient_files.is_some() {
+                for recipient_file in recipient_files.unwrap() {
+                    if !PathBuf::from(&recipient_file).is_file() {
+                        return Err(GPGError::new(
+                            GPGErrorType::FileNotFoundError(format!(
+                                "recipient file {} do not exist",
+                                recipient_file
+                            )),
+                            None,
+                        ));
+                    }
+                    args.append(&mut vec!["--recipient-file".to_string(), recipient_file]);
+                }
+            }
+            if encrypt_option.throw_keyids {
+                args.push("--throw-keyids".to_string());
+            }
+            encrypt_type.push_str("keys_");
+        } else if encrypt_to.is_some() {
+            // gpg will not encrypt to the encrypt-to key(s) alone
+            return Err(GPGError::new(
+                GPGErrorType::InvalidArgumentError(
+                    "encrypt_to can only be used together with recipients".to_string(),
+                ),
+                None,
+            ));
+        }
+        if encrypt_option.no_encrypt_to {
+            // --no-encrypt-to would also drop the encrypt_to key(s) passed here
+            if encrypt_to.is_some() {
+                return Err(GPGError::new(
+                    GPGErrorType::InvalidArgumentError(
+                        "encrypt_to can not be used together with no_encrypt_to".to_string(),
+                    ),
+                    None,
+                ));
+            }
+            args.push("--no-encrypt-to".to_string());
+        } else if encrypt_to.is_some() {
+            for recipient in encrypt_to.unwrap() {
+                args.append(&mut vec!["--encrypt-to".to_string(), recipient]);
+            }
+        }
+
+        if args.len() == 0 {
+            return Err(GPGError::new(
+                GPGErrorType::InvalidArgumentError(
+                    "Please choose symmetric or keys to encrypt your file".to_string(),
+                ),
+                None,
+            ));
+        }
+
+        if armor {
+            args.push("--armor".to_string());
+        }
+        if encrypt_option.textmode {
+            args.push("--textmode".to_string());
+        }
+        if output.is_some() {
+            set_output_without_confirmation(&mut args, &output.unwrap());
+        } else {
+            // if the system is handling the output
+            // the name wil be [<encryption_type>_encrypted_file_<YYYYMMDD_HH/MM/SS/NANO-SECOND>.<extension>]
+            // the encryption type will either [key] for public key encryption or [pass] for symmetric encryption or both
+            // the extension will be the same if file_path is provided,
+            // if a rust File type is provided, the file extension will be default to .asc if armored or .gpg if not
+
+            let ext: String = if file_path.is_some() {
+                get_file_extension(file_path)
+            } else if armor {
+                "asc".to_string()
+            } else {
+                "gpg".to_string()
+            };
+            let time_stamp: String = Local::now().format("%Y%m%d-%H:%M:%S:%9f").to_string();
+            let out: String = PathBuf::from(self.output_dir.clone())
+                .join(format!(
+                    "{}_encrypted_file_{}.{}",
+                    encrypt_type, time_stamp, ext
+                ))
+                .to_string_lossy()
+                .to_string();
+            args.append(&mut vec!["--output".to_string(), out]);
+        }
+
+        if encrypt_option.sign {
+            if sign_key.is_some() {
+                args.append(&mut vec![
+                    "--sign".to_string(),
+                    "--default-key".to_string(),
+                    sign_key.unwrap(),
+                ]);
+            } else {
+                args.push("--sign".to_string());
+            }
+            match gen_sig_metadata_args(
+                &encrypt_option.notations,
+                &encrypt_option.policy_url,
+                &encrypt_option.keyserver_url,
+            ) {
+                Ok(mut sig_args) => {
+                    args.append(&mut sig_args);
+                }
+                Err(e) => {
+                    return Err(e);
+                }
+            }
+        }
+
+        if encrypt_option.always_trust {
+            args.append(&mut vec!["--trust-model".to_string(), "always".to_string()]);
+        }
+
+        args.append(&mut algo_args);
+
+        if extra_args.is_some() {
+            args.append(&mut extra_args.unwrap());
+        }
+
+        return Ok(args);
+    }
+
+    // the algorithm(s) chosen were validated against the algorithms gpg advertise in --list-config
+    fn gen_algo_args(&self, encrypt_option: &EncryptOption) -> Result<Vec<String>, GPGError> {
+        let mut args: Vec<String> = vec![];
+        if encrypt_option.compress_level.is_some() {
+            let level: u8 = encrypt_option.compress_level.unwrap();
+            if level > 9 {
+                return Err(GPGError::new(
+                    GPGErrorType::InvalidArgumentError(format!(
+                        "compress level must be between 0 and 9, got {}",
+                        level
+                    )),
+                    None,
+                ));
+            }
+            args.append(&mut vec!["--compress-level".to_string(), level.to_string()]);
+        }
+        if encrypt_option.compliance.is_some() {
+            args.append(&mut vec![
+                "--compliance".to_string(),
+                encrypt_option.compliance.as_ref().unwrap().value(),
+            ]);
+        }
+        if encrypt_option.force_ocb {
+            // --force-aead was renamed to --force-ocb in gpg 2.4
+            if self.version >= 2.4 {
+                args.push("--force-ocb".to_string());
+            } else if self.version >= 2.3 {
+                args.push("--force-aead".to_string());
+            } else {
+                return Err(GPGError::new(
+                    GPGErrorType::InvalidArgumentError(format!(
+                        "force_ocb is not supported by gpg {}",
+                        self.full_version
+                    )),
+                    None,
+                ));
+            }
+        }
+
+        if encrypt_option.aead_algo.is_some() {
+            // gpg do not list the AEAD mode(s) in --list-config, they are only supported from gpg 2.3
+            if self.version < 2.3 {
+                return Err(GPGError::new(
+                    GPGErrorType::InvalidArgumentError(format!(
+                        "aead_algo is not supported by gpg {}",
+                        self.full_version
+                    )),
+                    None,
+                ));
+            }
+            args.append(&mut vec![
+                "--aead-algo".to_string(),
+                encrypt_option.aead_algo.as_ref().unwrap().value(),
+            ]);
+        }
+
+        let mut algos: Vec<(&str, &str, String)> = vec![];
+        if encrypt_option.cipher_algo.is_some() {
+            algos.push(("ciphername", "--cipher-algo", encrypt_option.cipher_algo.as_ref().unwrap().value()));
+        }
+        if encrypt_option.digest_algo.is_some() {
+            algos.push(("digestname", "--digest-algo", encrypt_option.digest_algo.as_ref().unwrap().value()));
+        }
+        if encrypt_option.compress_algo.is_some() {
+            algos.push(("compressname", "--compress-algo", encrypt_option.compress_algo.as_ref().unwrap().value()));
+        }
+        match self.gen_checked_algo_args(algos) {
+            Ok(mut algo_args) => {
+                args.append(&mut algo_args);
+            }
+            Err(e) => {
+                return Err(e);
+            }
+        }
+        return Ok(args);
+    }
+
+    // check each algorithm against the names gpg advertise in --list-config,
+    // algos: list of ( list-config item, gpg argument, algorithm name )
+    fn gen_checked_algo_args(&self, algos: Vec<(&str, &str, String)>) -> Result<Vec<String>, GPGError> {
+        let mut args: Vec<String> = vec![];
+        if algos.len() == 0 {
+            return Ok(args);
+        }
+
+        let config: Result<HashMap<String, Vec<String>>, GPGError> = self.list_config();
+        if let Err(e) = config {
+            return Err(e);
+        }
+        let config: HashMap<String, Vec<String>> = config.unwrap();
+        for (item, arg, algo) in algos {
+            let supported: bool = config
+                .get(item)
+                .map(|names| names.iter().any(|name| name.eq_ignore_ascii_case(&algo)))
+                .unwrap_or(false);
+            if !supported {
+                return Err(GPGError::new(
+                    GPGErrorType::InvalidArgumentError(format!(
+                        "{} is not supported by gpg {}",
+                        algo, self.full_version
+                    )),
+                    None,
+                ));
+            }
+            args.append(&mut vec![arg.to_string(), algo]);
+        }
+        return Ok(args);
+    }
+
+    //*******************************************************
+
+    //                   FILE DECRYPTION
+
+    //*******************************************************
+    // to encrypt file, use the DecryptionOption struct to create the decryption options
+    pub fn decrypt(&self, decrypt_option: DecryptOption) -> Result<CmdResult, GPGError> {
+        // decrypt_option: struct that contains all the decryption options ( refer to the struct for more info )
+
+        let k_p: Option<String> = decrypt_option.key_passphrase.clone();
+        let p: Option<String> = decrypt_option.passphrase.clone();
+        let mut pass: Option<String> = None;
+
+        if k_p.is_some() {
+            if !is_passphrase_valid(k_p.as_ref().unwrap()) {
+                return Err(GPGError::new(
+                    GPGErrorType::PassphraseError("key passphrase invalid".to_string()),
+                    None,
+                ));
+            }
+            pass = k_p;
+        } else if p.is_some() {
+            if !is_passphrase_valid(p.as_ref().unwrap()) {
+                return Err(GPGError::new(
+                    GPGErrorType::PassphraseError("passphrase invalid".to_string()),
+                    None,
+                ));
+            }
+            pass = p;
+        }
+
+        let provider: Option<PassphraseContext> = self.gen_passphrase_context(
+            decrypt_option.passphrase_provider.clone(),
+            decrypt_option.passphrase.clone(),
+        );
+        let args: Vec<String> = self.gen_decrypt_args(
+            decrypt_option.file_path.clone(),
+            decrypt_option.recipient,
+            decrypt_option.always_trust,
+            decrypt_option.show_session_key,
+            decrypt_option.output,
+            decrypt_option.extra_args,
+        );
+        let result: Result<CmdResult, GPGError> = handle_cmd_io_output(
+            Some(args),
+            pass,
+            self.version,
+            self.homedir.clone(),
+            self.options.clone(),
+            self.env.clone(),
+            decrypt_option.file,
+            decrypt_option.file_path,
+            None,
+            true,
+            true,
+            Operation::Decrypt,
+            provider,
+            None,
+        );
+
+        match result {
+            Ok(result) => {
+                return Ok(result);
+            }
+            Err(e) => {
+                return Err(e);
+            }
+        }
+    }
+
+    // to decrypt file and retrieve the metadata of the decryption
+    // ( receipients, key used, algorithm, embedded filename and signature(s) )
+    pub fn decrypt_with_result(
+        &self,
+        decrypt_option: DecryptOption,
+    ) -> Result<DecryptResult, GPGError> {
+        // decrypt_option: struct that contains all the decryption options ( refer to the struct for more info )
+
+        //*****************************************************************************************
+        //    NOTE: if the decryption failed, the metadata can still be retrieved from the result
+        //          of the error with decode_decrypt_result ( ex: to know which key was needed )
+        //******************************************************************************************
+
+        let result: Result<CmdResult, GPGError> = self.decrypt(decrypt_option);
+        match result {
+            Ok(result) => {
+                return Ok(decode_decrypt_result(&result));
+            }
+            Err(e) => {
+                return Err(e);
+            }
+        }
+    }
+
+    // to decrypt file with the session key of the file instead of the secret key or passphrase
+    // ( the session key can be obtained by decrypting with show_session_key )
+    pub fn decrypt_with_session_key(
+        &self,
+        decrypt_option: DecryptOption,
+        session_key: String,
+    ) -> Result<CmdResult, GPGError> {
+        // decrypt_option: struct that contains all the decryption options ( refer to the struct for more info )
+        //                 passphrase, key_passphrase and passphrase_provider will be ignored
+        // session_key: the session key of the file in the format of <algo>:<hexdigits> ( ex: 9:1C26...6E63 )
+
+        let valid: bool = match session_key.split_once(":") {
+            Some((algo, key)) => {
+                algo.len() > 0
+                    && algo.chars().all(|c| c.is_ascii_digit())
+                    && key.len() > 0
+                    && key.chars().all(|c| c.is_ascii_hexdigit())
+            }
+            None => false,
+        };
+        if !valid {
+            return Err(GPGError::new(
+                GPGErrorType::InvalidArgumentError("session key invalid".to_string()),
+                None,
+            ));
+        }
+
+        //*****************************************************************************************
+        //    NOTE: the session key was delivered through its own fd instead of the arguments,
+        //          so it will not be exposed in the process list, which was only supported on unix
+        //******************************************************************************************
+        let args: Vec<String> = self.gen_decrypt_args(
+            decrypt_option.file_path.clone(),
+            decrypt_option.recipient,
+            decrypt_option.always_trust,
+            decrypt_option.show_session_key,
+            decrypt_option.output,
+            decrypt_option.extra_args,
+        );
+        let result: Result<CmdResult, GPGError> = handle_cmd_session_key(
+            Some(args),
+            self.version,
+            self.homedir.clone(),
+            self.options.clone(),
+            self.env.clone(),
+            decrypt_option.file,
+            decrypt_option.file_path,
+            session_key,
+            Operation::Decrypt,
+        );
+        match result {
+            Ok(result) => {
+                return Ok(result);
+            }
+            Err(e) => {
+                return Err(e);
+            }
+        }
+    }
+
+    fn gen_decrypt_args(
+        &self,
+        file_path: Option<String>,
+        recipient: Option<String>,
+        always_trust: bool,
+        show_session_key: bool,
+        output: Option<String>,
+        extra_args: Option<Vec<String>>,
+    ) -> Vec<String> {
+        let mut args: Vec<String> = vec!["--decrypt".to_string()];
+        if recipient.is_some() {
+            args.append(&mut vec!["--recipient".to_string(), recipient.unwrap()]);
+        }
+        if show_session_key {
+            args.push("--show-session-key".to_string());
+        }
+        if always_trust {
+            args.append(&mut vec!["--trust-model".to_string(), "always".to_string()]);
+        }
+        if output.is_some() {
+            set_output_without_confirmation(&mut args, &output.unwrap());
+        } else {
+            // if the system is handling the output
+            // the name wil be [<encryption_type>_encrypted_file_<YYYYMMDD_HH/MM/SS/NANO-SECOND>.<extension>]
+            // the encryption type will either [key] for public key encryption or [pass] for symmetric encryption
+            // the extension will be the same if file_path is provided,
+            // if a rust File type is provided, the name will be extension will be default to gpg
+
+            let ext: String = get_file_extension(file_path);
+            let time_stamp: String = Local::now().format("%Y%m%d-%H:%M:%S:%9f").to_string();
+            let out: String = PathBuf::from(self.output_dir.clone())
+                .join(format!("decrypted_file_{}.{}", time_stamp, ext))
+                .to_string_lossy()
+                .to_string();
+            args.append(&mut vec!["--output".to_string(), out]);
+        }
+
+        if extra_args.is_some() {
+            args.append(&mut extra_args.unwrap());
+        }
+        return args;
+    }
+
+    //*******************************************************
+
+    //           IN-MEMORY ENCRYPTION / DECRYPTION
+
+    //*******************************************************
+    // to encrypt bytes held in memory, the encrypted output will be returned instead of written to a file
+    pub fn encrypt_bytes(
+        &self,
+        data: &[u8],
+        encrypt_option: EncryptOption,
+    ) -> Result<Vec<u8>, GPGError> {
+        // data: bytes to encrypt
+        // encrypt_option: struct that contains all the encryption options ( refer to the struct for more info )
+        //                 file, file_path and output will be ignored
+
+        let mut output: Vec<u8> = Vec::new();
+        let result: Result<CmdResult, GPGError> =
+            self.encrypt_stream(data, &mut output, encrypt_option);
+        match result {
+            Ok(_) => {
+                return Ok(output);
+            }
+            Err(e) => {
+                return Err(e);
+            }
+        }
+    }
+
+    // to decrypt bytes held in memory, the decrypted output will be returned instead of written to a file
+    pub fn decrypt_bytes(
+        &self,
+        data: &[u8],
+        decrypt_option: DecryptOption,
+    ) -> Result<Vec<u8>, GPGError> {
+        // data: bytes to decrypt
+        // decrypt_option: struct that contains all the decryption options ( refer to the struct for more info )
+        //                 file, file_path and output will be ignored
+
+        let mut output: Vec<u8> = Vec::new();
+        let result: Result<CmdResult, GPGError> =
+            self.decrypt_stream(data, &mut output, decrypt_option);
+        match result {
+            Ok(_) => {
+                return Ok(output);
+            }
+            Err(e) => {
+                return Err(e);
+            }
+        }
+    }
+
+    //*******************************************************
+
+    //           STREAM ENCRYPTION / DECRYPTION
+
+    //*******************************************************
+    // to encrypt from any reader into any writer, nothing will be written to disk
+    pub fn encrypt_stream<R: Read + Send, W: Write + Send>(
+        &self,
+        mut input: R,
+        output: &mut W,
+        encrypt_option: EncryptOption,
+    ) -> Result<CmdResult, GPGError> {
+        // input: reader of the data to encrypt
+        // output: writer that the encrypted data will be streamed into
+        // encrypt_option: struct that contains all the encryption options ( refer to the struct for more info )
+        //                 file, file_path and output will be ignored
+
+        let args: Result<(Vec<String>, Option<String>, Option<PassphraseContext>), GPGError> =
+            self.gen_encrypt_stream_args(encrypt_option);
+        match args {
+            Ok((args, passphrase, provider)) => {
+                return handle_cmd_stream(
+                    Some(args),
+                    passphrase,
+                    self.version,
+                    self.homedir.clone(),
+                    self.options.clone(),
+                    self.env.clone(),
+                    &mut input,
+                    Operation::Encrypt,
+                    provider,
+                    output,
+                );
+            }
+            Err(e) => {
+                return Err(e);
+            }
+        }
+    }
+
+    // to decrypt from any reader into any writer, nothing will be written to disk
+    pub fn decrypt_stream<R: Read + Send, W: Write + Send>(
+        &self,
+        mut input: R,
+        output: &mut W,
+        decrypt_option: DecryptOption,
+    ) -> Result<CmdResult, GPGError> {
+        // input: reader of the data to decrypt
+        // output: writer that the decrypted data will be streamed into
+        // decrypt_option: struct that contains all the decryption options ( refer to the struct for more info )
+        //                 file, file_path and output will be ignored
+
+        //*****************************************************************************************
+        //    NOTE: the decrypted data was streamed into the output as gpg produce it,
+        //          if an error was returned, the data written to the output should not be trusted
+        //******************************************************************************************
+
+        let args: Result<(Vec<String>, Option<String>, Option<PassphraseContext>), GPGError> =
+            self.gen_decrypt_stream_args(decrypt_option);
+        match args {
+            Ok((args, passphrase, provider)) => {
+                return handle_cmd_stream(
+                    Some(args),
+                    passphrase,
+                    self.version,
+                    self.homedir.clone(),
+                    self.options.clone(),
+                    self.env.clone(),
+                    &mut input,
+                    Operation::Decrypt,
+                    provider,
+                    output,
+                );
+            }
+            Err(e) => {
+                return Err(e);
+            }
+        }
+    }
+
+    // to obtain a writer that encrypt everything written to it into the output
+    pub fn encrypt_writer<W: Write + Send + 'static>(
+        &self,
+        output: W,
+        encrypt_option: EncryptOption,
+    ) -> Result<GpgEncryptWriter<W>, GPGError> {
+        // output: writer that the encrypted data will be streamed into
+        // encrypt_option: struct that contains all the encryption options ( refer to the struct for more info )
+        //                 file, file_path and output will be ignored
+
+        let args: Result<(Vec<String>, Option<String>, Option<PassphraseContext>), GPGError> =
+            self.gen_encrypt_stream_args(encrypt_option);
+        match args {
+            Ok((args, passphrase, provider)) => {
+                return GpgEncryptWriter::new(self, args, passphrase, provider, output);
+            }
+            Err(e) => {
+                return Err(e);
+            }
+        }
+    }
+
+    // to obtain a reader that read the decrypted data of the input
+    pub fn decrypt_reader<R: Read + Send + 'static>(
+        &self,
+        input: R,
+        decrypt_option: DecryptOption,
+    ) -> Result<GpgDecryptReader, GPGError> {
+        // input: reader of the data to decrypt
+        // decrypt_option: struct that contains all the decryption options ( refer to the struct for more info )
+        //                 file, file_path and output will be ignored
+
+        let args: Result<(Vec<String>, Option<String>, Option<PassphraseContext>), GPGError> =
+            self.gen_decrypt_stream_args(decrypt_option);
+        match args {
+            Ok((args, passphrase, provider)) => {
+                return GpgDecryptReader::new(self, args, passphrase, provider, Box::new(input));
+            }
+            Err(e) => {
+                return Err(e);
+            }
+        }
+    }
+
+    fn gen_encrypt_stream_args(
+        &self,
+        encrypt_option: EncryptOption,
+    ) -> Result<(Vec<String>, Option<String>, Option<PassphraseContext>), GPGError> {
+        let p: Result<Option<String>, GPGError> = get_encrypt_passphrase(&encrypt_option);
+        if let Err(e) = p {
+            return Err(e);
+        }
+        let p: Option<String> = p.unwrap();
+        let provider: Option<PassphraseContext> = self.gen_passphrase_context(
+            encrypt_option.passphrase_provider.clone(),
+            encrypt_option.passphrase.clone(),
+        );
+
+        // output to "-" will make gpg write the encrypted data to stdout
+        let args: Result<Vec<String>, GPGError> = self.gen_encrypt_args(
+            &encrypt_option,
+            None,
+            encrypt_option.passphrase.is_some() || provider.is_some(),
+            Some("-".to_string()),
+        );
+        match args {
+            Ok(args) => {
+                return Ok((args, p, provider));
+            }
+            Err(e) => {
+                return Err(e);
+            }
+        }
+    }
+
+    fn gen_decrypt_stream_args(
+        &self,
+        decrypt_option: DecryptOption,
+    ) -> Result<(Vec<String>, Option<String>, Option<PassphraseContext>), GPGError> {
+        let k_p: Option<String> = decrypt_option.key_passphrase.clone();
+        let p: Option<String> = decrypt_option.passphrase.clone();
+        let mut pass: Option<String> = None;
+
+        if k_p.is_some() {
+            if !is_passphrase_valid(k_p.as_ref().unwrap()) {
+                return Err(GPGError::new(
+                    GPGErrorType::PassphraseError("key passphrase invalid".to_string()),
+                    None,
+                ));
+            }
+            pass = k_p;
+        } else if p.is_some() {
+            if !is_passphrase_valid(p.as_ref().unwrap()) {
+                return Err(GPGError::new(
+                    GPGErrorType::PassphraseError("passphrase invalid".to_string()),
+                    None,
+                ));
+            }
+            pass = p;
+        }
+
+        let provider: Option<PassphraseContext> = self.gen_passphrase_context(
+            decrypt_option.passphrase_provider.clone(),
+            decrypt_option.passphrase.clone(),
+        );
+
+        // output to "-" will make gpg write the decrypted data to stdout
+        let args: Vec<String> = self.gen_decrypt_args(
+            None,
+            decrypt_option.recipient,
+            decrypt_option.always_trust,
+            decrypt_option.show_session_key,
+            Some("-".to_string()),
+            decrypt_option.extra_args,
+        );
+        return Ok((args, pass, provider));
+    }
+
+    //*******************************************************
+
+    //          DIRECTORY ENCRYPTION / DECRYPTION
+
+    //*******************************************************
+    // to encrypt a whole directory into a single archive ( compatible with gpgtar --decrypt )
+    pub fn encrypt_directory(
+        &self,
+        dir: String,
+        encrypt_option: EncryptOption,
+    ) -> Result<CmdResult, GPGError> {
+        // dir: path to the directory to encrypt, the archive will contain the directory itself
+        // encrypt_option: struct that contains all the encryption options ( refer to the struct for more info )
+        //                 file and file_path will be ignored
+
+        let path: PathBuf = match PathBuf::from(&dir).canonicalize() {
+            Ok(path) => path,
+            Err(_) => PathBuf::from(&dir),
+        };
+        if !path.is_dir() || path.file_name().is_none() {
+            return Err(GPGError::new(
+                GPGErrorType::FileNotFoundError(format!("directory {} do not exist", dir)),
+                None,
+            ));
+        }
+        let p: Result<Option<String>, GPGError> = get_encrypt_passphrase(&encrypt_option);
+        if let Err(e) = p {
+            return Err(e);
+        }
+        let p: Option<String> = p.unwrap();
+        let provider: Option<PassphraseContext> = self.gen_passphrase_context(
+            encrypt_option.passphrase_provider.clone(),
+            encrypt_option.passphrase.clone(),
+        );
+        let args: Result<Vec<String>, GPGError> = self.gen_encrypt_args(
+            &encrypt_option,
+            None,
+            encrypt_option.passphrase.is_some() || provider.is_some(),
+            encrypt_option.output.clone(),
+        );
+        if let Err(e) = args {
+            return Err(e);
+        }
+
+        let tar_args: Vec<String> = vec![
+            "--directory".to_string(),
+            path.parent().unwrap().to_string_lossy().to_string(),
+            path.file_name().unwrap().to_string_lossy().to_string(),
+        ];
+        let result: Result<CmdResult, GPGError> = handle_tar_create(
+            tar_args,
+            Some(args.unwrap()),
+            p,
+            self.version,
+            self.homedir.clone(),
+            self.options.clone(),
+            self.env.clone(),
+            Operation::Encrypt,
+            provider,
+        );
+        match result {
+            Ok(result) => {
+                return Ok(result);
+            }
+            Err(e) => {
+                return Err(e);
+            }
+        }
+    }
+
+    // to decrypt an archive created by encrypt_directory ( or gpgtar --encrypt ) and extract it into dest_dir
+    pub fn decrypt_archive(
+        &self,
+        archive: String,
+        decrypt_option: DecryptOption,
+        dest_dir: String,
+    ) -> Result<CmdResult, GPGError> {
+        // archive: path to the encrypted archive
+        // decrypt_option: struct that contains all the decryption options ( refer to the struct for more info )
+        //                 file, file_path and output will be ignored
+        // dest_dir: path to the directory to extract into, will be created if it do not exist
+
+        //*****************************************************************************************
+        //    NOTE: gpgtar write the files while gpg is still decrypting, before the integrity
+        //          check ( MDC / AEAD tag ) at the end of the message. So the archive was extracted
+        //          into a temporary directory next to dest_dir first and only moved into dest_dir
+        //          after gpg reported DECRYPTION_OKAY, otherwise the temporary directory was removed
+        //******************************************************************************************
+
+        let dest: PathBuf = PathBuf::from(&dest_dir);
+        let parent: PathBuf = match dest.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+            _ => PathBuf::from("."),
+        };
+        if let Err(e) = std::fs::create_dir_all(&parent) {
+            return Err(GPGError::new(
+                GPGErrorType::OutputDirError(format!(
+                    "failed to create directory {}: {}",
+                    parent.display(),
+                    e
+                )),
+                None,
+            ));
+        }
+        let time_stamp: String = Local::now().format("%Y%m%d%H%M%S%9f").to_string();
+        let temp_dir: PathBuf = parent.join(format!(
+            ".{}.partial_{}",
+            dest.file_name().unwrap_or_default().to_string_lossy(),
+            time_stamp
+        ));
+        if let Err(e) = std::fs::create_dir(&temp_dir) {
+            return Err(GPGError::new(
+                GPGErrorType::OutputDirError(format!(
+                    "failed to create directory {}: {}",
+                    temp_dir.display(),
+                    e
+                )),
+                None,
+            ));
+        }
+
+        let tar_args: Vec<String> = vec![
+            "--extract".to_string(),
+            "--directory".to_string(),
+            temp_dir.to_string_lossy().to_string(),
+        ];
+        let result: Result<(CmdResult, String), GPGError> =
+            self.handle_archive(archive, decrypt_option, tar_args);
+        let result: CmdResult = match result {
+            Ok((result, _)) => result,
+            Err(e) => {
+                let _ = std::fs::remove_dir_all(&temp_dir);
+                return Err(e);
+            }
+        };
+        if !result
+            .get_raw_data()
+            .unwrap_or_default()
+            .contains("[GNUPG:] DECRYPTION_OKAY")
+        {
+            let _ = std::fs::remove_dir_all(&temp_dir);
+            return Err(GPGError::new(
+                GPGErrorType::GPGProcessError(
+                    "gpg did not report DECRYPTION_OKAY for the archive".to_string(),
                 ),
+                Some(result),
+            ));
+        }
+        let moved: Result<(), GPGError> = move_extracted(&temp_dir, &dest);
+        let _ = std::fs::remove_dir_all(&temp_dir);
+        match moved {
+            Ok(_) => {
+                return Ok(result);
+            }
+            Err(e) => {
+                return Err(e);
+            }
+        }
+    }
+
+    // to list the content of an encrypted archive without extracting it
+    pub fn list_archive(
+        &self,
+        archive: String,
+        decrypt_option: DecryptOption,
+    ) -> Result<Vec<ArchiveEntry>, GPGError> {
+        // archive: path to the encrypted archive
+        // decrypt_option: struct that contains all the decryption options ( refer to the struct for more info )
+        //                 file, file_path and output will be ignored
+
+        let result: Result<(CmdResult, String), GPGError> =
+            self.handle_archive(archive, decrypt_option, vec!["--list-archive".to_string()]);
+        match result {
+            Ok((_, output)) => {
+                return Ok(decode_archive_list(&output));
+            }
+            Err(e) => {
+                return Err(e);
+            }
+        }
+    }
+
+    fn handle_archive(
+        &self,
+        archive: String,
+        decrypt_option: DecryptOption,
+        tar_args: Vec<String>,
+    ) -> Result<(CmdResult, String), GPGError> {
+        if !PathBuf::from(&archive).is_file() {
+            return Err(GPGError::new(
+                GPGErrorType::FileNotFoundError(format!("archive {} do not exist", archive)),
+                None,
+            ));
+        }
+        let args: Result<(Vec<String>, Option<String>, Option<PassphraseContext>), GPGError> =
+            self.gen_decrypt_stream_args(decrypt_option);
+        match args {
+            Ok((args, passphrase, provider)) => {
+                return handle_tar_extract(
+                    tar_args,
+                    Some(args),
+                    passphrase,
+                    self.version,
+                    self.homedir.clone(),
+                    self.options.clone(),
+                    self.env.clone(),
+                    Some(archive),
+                    Operation::Decrypt,
+                    provider,
+                );
+            }
+            Err(e) => {
+                return Err(e);
+            }
+        }
+    }
+
+    //*******************************************************
+
+    //                   RE-ENCRYPTION
+
+    //*******************************************************
+    // to re-encrypt an encrypted file to a new set of recipients,
+    // the decrypted data was piped straight into the encryption so the plaintext never touch the disk
+    pub fn reencrypt(
+        &self,
+        input: String,
+        decrypt_option: DecryptOption,
+        new_recipients: Vec<String>,
+        output: Option<String>,
+    ) -> Result<CmdResult, GPGError> {
+        // input: path to the encrypted file
+        // decrypt_option: struct that contains all the decryption options ( refer to the struct for more info )
+        //                 file, file_path, output and show_session_key will be ignored
+        // new_recipients: keyid(s) or recipient group name(s) to re-encrypt to
+        // output: path to write the re-encrypted file, can be the same as input,
+        //         will use the default output dir with file name as [reencrypted_file_<datetime>.<extension>] set in GPG if not provided
+
+        //*****************************************************************************************
+        //    NOTE: for gpg 2.2 and above, only the encryption layer was removed ( --unwrap ),
+        //          so the signature(s), compression and embedded filename of the original message
+        //          will be kept as it is. The armor of the output will follow the input.
+        //******************************************************************************************
+
+        let mut buffer: Vec<u8> = vec![0; 64];
+        let armored: bool = match File::open(&input) {
+            Ok(mut file) => {
+                let n: usize = file.read(&mut buffer).unwrap_or(0);
+                String::from_utf8_lossy(&buffer[..n])
+                    .trim_start()
+                    .starts_with("-----BEGIN PGP ")
+            }
+            Err(_) => {
+                return Err(GPGError::new(
+                    GPGErrorType::FileNotFoundError(format!("file {} do not exist", input)),
+                    None,
+                ));
+            }
+        };
+        let output: String = match output {
+            Some(output) => output,
+            None => {
+                let ext: String = get_file_extension(Some(input.clone()));
+                let time_stamp: String = Local::now().format("%Y%m%d-%H:%M:%S:%9f").to_string();
+                PathBuf::from(self.output_dir.clone())
+                    .join(format!("reencrypted_file_{}.{}", time_stamp, ext))
+                    .to_string_lossy()
+                    .to_string()
+            }
+        };
+        // gpg can not read and write the same file, the output will replace the input once finished
+        let in_place: bool = PathBuf::from(&output).canonicalize().ok()
+            == PathBuf::from(&input).canonicalize().ok();
+        let target: String = if in_place {
+            format!("{}.reencrypt", output)
+        } else {
+            output.clone()
+        };
+        let unwrap: bool = self.version >= 2.2;
+        let always_trust: bool = decrypt_option.always_trust;
+
+        let decrypt_args: Result<(Vec<String>, Option<String>, Option<PassphraseContext>), GPGError> =
+            self.gen_decrypt_stream_args(decrypt_option);
+        if let Err(e) = decrypt_args {
+            return Err(e);
+        }
+        let (mut decrypt_args, passphrase, provider) = decrypt_args.unwrap();
+        if unwrap {
+            decrypt_args.push("--unwrap".to_string());
+        }
+        decrypt_args.append(&mut vec!["--".to_string(), input.clone()]);
+
+        // the new encryption is only to the new recipient(s), keeping the armor of the input
+        let mut encrypt_option: EncryptOption =
+            EncryptOption::default(None, None, new_recipients, Some(target.clone()));
+        encrypt_option.always_trust = always_trust;
+        encrypt_option.armor = Some(armored);
+        let encrypt_args: Result<Vec<String>, GPGError> =
+            self.gen_encrypt_args(&encrypt_option, Some(input.clone()), false, encrypt_option.output.clone());
+        if let Err(e) = encrypt_args {
+            return Err(e);
+        }
+        let mut encrypt_args: Vec<String> = encrypt_args.unwrap();
+        if unwrap {
+            // the unwrapped message was encrypted as it is, without being wrapped or compressed again
+            encrypt_args.append(&mut vec![
+                "--no-literal".to_string(),
+                "--compress-algo".to_string(),
+                "none".to_string(),
+            ]);
+        }
+
+        let decrypt_cmd: PipeCmd = PipeCmd {
+            args: Some(decrypt_args),
+            passphrase: passphrase,
+            provider: provider,
+            version: self.version,
+            homedir: self.homedir.clone(),
+            options: self.options.clone(),
+            env: self.env.clone(),
+            ops: Operation::Decrypt,
+        };
+        // the encryption is only to public keys, no passphrase is needed
+        let encrypt_cmd: PipeCmd = PipeCmd {
+            args: Some(encrypt_args),
+            passphrase: None,
+            provider: None,
+            version: self.version,
+            homedir: self.homedir.clone(),
+            options: self.options.clone(),
+            env: self.env.clone(),
+            ops: Operation::Encrypt,
+        };
+        let result: Result<(CmdResult, CmdResult), GPGError> = handle_cmd_pipe(decrypt_cmd, encrypt_cmd);
+        match result {
+            Ok((_, result)) => {
+                if in_place {
+                    if let Err(e) = std::fs::rename(&target, &output) {
+                        return Err(GPGError::new(
+                            GPGErrorType::WriteFailError(e.to_string()),
+                            Some(result),
+                        ));
+                    }
+                }
+                return Ok(result);
+            }
+            Err(e) => {
+                // a partially re-encrypted file is of no use
+                let _ = std::fs::remove_file(&target);
+                return Err(e);
+            }
+        }
+    }
+
+    // to re-encrypt every file under a directory ( recursively ) to a new set of recipients
+    pub fn reencrypt_directory(
+        &self,
+        dir: String,
+        decrypt_option: DecryptOption,
+        new_recipients: Vec<String>,
+        output_dir: String,
+    ) -> Result<Vec<ReencryptResult>, GPGError> {
+        // dir: path to the directory of encrypted files
+        // decrypt_option: struct that contains all the decryption options ( refer to the struct for more info )
+        //                 file, file_path, output and show_session_key will be ignored
+        // new_recipients: keyid(s) or recipient group name(s) to re-encrypt to
+        // output_dir: path to the directory to write the re-encrypted files into with the same structure as dir,
+        //             can be the same as dir to re-encrypt in place
+
+        //*****************************************************************************************
+        //    NOTE: a file that failed to be re-encrypted will not stop the others,
+        //          check the result of each file
+        //******************************************************************************************
+
+        if !check_is_dir(dir.clone()) {
+            return Err(GPGError::new(
+                GPGErrorType::FileNotFoundError(format!("directory {} do not exist", dir)),
                 None,
             ));
         }
+        let mut results: Vec<ReencryptResult> = vec![];
+        for file in list_files_in_dir(Path::new(&dir)) {
+            let relative: PathBuf = file.strip_prefix(&dir).unwrap_or(&file).to_path_buf();
+            let output: PathBuf = PathBuf::from(&output_dir).join(relative);
+            if let Some(parent) = output.parent() {
+                let _ = std::fs::create_dir_all(parent);
+            }
+            let option: DecryptOption = DecryptOption {
+                file: None,
+                file_path: None,
+                recipient: decrypt_option.recipient.clone(),
+                always_trust: decrypt_option.always_trust,
+                passphrase: decrypt_option.passphrase.clone(),
+                key_passphrase: decrypt_option.key_passphrase.clone(),
+                show_session_key: false,
+                passphrase_provider: decrypt_option.passphrase_provider.clone(),
+                output: None,
+                extra_args: decrypt_option.extra_args.clone(),
+            };
+            let input: String = file.to_string_lossy().to_string();
+            let output: String = output.to_string_lossy().to_string();
+            let result: Result<CmdResult, GPGError> = self.reencrypt(
+                input.clone(),
+                option,
+                new_recipients.clone(),
+                Some(output.clone()),
+            );
+            results.push(ReencryptResult {
+                input: input,
+                output: output,
+                success: result.is_ok(),
+                error: result.err().map(|e| e.error_type.to_string()),
+            });
+        }
+        return Ok(results);
+    }
+
+    //*******************************************************
+
+    //                   FILE SIGNING
+
+    //*******************************************************
+    pub fn sign(&self, sign_option: SignOption) -> Result<CmdResult, GPGError> {
+        // sign_option: struct that contains all the signing options ( refer to the struct for more info )
+
+        //*****************************************************************************************
+        //    NOTE: gpg only read 1 passphrase from the passphrase fd, when signing with multiple
+        //          protected keys ( signers ), use passphrase_provider so each key get its own passphrase
+        //******************************************************************************************
+
+        if sign_option.key_passphrase.is_some() {
+            if !is_passphrase_valid(sign_option.key_passphrase.as_ref().unwrap()) {
+                return Err(GPGError::new(
+                    GPGErrorType::PassphraseError("passphrase invalid".to_string()),
+                    None,
+                ));
+            }
+        };
+        let mut sig_args: Vec<String> = match gen_sig_metadata_args(
+            &sign_option.notations,
+            &sign_option.policy_url,
+            &sign_option.keyserver_url,
+        ) {
+            Ok(sig_args) => sig_args,
+            Err(e) => {
+                return Err(e);
+            }
+        };
+        let args: Result<Vec<String>, GPGError> = self.gen_sign_args(
+            sign_option.keyid.clone(),
+            sign_option.signers.clone(),
+            &sign_option.mode,
+            sign_option.armor.unwrap_or(self.armor),
+            sign_option.textmode,
+            sign_option.digest_algo.clone(),
+            sign_option.sig_expire.clone(),
+            sign_option.output,
+            sign_option.extra_args,
+        );
+        let mut args: Vec<String> = match args {
+            Ok(args) => args,
+            Err(e) => {
+                return Err(e);
+            }
+        };
+        args.append(&mut sig_args);
+
+        let provider: Option<PassphraseContext> =
+            self.gen_passphrase_context(sign_option.passphrase_provider.clone(), None);
+        let result: Result<CmdResult, GPGError> = handle_cmd_io_output(
+            Some(args),
+            sign_option.key_passphrase,
+            self.version,
+            self.homedir.clone(),
+            self.options.clone(),
+            self.env.clone(),
+            sign_option.file,
+            sign_option.file_path,
+            None,
+            true,
+            true,
+            Operation::Sign,
+            provider,
+            None,
+        );
+        match result {
+            Ok(result) => {
+                return Ok(result);
+            }
+            Err(e) => {
+                return Err(e);
+            }
+        }
+    }
 
-        if self.armor {
-            args.push("--armor".to_string());
+    // to sign file and retrieve the signature created by each signing key
+    pub fn sign_with_result(&self, sign_option: SignOption) -> Result<Vec<SignResult>, GPGError> {
+        // sign_option: struct that contains all the signing options ( refer to the struct for more info )
+
+        let result: Result<CmdResult, GPGError> = self.sign(sign_option);
+        match result {
+            Ok(result) => {
+                return Ok(decode_sign_result(&result));
+            }
+            Err(e) => {
+                return Err(e);
+            }
         }
-        if output.is_some() {
-            set_output_without_confirmation(&mut args, &output.unwrap());
-        } else {
-            // if the system is handling the output
-            // the name wil be [<encryption_type>_encrypted_file_<YYYYMMDD_HH/MM/SS/NANO-SECOND>.<extension>]
-            // the encryption type will either [key] for public key encryption or [pass] for symmetric encryption or both
-            // the extension will be the same if file_path is provided,
-            // if a rust File type is provided, the file extension will be default to .gpg
+    }
 
-            let ext: String = get_file_extension(file_path);
-            let time_stamp: String = Local::now().format("%Y%m%d-%H:%M:%S:%9f").to_string();
-            let out: String = PathBuf::from(self.output_dir.clone())
-                .join(format!(
-                    "{}_encrypted_file_{}.{}",
-                    encrypt_type, time_stamp, ext
-                ))
-                .to_string_lossy()
-                .to_string();
-            args.append(&mut vec!["--output".to_string(), out]);
+    fn gen_sign_args(
+        &self,
+        keyid: Option<String>,
+        signers: Vec<String>,
+        mode: &SignMode,
+        armor: bool,
+        textmode: bool,
+        digest_algo: Option<DigestAlgo>,
+        sig_expire: Option<String>,
+        output: Option<String>,
+        extra_args: Option<Vec<String>>,
+    ) -> Result<Vec<String>, GPGError> {
+        // the expiry and digest algorithm were validated before gpg was called, same as the encryption
+        if sig_expire.is_some() && !is_sig_expire_valid(sig_expire.as_ref().unwrap()) {
+            return Err(GPGError::new(
+                GPGErrorType::InvalidArgumentError(format!(
+                    "sig_expire must be 0, <n>[d|w|m|y] or an ISO date ( YYYY-MM-DD ), got {}",
+                    sig_expire.unwrap()
+                )),
+                None,
+            ));
+        }
+        let mut digest_args: Vec<String> = vec![];
+        if digest_algo.is_some() {
+            match self.gen_checked_algo_args(vec![("digestname", "--digest-algo", digest_algo.unwrap().value())]) {
+                Ok(algo_args) => {
+                    digest_args = algo_args;
+                }
+                Err(e) => {
+                    return Err(e);
+                }
+            }
         }
 
-        if sign {
-            if sign_key.is_some() {
-                args.append(&mut vec![
-                    "--sign".to_string(),
-                    "--default-key".to_string(),
-                    sign_key.unwrap(),
-                ]);
-            } else {
+        let mut args: Vec<String> = vec![];
+        let time_stamp: String = Local::now().format("%Y%m%d-%H:%M:%S:%9f").to_string();
+
+        // clearsigned output is always ascii armored
+        let extension: &str = match mode {
+            SignMode::Inline => {
                 args.push("--sign".to_string());
+                if armor { ".asc" } else { ".gpg" }
             }
-        }
+            SignMode::Clear => {
+                args.push("--clearsign".to_string());
+                ".asc"
+            }
+            SignMode::Detached | SignMode::DetachedText => {
+                args.push("--detach-sign".to_string());
+                if armor { ".asc" } else { ".sig" }
+            }
+        };
+        let file_path: String = output.unwrap_or(
+            PathBuf::from(self.output_dir.clone())
+                .join(format!("{}_sign_{}{}", mode.value(), time_stamp, extension))
+                .to_string_lossy()
+                .to_string(),
+        );
+        set_output_without_confirmation(&mut args, &file_path);
+
+        if signers.len() > 0 {
+            // gpg ignore --default-key once --local-user was provided, so keyid is treated as 1 of the signers
+            let mut local_users: Vec<String> = keyid.into_iter().collect();
+            for signer in signers {
+                if !local_users.contains(&signer) {
+                    local_users.push(signer);
+                }
+            }
+            for local_user in local_users {
+                args.append(&mut vec!["--local-user".to_string(), local_user]);
+            }
+        } else if keyid.is_some() {
+            args.append(&mut vec!["--default-key".to_string(), keyid.unwrap()]);
+        };
 
-        if always_trust {
-            args.append(&mut vec!["--trust-model".to_string(), "always".to_string()]);
+        if armor && *mode != SignMode::Clear {
+            args.push("--armor".to_string());
+        }
+        if textmode || *mode == SignMode::DetachedText {
+            args.push("--textmode".to_string());
+        }
+        args.append(&mut digest_args);
+        if sig_expire.is_some() {
+            args.append(&mut vec!["--default-sig-expire".to_string(), sig_expire.unwrap()]);
         }
 
         if extra_args.is_some() {
@@ -915,57 +3332,43 @@ impl GPG {
 
     //*******************************************************
 
-    //                   FILE DECRYPTION
+    //                   FILE VERIFICATION
 
     //*******************************************************
-    // to encrypt file, use the DecryptionOption struct to create the decryption options
-    pub fn decrypt(&self, decrypt_option: DecryptOption) -> Result<CmdResult, GPGError> {
-        // decrypt_option: struct that contains all the decryption options ( refer to the struct for more info )
-
-        let k_p: Option<String> = decrypt_option.key_passphrase.clone();
-        let p: Option<String> = decrypt_option.passphrase.clone();
-        let mut pass: Option<String> = None;
+    pub fn verify_file(
+        &self,
+        file: Option<File>,
+        file_path: Option<String>,
+        signature_file_path: Option<String>,
+        extra_args: Option<Vec<String>>,
+    ) -> Result<CmdResult, GPGError> {
+        // file: file object
+        // file_path: path to file
+        // signature_file_path: path to signature file
+        // extra_args: extra arguments to pass to gpg
 
-        if k_p.is_some() {
-            if !is_passphrase_valid(k_p.as_ref().unwrap()) {
-                return Err(GPGError::new(
-                    GPGErrorType::PassphraseError("key passphrase invalid".to_string()),
-                    None,
-                ));
-            }
-            pass = k_p;
-        } else if p.is_some() {
-            if !is_passphrase_valid(p.as_ref().unwrap()) {
-                return Err(GPGError::new(
-                    GPGErrorType::PassphraseError("passphrase invalid".to_string()),
-                    None,
-                ));
-            }
-            pass = p;
-        }
+        //*****************************************************************************************
+        //    NOTE: If only file or file_path is provided, it expected the file to include a
+        //          complete signature.
+        //          For detached signature, signature_file_path is required along
+        //          with file or file_path
+        //******************************************************************************************
 
-        let args: Vec<String> = self.gen_decrypt_args(
-            decrypt_option.file_path.clone(),
-            decrypt_option.recipient,
-            decrypt_option.always_trust,
-            decrypt_option.output,
-            decrypt_option.extra_args,
-        );
+        let args: Vec<String> = self.gen_verify_file_args(signature_file_path, extra_args);
         let result: Result<CmdResult, GPGError> = handle_cmd_io(
             Some(args),
-            pass,
+            None,
             self.version,
             self.homedir.clone(),
             self.options.clone(),
             self.env.clone(),
-            decrypt_option.file,
-            decrypt_option.file_path,
+            file,
+            file_path.clone(),
             None,
             true,
             true,
-            Operation::Decrypt,
+            Operation::VerifyFile,
         );
-
         match result {
             Ok(result) => {
                 return Ok(result);
@@ -976,86 +3379,211 @@ impl GPG {
         }
     }
 
-    fn gen_decrypt_args(
+    // to verify file and retrieve the result of each signature
+    // ( signer, algorithms, creation time, trust level, etc. )
+    pub fn verify_file_with_result(
         &self,
+        file: Option<File>,
         file_path: Option<String>,
-        recipient: Option<String>,
-        always_trust: bool,
-        output: Option<String>,
+        signature_file_path: Option<String>,
         extra_args: Option<Vec<String>>,
-    ) -> Vec<String> {
-        let mut args: Vec<String> = vec!["--decrypt".to_string()];
-        if recipient.is_some() {
-            args.append(&mut vec!["--recipient".to_string(), recipient.unwrap()]);
+    ) -> Result<Vec<VerifyResult>, GPGError> {
+        // file: file object
+        // file_path: path to file
+        // signature_file_path: path to signature file
+        // extra_args: extra arguments to pass to gpg
+
+        //*****************************************************************************************
+        //    NOTE: a valid signature made with a key that is not fully trusted will still be returned,
+        //          check trusted ( or trust_level ) of each result, not only the status.
+        //          If the verification failed ( ex: BADSIG ), the result of each signature can still
+        //          be retrieved from the result of the error with decode_verify_result
+        //******************************************************************************************
+
+        let result: Result<CmdResult, GPGError> =
+            self.verify_file(file, file_path, signature_file_path, extra_args);
+        match result {
+            Ok(result) => {
+                return Ok(decode_verify_result(&result));
+            }
+            Err(e) => {
+                return Err(e);
+            }
         }
-        if always_trust {
-            args.append(&mut vec!["--trust-model".to_string(), "always".to_string()]);
+    }
+
+    // to verify data from any reader ( ex: a request body ) against a detached signature held in memory,
+    // nothing will be written to the disk
+    pub fn verify_detached(
+        &self,
+        mut data: impl Read + Send,
+        signature: &[u8],
+    ) -> Result<StreamVerifyResult, GPGError> {
+        // data: reader of the signed data
+        // signature: the detached signature ( armored or binary )
+
+        //*****************************************************************************************
+        //    NOTE: a failed verification ( ex: BADSIG ) was still returned as the result, with verified
+        //          as false and the result of each signature that was checked. An error was only
+        //          returned if no signature could be checked ( ex: the data could not be read )
+        //******************************************************************************************
+
+        let result: Result<CmdResult, GPGError> = handle_cmd_verify_stream(
+            Some(vec!["--verify".to_string()]),
+            self.version,
+            self.homedir.clone(),
+            self.options.clone(),
+            self.env.clone(),
+            &mut data,
+            Some(signature.to_vec()),
+            Operation::VerifyFile,
+        );
+        return gen_stream_verify_result(result);
+    }
+
+    // to verify a message with embedded signature ( signed or clearsigned ) from any reader
+    pub fn verify_inline(&self, mut message: impl Read + Send) -> Result<StreamVerifyResult, GPGError> {
+        // message: reader of the signed message
+
+        //*****************************************************************************************
+        //    NOTE: same as verify_detached, a failed verification was still returned as the result
+        //******************************************************************************************
+
+        let result: Result<CmdResult, GPGError> = handle_cmd_verify_stream(
+            Some(vec!["--verify".to_string()]),
+            self.version,
+            self.homedir.clone(),
+            self.options.clone(),
+            self.env.clone(),
+            &mut message,
+            None,
+            Operation::VerifyFile,
+        );
+        return gen_stream_verify_result(result);
+    }
+
+    // to verify file signed by multiple signer(s) against a policy ( ex: 2 of 4 maintainers must sign )
+    pub fn verify_with_policy(
+        &self,
+        data: String,
+        signatures: Vec<String>,
+        policy: VerifyPolicy,
+    ) -> Result<PolicyResult, GPGError> {
+        // data: path to the signed file
+        // signatures: path(s) to the detached signature file(s), each file can hold 1 or more signature(s)
+        // policy: struct that contains the requirement(s) of the verification ( refer to the struct for more info )
+
+        //*****************************************************************************************
+        //    NOTE: a bad signature ( or any signature that failed the policy ) will not fail the
+        //          verification by itself, it will only not be counted, check rejected of the result
+        //          for the signature(s) that were not counted and the reason
+        //******************************************************************************************
+
+        // a policy that need no signature ( or allow no signer ) would pass without any good signature
+        if policy.min_signatures == 0 {
+            return Err(GPGError::new(
+                GPGErrorType::InvalidArgumentError("min_signatures of the policy must be at least 1".to_string()),
+                None,
+            ));
+        }
+        if policy.allowed_signers.len() == 0 {
+            return Err(GPGError::new(
+                GPGErrorType::InvalidArgumentError("allowed_signers of the policy must not be empty".to_string()),
+                None,
+            ));
         }
-        if output.is_some() {
-            set_output_without_confirmation(&mut args, &output.unwrap());
-        } else {
-            // if the system is handling the output
-            // the name wil be [<encryption_type>_encrypted_file_<YYYYMMDD_HH/MM/SS/NANO-SECOND>.<extension>]
-            // the encryption type will either [key] for public key encryption or [pass] for symmetric encryption
-            // the extension will be the same if file_path is provided,
-            // if a rust File type is provided, the name will be extension will be default to gpg
 
-            let ext: String = get_file_extension(file_path);
-            let time_stamp: String = Local::now().format("%Y%m%d-%H:%M:%S:%9f").to_string();
-            let out: String = PathBuf::from(self.output_dir.clone())
-                .join(format!("decrypted_file_{}.{}", time_stamp, ext))
-                .to_string_lossy()
-                .to_string();
-            args.append(&mut vec!["--output".to_string(), out]);
+        let mut results: Vec<VerifyResult> = vec![];
+        for signature in signatures {
+            let result: Result<CmdResult, GPGError> =
+                self.verify_file(None, Some(data.clone()), Some(signature), None);
+            match result {
+                Ok(result) => {
+                    results.append(&mut decode_verify_result(&result));
+                }
+                Err(e) => {
+                    // the signature(s) were still checked by gpg, unless the signature file could not be read
+                    let mut signatures: Vec<VerifyResult> = match &e.cmd_result {
+                        Some(result) => decode_verify_result(result),
+                        None => vec![],
+                    };
+                    if signatures.len() == 0 {
+                        return Err(e);
+                    }
+                    results.append(&mut signatures);
+                }
+            }
         }
+        return Ok(check_verify_policy(&policy, results));
+    }
 
-        if extra_args.is_some() {
-            args.append(&mut extra_args.unwrap());
+    fn gen_verify_file_args(
+        &self,
+        signature_file_path: Option<String>,
+        extra_args: Option<Vec<String>>,
+    ) -> Vec<String> {
+        // extra arguments must come before the file(s) to verify, otherwise gpg will treat them as file(s)
+        let mut args: Vec<String> = extra_args.unwrap_or(vec![]);
+        args.push("--verify".to_string());
+        if signature_file_path.is_some() {
+            args.append(&mut vec![signature_file_path.unwrap(), "-".to_string()]);
         }
         return args;
     }
 
     //*******************************************************
 
-    //                   FILE SIGNING
+    //                 MESSAGE INSPECTION
 
     //*******************************************************
-    pub fn sign(&self, sign_option: SignOption) -> Result<CmdResult, GPGError> {
-        // sign_option: struct that contains all the signing options ( refer to the struct for more info )
+    // to inspect the packet(s) of an OpenPGP message without decrypting or verifying it
+    // ( receipients, symmetric encryption, signer(s), embedded filename, etc. )
+    pub fn inspect_message(
+        &self,
+        file: Option<File>,
+        file_path: Option<String>,
+    ) -> Result<MessageInfo, GPGError> {
+        // file: file object
+        // file_path: path to file
 
-        if sign_option.key_passphrase.is_some() {
-            if !is_passphrase_valid(sign_option.key_passphrase.as_ref().unwrap()) {
-                return Err(GPGError::new(
-                    GPGErrorType::PassphraseError("passphrase invalid".to_string()),
-                    None,
-                ));
+        let file: Result<File, GPGError> = get_file_obj(file, file_path);
+        let mut file: File = match file {
+            Ok(file) => file,
+            Err(e) => {
+                return Err(e);
             }
         };
-        let args: Vec<String> = self.gen_sign_args(
-            sign_option.keyid.clone(),
-            sign_option.clearsign,
-            sign_option.detach,
-            sign_option.output,
-            sign_option.extra_args,
-        );
 
+        // only the beginning of the file was read to know if it was armored, the whole file was then
+        // streamed into gpg instead of being loaded into memory
+        let mut head: Vec<u8> = vec![];
+        let mut read: Result<u64, std::io::Error> = Read::by_ref(&mut file).take(64).read_to_end(&mut head).map(|_| 0);
+        if read.is_ok() {
+            read = file.seek(SeekFrom::Start(0));
+        }
+        if let Err(e) = read {
+            return Err(GPGError::new(
+                GPGErrorType::ReadFailError(format!("failed to read the message: {}", e)),
+                None,
+            ));
+        }
         let result: Result<CmdResult, GPGError> = handle_cmd_io(
-            Some(args),
-            sign_option.key_passphrase,
+            Some(vec!["--list-packets".to_string(), "--list-only".to_string()]),
+            None,
             self.version,
             self.homedir.clone(),
             self.options.clone(),
             self.env.clone(),
-            sign_option.file,
-            sign_option.file_path,
+            Some(file),
+            None,
             None,
             true,
             true,
-            Operation::Sign,
+            Operation::InspectMessage,
         );
         match result {
             Ok(result) => {
-                return Ok(result);
+                return Ok(decode_message_info(&result, is_armored(&head)));
             }
             Err(e) => {
                 return Err(e);
@@ -1063,97 +3591,32 @@ impl GPG {
         }
     }
 
-    fn gen_sign_args(
-        &self,
-        keyid: Option<String>,
-        clearsign: bool,
-        detach: bool,
-        output: Option<String>,
-        extra_args: Option<Vec<String>>,
-    ) -> Vec<String> {
-        let mut args: Vec<String> = vec!["--sign".to_string()];
-        let time_stamp: String = Local::now().format("%Y%m%d-%H:%M:%S:%9f").to_string();
-
-        if clearsign {
-            args.push("--clearsign".to_string());
-        };
-        if detach {
-            args.push("--detach-sign".to_string());
-            let extension = if self.armor { ".asc" } else { ".sig" };
-            let file_path: String = output.unwrap_or(
-                PathBuf::from(self.output_dir.clone())
-                    .join(format!("detach_sign_{}{}", time_stamp, extension))
-                    .to_string_lossy()
-                    .to_string(),
-            );
-            set_output_without_confirmation(&mut args, &file_path);
-        } else {
-            let file_path: String = output.unwrap_or(
-                PathBuf::from(self.output_dir.clone())
-                    .join(format!("embedded_sign_{}.gpg", time_stamp))
-                    .to_string_lossy()
-                    .to_string(),
-            );
-            set_output_without_confirmation(&mut args, &file_path);
-        }
-
-        if keyid.is_some() {
-            args.append(&mut vec!["--default-key".to_string(), keyid.unwrap()]);
-        };
-
-        if self.armor {
-            args.push("--armor".to_string());
-        }
-
-        if extra_args.is_some() {
-            args.append(&mut extra_args.unwrap());
-        }
-
-        return args;
-    }
-
-    //*******************************************************
-
-    //                   FILE VERIFICATION
-
-    //*******************************************************
-    pub fn verify_file(
-        &self,
-        file: Option<File>,
-        file_path: Option<String>,
-        signature_file_path: Option<String>,
-        extra_args: Option<Vec<String>>,
-    ) -> Result<CmdResult, GPGError> {
-        // file: file object
-        // file_path: path to file
-        // signature_file_path: path to signature file
-        // extra_args: extra arguments to pass to gpg
+    // same as inspect_message, but for a message held in memory
+    pub fn inspect_message_bytes(&self, data: &[u8]) -> Result<MessageInfo, GPGError> {
+        // data: bytes of the message
 
         //*****************************************************************************************
-        //    NOTE: If only file or file_path is provided, it expected the file to include a
-        //          complete signature.
-        //          For detached signature, signature_file_path is required along
-        //          with file or file_path
+        //    NOTE: the content of encrypted data ( signature, filename ) can not be known
+        //          without decrypting it, refer to decrypt_with_result for those
         //******************************************************************************************
 
-        let args: Vec<String> = self.gen_verify_file_args(signature_file_path, extra_args);
         let result: Result<CmdResult, GPGError> = handle_cmd_io(
-            Some(args),
+            Some(vec!["--list-packets".to_string(), "--list-only".to_string()]),
             None,
             self.version,
             self.homedir.clone(),
             self.options.clone(),
             self.env.clone(),
-            file,
-            file_path.clone(),
             None,
+            None,
+            Some(data.to_vec()),
             true,
-            true,
-            Operation::VerifyFile,
+            false,
+            Operation::InspectMessage,
         );
         match result {
             Ok(result) => {
-                return Ok(result);
+                return Ok(decode_message_info(&result, is_armored(data)));
             }
             Err(e) => {
                 return Err(e);
@@ -1161,19 +3624,293 @@ impl GPG {
         }
     }
 
-    fn gen_verify_file_args(
+    //*******************************************************
+
+    //               PASSPHRASE PROVIDER
+
+    //*******************************************************
+    // the keygrip of the key gpg prompt the passphrase for will be passed along to the provider,
+    // it was looked up when the prompt arrive ( refer to PassphraseContext::lookup_keygrip )
+    fn gen_passphrase_context(
         &self,
-        signature_file_path: Option<String>,
-        extra_args: Option<Vec<String>>,
-    ) -> Vec<String> {
-        let mut args: Vec<String> = vec!["--verify".to_string()];
-        if signature_file_path.is_some() {
-            args.append(&mut vec![signature_file_path.unwrap(), "-".to_string()]);
+        provider: Option<Arc<dyn PassphraseProvider>>,
+        symmetric_passphrase: Option<String>,
+    ) -> Option<PassphraseContext> {
+        if provider.is_none() {
+            return None;
         }
-        if extra_args.is_some() {
-            args.append(&mut extra_args.unwrap());
+        return Some(PassphraseContext::new(
+            provider.unwrap(),
+            self.clone(),
+            symmetric_passphrase,
+        ));
+    }
+}
+
+// move the extracted entries into dest, an entry that already exist in dest will not be overwritten
+fn move_extracted(temp_dir: &Path, dest: &Path) -> Result<(), GPGError> {
+    if !dest.exists() {
+        if let Err(e) = std::fs::rename(temp_dir, dest) {
+            return Err(GPGError::new(
+                GPGErrorType::OutputDirError(format!(
+                    "failed to move the extracted archive into {}: {}",
+                    dest.display(),
+                    e
+                )),
+                None,
+            ));
         }
-        return args;
+        return Ok(());
+    }
+
+    let entries: Vec<PathBuf> = match std::fs::read_dir(temp_dir) {
+        Ok(entries) => entries.filter_map(|entry| entry.ok()).map(|entry| entry.path()).collect(),
+        Err(e) => {
+            return Err(GPGError::new(GPGErrorType::OutputDirError(e.to_string()), None));
+        }
+    };
+    // check every entry first so nothing was moved when one of them already exist
+    for entry in entries.iter() {
+        let target: PathBuf = dest.join(entry.file_name().unwrap_or_default());
+        if target.exists() {
+            return Err(GPGError::new(
+                GPGErrorType::OutputDirError(format!("{} already exist", target.display())),
+                None,
+            ));
+        }
+    }
+    for entry in entries.iter() {
+        let target: PathBuf = dest.join(entry.file_name().unwrap_or_default());
+        if let Err(e) = std::fs::rename(entry, &target) {
+            return Err(GPGError::new(
+                GPGErrorType::OutputDirError(format!(
+                    "failed to move the extracted {} into {}: {}",
+                    entry.display(),
+                    dest.display(),
+                    e
+                )),
+                None,
+            ));
+        }
+    }
+    return Ok(());
+}
+
+// if the beginning of the message was an ascii armor header
+fn is_armored(data: &[u8]) -> bool {
+    return String::from_utf8_lossy(&data[..data.len().min(64)])
+        .trim_start()
+        .starts_with("-----BEGIN PGP ");
+}
+
+// a failed verification with signature(s) checked was returned as the result, not as an error
+fn gen_stream_verify_result(result: Result<CmdResult, GPGError>) -> Result<StreamVerifyResult, GPGError> {
+    match result {
+        Ok(result) => {
+            return Ok(StreamVerifyResult {
+                verified: true,
+                signatures: decode_verify_result(&result),
+            });
+        }
+        Err(e) => {
+            if !matches!(e.error_type, GPGErrorType::GPGProcessError(_)) {
+                return Err(e);
+            }
+            let signatures: Vec<VerifyResult> = match &e.cmd_result {
+                Some(result) => decode_verify_result(result),
+                None => vec![],
+            };
+            if signatures.len() == 0 {
+                return Err(e);
+            }
+            return Ok(StreamVerifyResult {
+                verified: false,
+                signatures: signatures,
+            });
+        }
+    }
+}
+
+fn is_locate_miss(output: &str) -> bool {
+    // only a miss of the mechanism ( ex. no WKD for the domain ) will try the next one,
+    // any other failure ( ex. bad homedir, dirmngr or keyserver unavailable ) was returned
+    return output.contains("error reading key: No public key")
+        || output.contains("error reading key: No data")
+        || output.contains("error reading key: No name")
+        || output.contains("error reading key: Not found");
+}
+
+// the exact selector of a group member, fingerprint / keyid as it is, email as <mbox> and the rest as the full =uid
+fn gen_member_selector(member: &str) -> String {
+    let member: &str = member.trim();
+    let hex: &str = member.strip_prefix("0x").unwrap_or(member);
+    if [8, 16, 32, 40].contains(&hex.len()) && hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return member.to_string();
+    }
+    if member.starts_with('<') || member.starts_with('=') {
+        return member.to_string();
+    }
+    if member.contains('@') {
+        return format!("<{}>", member);
+    }
+    return format!("={}", member);
+}
+
+// retrieve the passphrase that will be passed to gpg for the encryption
+fn get_encrypt_passphrase(encrypt_option: &EncryptOption) -> Result<Option<String>, GPGError> {
+    //*****************************************************************************************
+    //    NOTE: gpg only read 1 passphrase from the passphrase fd,
+    //          the symmetric passphrase will be used if encrypting symmetrically,
+    //          otherwise the key passphrase will be used for signing.
+    //          Signing while encrypting symmetrically will only work if both passphrase were the same.
+    //******************************************************************************************
+
+    let p: Option<String> = encrypt_option.passphrase.clone();
+    let k_p: Option<String> = encrypt_option.key_passphrase.clone();
+
+    if p.is_some() {
+        if !is_passphrase_valid(p.as_ref().unwrap()) {
+            return Err(GPGError::new(
+                GPGErrorType::PassphraseError("passphrase invalid".to_string()),
+                None,
+            ));
+        }
+    }
+    if k_p.is_some() {
+        if !is_passphrase_valid(k_p.as_ref().unwrap()) {
+            return Err(GPGError::new(
+                GPGErrorType::PassphraseError("key passphrase invalid".to_string()),
+                None,
+            ));
+        }
+    }
+
+    if encrypt_option.symmetric {
+        if encrypt_option.sign && k_p.is_some() && k_p != p {
+            return Err(GPGError::new(
+                GPGErrorType::InvalidArgumentError(
+                    "key passphrase must be the same as passphrase when signing while encrypting symmetrically".to_string(),
+                ),
+                None,
+            ));
+        }
+        return Ok(p);
+    }
+    if encrypt_option.sign {
+        return Ok(k_p);
+    }
+    return Ok(p);
+}
+
+// the notation(s), policy url and preferred keyserver url that will be added to the signature
+fn gen_sig_metadata_args(
+    notations: &Vec<(String, String, bool)>,
+    policy_url: &Option<String>,
+    keyserver_url: &Option<String>,
+) -> Result<Vec<String>, GPGError> {
+    let mut args: Vec<String> = vec![];
+    for (name, value, critical) in notations {
+        if name.len() == 0 || name.contains('=') || name.contains(char::is_whitespace) {
+            return Err(GPGError::new(
+                GPGErrorType::InvalidArgumentError(format!("invalid notation name: {}", name)),
+                None,
+            ));
+        }
+        // a critical notation is marked with ! in front of the name
+        let name: String = if *critical { format!("!{}", name) } else { name.clone() };
+        args.append(&mut vec!["--sig-notation".to_string(), format!("{}={}", name, value)]);
+    }
+    if policy_url.is_some() {
+        args.append(&mut vec!["--sig-policy-url".to_string(), policy_url.clone().unwrap()]);
+    }
+    if keyserver_url.is_some() {
+        args.append(&mut vec!["--sig-keyserver-url".to_string(), keyserver_url.clone().unwrap()]);
+    }
+    return Ok(args);
+}
+
+// the signature expiry accepted by gpg: 0 ( never ), <n>[d|w|m|y] or an ISO date ( YYYY-MM-DD or YYYYMMDDTHHMMSS )
+fn is_sig_expire_valid(sig_expire: &str) -> bool {
+    let sig_expire: &str = sig_expire.trim();
+    let count: &str = sig_expire.strip_suffix(['d', 'w', 'm', 'y', 'D', 'W', 'M', 'Y']).unwrap_or(sig_expire);
+    if count.len() > 0 && count.chars().all(|c| c.is_ascii_digit()) {
+        return true;
+    }
+    return chrono::NaiveDate::parse_from_str(sig_expire, "%Y-%m-%d").is_ok()
+        || chrono::NaiveDateTime::parse_from_str(sig_expire, "%Y%m%dT%H%M%S").is_ok();
+}
+
+// check the signature(s) against the policy, a signer was only counted once even if it signed multiple times
+fn check_verify_policy(policy: &VerifyPolicy, signatures: Vec<VerifyResult>) -> PolicyResult {
+    let normalize = |fingerprint: &str| fingerprint.replace(" ", "").to_uppercase();
+    let allowed_signers: Vec<String> =
+        policy.allowed_signers.iter().map(|signer| normalize(signer)).collect();
+    let mut accepted_signers: Vec<String> = vec![];
+    let mut rejected: Vec<PolicyViolation> = vec![];
+
+    for signature in signatures.iter() {
+        let allowed: bool = [&signature.primary_fingerprint, &signature.fingerprint]
+            .iter()
+            .any(|fingerprint| match fingerprint {
+                Some(fingerprint) => allowed_signers.contains(&normalize(fingerprint)),
+                None => false,
+            });
+        let reason: Option<PolicyRejection> = match signature.status.as_str() {
+            "BADSIG" => Some(PolicyRejection::BadSignature),
+            "ERRSIG" => Some(PolicyRejection::VerificationError),
+            "REVKEYSIG" => Some(PolicyRejection::RevokedKey),
+            "EXPSIG" => Some(PolicyRejection::ExpiredSignature),
+            "EXPKEYSIG" if !policy.allow_expired_keys => Some(PolicyRejection::ExpiredKey),
+            _ if !signature.valid => Some(PolicyRejection::VerificationError),
+            _ if !allowed => Some(PolicyRejection::NotAllowedSigner),
+            _ => match &policy.min_trust_level {
+                Some(min_trust_level)
+                    if signature.trust_level.as_ref().map(trust_rank).unwrap_or(0)
+                        < trust_rank(min_trust_level) =>
+                {
+                    Some(PolicyRejection::TrustTooLow)
+                }
+                _ => None,
+            },
+        };
+        match reason {
+            Some(reason) => {
+                rejected.push(PolicyViolation {
+                    keyid: signature.keyid.clone(),
+                    primary_fingerprint: signature.primary_fingerprint.clone(),
+                    reason: reason,
+                });
+            }
+            None => {
+                let signer: String = signature
+                    .primary_fingerprint
+                    .clone()
+                    .or(signature.fingerprint.clone())
+                    .unwrap_or(signature.keyid.clone());
+                if !accepted_signers.contains(&signer) {
+                    accepted_signers.push(signer);
+                }
+            }
+        }
+    }
+
+    // each signature was already checked against the other requirement(s) before it was counted
+    return PolicyResult {
+        passed: accepted_signers.len() >= policy.min_signatures,
+        accepted_signers: accepted_signers,
+        rejected: rejected,
+        signatures: signatures,
+    };
+}
+
+// the trust level(s) ordered from the least to the most trusted
+fn trust_rank(trust_level: &TrustLevel) -> u8 {
+    match trust_level {
+        TrustLevel::Expired | TrustLevel::Never => 0,
+        TrustLevel::Undefined => 1,
+        TrustLevel::Marginal => 2,
+        TrustLevel::Fully => 3,
+        TrustLevel::Ultimate => 4,
     }
 }
 
@@ -1191,8 +3928,18 @@ pub struct EncryptOption {
     pub file: Option<File>,
     // file_path: path to file
     pub file_path: Option<String>,
-    // receipients: list of receipients keyid
+    // receipients: list of receipients keyid or recipient group name ( refer to GPG::add_group )
     pub recipients: Option<Vec<String>>,
+    // hidden_recipients: list of receipients keyid that will not be shown in the encrypted file
+    pub hidden_recipients: Option<Vec<String>>,
+    // recipient_files: list of path to public key file(s) to encrypt to, the key(s) do not need to be in the keyring
+    pub recipient_files: Option<Vec<String>>,
+    // throw_keyids: whether to hide the keyid of all receipients in the encrypted file
+    pub throw_keyids: bool,
+    // encrypt_to: list of keyid that will always be added as receipients ( ex: your own key ) [at least 1 receipient is required]
+    pub encrypt_to: Option<Vec<String>>,
+    // no_encrypt_to: whether to disable all encrypt-to keyid set in gpg.conf [can not be used together with encrypt_to]
+    pub no_encrypt_to: bool,
     // sign: whether to sign the file
     pub sign: bool,
     // sign_key: keyid to sign the file
@@ -1202,10 +3949,40 @@ pub struct EncryptOption {
     pub symmetric: bool,
     // symmetric_algo: symmetric algorithm to use [if not provided a highly ranked cipher willl be chosen]
     pub symmetric_algo: Option<String>,
+    // cipher_algo: cipher algorithm to force, even if it violate the receipients preferences [will be used over symmetric_algo]
+    pub cipher_algo: Option<CipherAlgo>,
+    // digest_algo: digest algorithm to force when signing
+    pub digest_algo: Option<DigestAlgo>,
+    // compress_algo: compression algorithm to use
+    pub compress_algo: Option<CompressAlgo>,
+    // compress_level: compression level to use, from 0 ( no compression ) to 9
+    pub compress_level: Option<u8>,
+    // aead_algo: AEAD mode to use [only supported from gpg 2.3]
+    pub aead_algo: Option<AeadAlgo>,
+    // force_ocb: whether to force AEAD ( OCB ) encryption regardless of the receipients preferences [only supported from gpg 2.3]
+    pub force_ocb: bool,
+    // compliance: packet format that gpg should follow ( ex: RFC4880 )
+    pub compliance: Option<Compliance>,
     // always_trust: whether to always trust keys
     pub always_trust: bool,
     // passphrase: passphrase to use for symmetric encryption [required if symmetric is true]
     pub passphrase: Option<String>,
+    // key_passphrase: passphrase for passphrase protected private key used for signing [required if sign is true with passphrase protected key]
+    pub key_passphrase: Option<String>,
+    // passphrase_provider: called for the passphrase of each key gpg need, key_passphrase will be ignored if provided
+    //                      [if passphrase was not provided, it will also be called for the symmetric passphrase]
+    pub passphrase_provider: Option<Arc<dyn PassphraseProvider>>,
+    // armor: whether to ascii armor the encrypted output, will use the armor set in GPG if not provided
+    pub armor: Option<bool>,
+    // textmode: whether to treat the input as text and sign / encrypt it with canonical line endings
+    pub textmode: bool,
+    // notations: notation(s) to add to the signature as (name, value, critical) [only used if sign is true]
+    //            the name of a user notation must be in the form of name@domain
+    pub notations: Vec<(String, String, bool)>,
+    // policy_url: url of the signature policy to add to the signature [only used if sign is true]
+    pub policy_url: Option<String>,
+    // keyserver_url: url of the preferred keyserver to add to the signature [only used if sign is true]
+    pub keyserver_url: Option<String>,
     // output: path to write the encrypted output,
     //         will use the default output dir set in GPG if not provided and
     //         with file name as [<encryption_type>_encrypted_file_<datetime>.<extension>]
@@ -1226,12 +4003,31 @@ impl EncryptOption {
             file: file,
             file_path: file_path,
             recipients: Some(recipients),
+            hidden_recipients: None,
+            recipient_files: None,
+            throw_keyids: false,
+            encrypt_to: None,
+            no_encrypt_to: false,
             sign: false,
             sign_key: None,
             symmetric: false,
             symmetric_algo: None,
+            cipher_algo: None,
+            digest_algo: None,
+            compress_algo: None,
+            compress_level: None,
+            aead_algo: None,
+            force_ocb: false,
+            compliance: None,
             always_trust: true,
             passphrase: None,
+            key_passphrase: None,
+            passphrase_provider: None,
+            armor: None,
+            textmode: false,
+            notations: vec![],
+            policy_url: None,
+            keyserver_url: None,
             output: output,
             extra_args: None,
         };
@@ -1249,12 +4045,31 @@ impl EncryptOption {
             file: file,
             file_path: file_path,
             recipients: None,
+            hidden_recipients: None,
+            recipient_files: None,
+            throw_keyids: false,
+            encrypt_to: None,
+            no_encrypt_to: false,
             sign: false,
             sign_key: None,
             symmetric: true,
             symmetric_algo: symmetric_algo,
+            cipher_algo: None,
+            digest_algo: None,
+            compress_algo: None,
+            compress_level: None,
+            aead_algo: None,
+            force_ocb: false,
+            compliance: None,
             always_trust: true,
             passphrase: Some(passphrase),
+            key_passphrase: None,
+            passphrase_provider: None,
+            armor: None,
+            textmode: false,
+            notations: vec![],
+            policy_url: None,
+            keyserver_url: None,
             output: output,
             extra_args: None,
         };
@@ -1273,12 +4088,31 @@ impl EncryptOption {
             file: file,
             file_path: file_path,
             recipients: recipients,
+            hidden_recipients: None,
+            recipient_files: None,
+            throw_keyids: false,
+            encrypt_to: None,
+            no_encrypt_to: false,
             sign: false,
             sign_key: None,
             symmetric: true,
             symmetric_algo: symmetric_algo,
+            cipher_algo: None,
+            digest_algo: None,
+            compress_algo: None,
+            compress_level: None,
+            aead_algo: None,
+            force_ocb: false,
+            compliance: None,
             always_trust: true,
             passphrase: Some(passphrase),
+            key_passphrase: None,
+            passphrase_provider: None,
+            armor: None,
+            textmode: false,
+            notations: vec![],
+            policy_url: None,
+            keyserver_url: None,
             output: output,
             extra_args: None,
         };
@@ -1307,6 +4141,11 @@ pub struct DecryptOption {
     pub passphrase: Option<String>,
     // key_passphrase: passphrase if file is key encrypted and need passphrase protected private key to decrypt
     pub key_passphrase: Option<String>,
+    // show_session_key: whether to retrieve the session key of the file ( refer to decrypt_with_result and decrypt_with_session_key )
+    pub show_session_key: bool,
+    // passphrase_provider: called for the passphrase of each key gpg try to decrypt with, key_passphrase will be ignored if provided
+    //                      [if passphrase was not provided, it will also be called for the symmetric passphrase]
+    pub passphrase_provider: Option<Arc<dyn PassphraseProvider>>,
     // output: path to write the decrypted output,
     //         will use the default output dir with file name as [decrypted_file_<datetime>.<extension>] set in GPG if not provided
     pub output: Option<String>,
@@ -1331,6 +4170,8 @@ impl DecryptOption {
             always_trust: true,
             passphrase: None,
             key_passphrase: key_passphrase,
+            passphrase_provider: None,
+            show_session_key: false,
             output: output,
             extra_args: None,
         };
@@ -1350,12 +4191,105 @@ impl DecryptOption {
             always_trust: true,
             passphrase: Some(passphrase),
             key_passphrase: None,
+            passphrase_provider: None,
+            show_session_key: false,
+            output: output,
+            extra_args: None,
+        };
+    }
+
+    // for with_provider, it will be a decryption with any secret key ( or the symmetric passphrase ) gpg can use,
+    // each passphrase will be asked from the passphrase provider and always trust will be true
+    pub fn with_provider(
+        file: Option<File>,
+        file_path: Option<String>,
+        passphrase_provider: Arc<dyn PassphraseProvider>,
+        output: Option<String>,
+    ) -> DecryptOption {
+        return DecryptOption {
+            file: file,
+            file_path: file_path,
+            recipient: None,
+            always_trust: true,
+            passphrase: None,
+            key_passphrase: None,
+            passphrase_provider: Some(passphrase_provider),
+            show_session_key: false,
+            output: output,
+            extra_args: None,
+        };
+    }
+
+    // for from_message, it will be a decryption with passphrase if the message was only encrypted symmetrically,
+    // otherwise a decryption with secret key of the first recipient ( refer to GPG::inspect_message )
+    // [passphrase will be used as the key_passphrase for decryption with secret key]
+    pub fn from_message(
+        message_info: &MessageInfo,
+        file: Option<File>,
+        file_path: Option<String>,
+        passphrase: Option<String>,
+        output: Option<String>,
+    ) -> DecryptOption {
+        if message_info.symmetric && message_info.recipients.is_empty() {
+            return DecryptOption::with_symmetric(
+                file,
+                file_path,
+                passphrase.unwrap_or("".to_string()),
+                output,
+            );
+        }
+        // a hidden recipient ( 0000000000000000 ) can not be passed to gpg, gpg will try the secret keys instead
+        let recipient: Option<String> = message_info
+            .recipients
+            .iter()
+            .find(|recipient| recipient.as_str() != "0000000000000000")
+            .cloned();
+        return DecryptOption {
+            file: file,
+            file_path: file_path,
+            recipient: recipient,
+            always_trust: true,
+            passphrase: None,
+            key_passphrase: passphrase,
+            passphrase_provider: None,
+            show_session_key: false,
             output: output,
             extra_args: None,
         };
     }
 }
 
+// a struct to represent a policy for verifying signature(s) from multiple signers
+// use this to construct the policy that will be pass to verify_with_policy
+//*******************************************************
+
+//         RELATED TO GPG VERIFICATION POLICY
+
+//*******************************************************
+#[derive(Debug, Clone)]
+pub struct VerifyPolicy {
+    // allowed_signers: fingerprint(s) of the primary key ( or signing subkey ) of the signer(s) allowed to sign [at least 1 is required]
+    pub allowed_signers: Vec<String>,
+    // min_signatures: minimum number of allowed signer(s) that must have a good signature [must be at least 1]
+    pub min_signatures: usize,
+    // min_trust_level: minimum trust level of the signer key, the trust level will not be checked if not provided
+    pub min_trust_level: Option<TrustLevel>,
+    // allow_expired_keys: whether a good signature made with a key that has since expired will be counted
+    pub allow_expired_keys: bool,
+}
+
+impl VerifyPolicy {
+    // for new, the trust level will not be checked and expired signer keys will not be counted
+    pub fn new(allowed_signers: Vec<String>, min_signatures: usize) -> VerifyPolicy {
+        return VerifyPolicy {
+            allowed_signers: allowed_signers,
+            min_signatures: min_signatures,
+            min_trust_level: None,
+            allow_expired_keys: false,
+        };
+    }
+}
+
 // a struct to represent GPG Signing Option
 // use this to construct the options for GPG Signing
 // that will be pass to the signing method
@@ -1372,16 +4306,34 @@ pub struct SignOption {
     pub file_path: Option<String>,
     // keyid: keyid for signing
     pub keyid: Option<String>,
+    // signers: keyid(s) of additional signing key(s), each key will add its own signature to the same output
+    //          [the passphrase of each protected key should come from the passphrase_provider]
+    pub signers: Vec<String>,
     // key_passphrase: required for passphrase protected private key
     pub key_passphrase: Option<String>,
-    // clearsign: Whether to use clear signing
-    pub clearsign: bool,
-    // detach: Whether to produce a detached signature.
-    pub detach: bool,
+    // passphrase_provider: called for the passphrase of the signing key, key_passphrase will be ignored if provided
+    pub passphrase_provider: Option<Arc<dyn PassphraseProvider>>,
+    // mode: the kind of signature to produce ( inline, clearsigned, detached or detached over text )
+    pub mode: SignMode,
+    // armor: whether to ascii armor the signature, will use the armor set in GPG if not provided [clearsigned output is always armored]
+    pub armor: Option<bool>,
+    // textmode: whether to treat the input as text and sign it with canonical line endings [always on for DetachedText]
+    pub textmode: bool,
+    // digest_algo: digest algorithm to use for the signature
+    pub digest_algo: Option<DigestAlgo>,
+    // sig_expire: expiration of the signature, 0 ( never ), <n>[d|w|m|y] ( ex: 2y ) or an ISO date ( ex: 2030-01-01 )
+    pub sig_expire: Option<String>,
+    // notations: notation(s) to add to the signature as (name, value, critical)
+    //            the name of a user notation must be in the form of name@domain
+    pub notations: Vec<(String, String, bool)>,
+    // policy_url: url of the signature policy to add to the signature
+    pub policy_url: Option<String>,
+    // keyserver_url: url of the preferred keyserver to add to the signature
+    pub keyserver_url: Option<String>,
     // output: path to write the detached signature or embedded sign file
     //         if output not specified:
-    //           will use the default output dir with file name as [<sign_type>_<datetime>.<sig or gpg>] set in GPG if
-    //           file is provided instead of file_path or detached signature
+    //           will use the default output dir set in GPG with file name as [<mode>_sign_<datetime>.<extension>]
+    //           where extension is asc if armored ( or clearsigned ), otherwise sig for detached and gpg for inline
     pub output: Option<String>,
     // extra_args: extra arguments to pass to gpg
     pub extra_args: Option<Vec<String>>,
@@ -1401,9 +4353,17 @@ impl SignOption {
             file: file,
             file_path: file_path,
             keyid: Some(keyid),
+            signers: vec![],
             key_passphrase: key_passphrase,
-            clearsign: true,
-            detach: false,
+            passphrase_provider: None,
+            mode: SignMode::Clear,
+            armor: None,
+            textmode: false,
+            digest_algo: None,
+            sig_expire: None,
+            notations: vec![],
+            policy_url: None,
+            keyserver_url: None,
             output: output,
             extra_args: None,
         };
@@ -1421,9 +4381,46 @@ impl SignOption {
             file: file,
             file_path: file_path,
             keyid: Some(keyid),
+            signers: vec![],
             key_passphrase: key_passphrase,
-            clearsign: false,
-            detach: true,
+            passphrase_provider: None,
+            mode: SignMode::Detached,
+            armor: None,
+            textmode: false,
+            digest_algo: None,
+            sig_expire: None,
+            notations: vec![],
+            policy_url: None,
+            keyserver_url: None,
+            output: output,
+            extra_args: None,
+        };
+    }
+
+    // for with_signers, all the signing key(s) will sign the same output in 1 pass with the mode provided
+    pub fn with_signers(
+        file: Option<File>,
+        file_path: Option<String>,
+        signers: Vec<String>,
+        passphrase_provider: Option<Arc<dyn PassphraseProvider>>,
+        mode: SignMode,
+        output: Option<String>,
+    ) -> SignOption {
+        return SignOption {
+            file: file,
+            file_path: file_path,
+            keyid: None,
+            signers: signers,
+            key_passphrase: None,
+            passphrase_provider: passphrase_provider,
+            mode: mode,
+            armor: None,
+            textmode: false,
+            digest_algo: None,
+            sig_expire: None,
+            notations: vec![],
+            policy_url: None,
+            keyserver_url: None,
             output: output,
             extra_args: None,
         };
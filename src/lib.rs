@@ -1,4 +1,7 @@
 pub mod gnupg;
+pub mod passphrase;
 #[doc(hidden)]
 pub mod process;
+pub mod stream;
 pub mod utils;
+pub mod wkd;
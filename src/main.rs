@@ -1,5 +1,7 @@
 pub mod gnupg;
+pub mod passphrase;
 pub mod process;
+pub mod stream;
 pub mod utils;
 
 fn main(){
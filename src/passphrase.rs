@@ -0,0 +1,125 @@
+use std::{
+    collections::HashMap,
+    fmt::{Debug, Formatter},
+    sync::Arc,
+};
+
+use crate::gnupg::GPG;
+use crate::utils::errors::GPGError;
+
+//*******************************************************
+
+//           RELATED TO PASSPHRASE PROVIDER
+
+//*******************************************************
+// a request for passphrase, made every time gpg prompt for a passphrase ( NEED_PASSPHRASE / GET_HIDDEN )
+#[derive(Debug, Clone)]
+pub struct PassphraseRequest {
+    // keyid of the ( sub )key that need the passphrase, empty for symmetric passphrase
+    pub keyid: String,
+    // keyid of the primary key of the ( sub )key, empty for symmetric passphrase
+    pub main_keyid: String,
+    // keygrip of the ( sub )key, None if it was not found in the local secret keys
+    pub keygrip: Option<String>,
+    // the user id of the key that need the passphrase
+    pub uid_hint: Option<String>,
+    // if the passphrase was for symmetric encryption / decryption
+    pub symmetric: bool,
+    // number of time the passphrase was requested for the same key ( start from 1 )
+    pub attempt: u32,
+}
+
+// a provider that will be called back for the passphrase of each key gpg need
+// return None if the passphrase is not known, gpg will then skip or fail on the key
+pub trait PassphraseProvider: Send + Sync {
+    fn get_passphrase(&self, request: &PassphraseRequest) -> Option<String>;
+}
+
+// any closure can be used as a passphrase provider
+impl<F> PassphraseProvider for F
+where
+    F: Fn(&PassphraseRequest) -> Option<String> + Send + Sync,
+{
+    fn get_passphrase(&self, request: &PassphraseRequest) -> Option<String> {
+        return self(request);
+    }
+}
+
+#[doc(hidden)]
+impl Debug for dyn PassphraseProvider {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "PassphraseProvider")
+    }
+}
+
+// the context used by the process to answer the passphrase prompt(s) of gpg
+#[doc(hidden)]
+#[derive(Clone)]
+pub struct PassphraseContext {
+    // provider: the passphrase provider to call back
+    // gpg: used to look up the keygrip of the ( sub )key gpg prompt the passphrase for
+    // keygrips: keyid -> keygrip of the ( sub )key(s) already looked up, None if it was not found
+    // symmetric_passphrase: if provided, will be used for symmetric passphrase prompt instead of calling the provider
+    pub provider: Arc<dyn PassphraseProvider>,
+    pub gpg: GPG,
+    pub keygrips: HashMap<String, Option<String>>,
+    pub symmetric_passphrase: Option<String>,
+}
+
+#[doc(hidden)]
+impl PassphraseContext {
+    pub fn new(
+        provider: Arc<dyn PassphraseProvider>,
+        gpg: GPG,
+        symmetric_passphrase: Option<String>,
+    ) -> PassphraseContext {
+        return PassphraseContext {
+            provider: provider,
+            gpg: gpg,
+            keygrips: HashMap::new(),
+            symmetric_passphrase: symmetric_passphrase,
+        };
+    }
+
+    // the keygrip was only looked up when gpg prompt for the passphrase of the ( sub )key,
+    // so only the key(s) involved in the operation will be listed
+    pub fn lookup_keygrip(&mut self, keyid: &str) -> Result<Option<String>, GPGError> {
+        if let Some(keygrip) = self.keygrips.get(keyid) {
+            return Ok(keygrip.clone());
+        }
+        let keys = match self.gpg.list_keys(true, Some(vec![keyid.to_string()]), false) {
+            Ok(keys) => keys,
+            Err(e) => {
+                return Err(e);
+            }
+        };
+        let mut keygrip: Option<String> = None;
+        for key in keys {
+            if key.keyid == keyid && is_keygrip_available(&key.keygrip) {
+                keygrip = Some(key.keygrip.clone());
+            }
+            for subkey in key.subkeys {
+                if subkey.keyid == keyid && is_keygrip_available(&subkey.keygrip) {
+                    keygrip = Some(subkey.keygrip.clone());
+                }
+            }
+        }
+        self.keygrips.insert(keyid.to_string(), keygrip.clone());
+        return Ok(keygrip);
+    }
+
+    pub fn get_passphrase(&self, request: &PassphraseRequest) -> String {
+        if request.symmetric && self.symmetric_passphrase.is_some() {
+            return self.symmetric_passphrase.clone().unwrap();
+        }
+        return self
+            .provider
+            .get_passphrase(request)
+            .unwrap_or("".to_string());
+    }
+}
+
+// the keygrip of a key without its secret part in the keyring ( ex: a stub ) was not available
+fn is_keygrip_available(keygrip: &str) -> bool {
+    return !keygrip.is_empty() && keygrip != "Unavailable";
+}
@@ -1,12 +1,13 @@
 use std::{
     collections::HashMap,
     fs::File,
-    io::{Error, Read, Write},
+    io::{BufRead, BufReader, Error, ErrorKind, Read, Write},
     process::{Child, ChildStderr, ChildStdin, ChildStdout, Command, ExitStatus, Stdio},
     sync::{Arc, Mutex},
     thread::{self, JoinHandle},
 };
 
+use crate::passphrase::{PassphraseContext, PassphraseRequest};
 use crate::utils::{
     enums::Operation,
     errors::{GPGError, GPGErrorType},
@@ -14,7 +15,22 @@ use crate::utils::{
     utils::get_file_obj,
 };
 
+#[cfg(unix)]
+use std::os::unix::{
+    io::{AsRawFd, FromRawFd},
+    process::CommandExt,
+};
+
 const BUFFER_SIZE: usize = 8192;
+// the passphrase was delivered on its own channel ( fd 3 ) so it will not be mixed with the data on stdin
+#[cfg(unix)]
+pub const PASSPHRASE_FD: i32 = 3;
+// on non-unix it can only be stdin, see start_process for the limitation
+#[cfg(not(unix))]
+pub const PASSPHRASE_FD: i32 = 0;
+// the session key was delivered on its own channel ( fd 4 ), apart from the passphrase and the data on stdin
+#[cfg(unix)]
+pub const SESSION_KEY_FD: i32 = 4;
 
 //*******************************************************
 
@@ -36,50 +52,320 @@ pub fn handle_cmd_io(
     file_needed: bool,
     ops: Operation,
 ) -> Result<CmdResult, GPGError> {
-    let mut write_thread: Option<JoinHandle<()>> = None;
-    let passphrase: Option<String> = if passphrase.is_some() {passphrase.clone()} else {Some("".to_string())};
-    let process: Result<Child, Error> = start_process(
-        Some(cmd_args.unwrap()),
-        passphrase.clone(),
+    return handle_cmd_io_output(
+        cmd_args,
+        passphrase,
         version,
         homedir,
         options,
         env,
+        file,
+        file_path,
+        byte_input,
+        write,
+        file_needed,
+        ops,
+        None,
+        None,
     );
-    let mut cmd_process = match process {
-        Ok(child) => child,
-        Err(e) => {
-            return Err(GPGError::new(
-                GPGErrorType::FailedToStartProcess(e.to_string()),
-                None,
-            ))
-        }
-    };
-    let mut stdin: ChildStdin = cmd_process.stdin.take().unwrap();
-    match passphrase {
-        Some(passphrase) => {
-            let _ = stdin.write_all(passphrase.as_bytes());
-            let _ = stdin.write_all("\n".as_bytes());
-        }
-        None => {}
-    }
+}
+
+// same as handle_cmd_io, but the stdout of the process will be written to the output provided
+// instead of being collected into the raw_data of the result
+pub fn handle_cmd_io_output(
+    cmd_args: Option<Vec<String>>,
+    passphrase: Option<String>,
+    version: f32,
+    homedir: String,
+    options: Option<Vec<String>>,
+    env: Option<HashMap<String, String>>,
+    file: Option<File>,
+    file_path: Option<String>,
+    byte_input: Option<Vec<u8>>,
+    write: bool,
+    file_needed: bool,
+    ops: Operation,
+    provider: Option<PassphraseContext>,
+    output: Option<&mut (dyn Write + Send)>,
+) -> Result<CmdResult, GPGError> {
+    let mut reader: Option<Box<dyn Read + Send>> = None;
     if write {
         let file: Result<File, GPGError> = get_file_obj(file, file_path);
         match file {
             Ok(file) => {
-                write_thread = Some(start_writing_process(Some(file), byte_input, stdin));
+                reader = Some(Box::new(file));
             }
             Err(err) => {
                 if file_needed {
                     return Err(err);
                 }
-                write_thread = Some(start_writing_process(None, byte_input, stdin));
             }
         }
     }
+    return run_cmd_io(
+        cmd_args, passphrase, version, homedir, options, env, reader, byte_input, write, ops, provider,
+        output,
+    );
+}
+
+// stream the input ( any reader ) into the process and the stdout of the process into the output ( any writer ).
+// The input was streamed on a scoped thread, so it do not need to outlive the call ( ex: a borrowed slice )
+pub fn handle_cmd_stream(
+    cmd_args: Option<Vec<String>>,
+    passphrase: Option<String>,
+    version: f32,
+    homedir: String,
+    options: Option<Vec<String>>,
+    env: Option<HashMap<String, String>>,
+    input: &mut (dyn Read + Send),
+    ops: Operation,
+    provider: Option<PassphraseContext>,
+    output: &mut (dyn Write + Send),
+) -> Result<CmdResult, GPGError> {
+    return run_cmd_io(
+        cmd_args,
+        passphrase,
+        version,
+        homedir,
+        options,
+        env,
+        Some(Box::new(input)),
+        None,
+        true,
+        ops,
+        provider,
+        Some(output),
+    );
+}
+
+// verify the input ( any reader ) with embedded signature, or against a detached signature held in memory,
+// the signature was delivered on its own channel ( fd 3 ) while the data was streamed through stdin.
+// The input was streamed on a scoped thread, so it do not need to outlive the call ( ex: a borrowed request body )
+pub fn handle_cmd_verify_stream(
+    cmd_args: Option<Vec<String>>,
+    version: f32,
+    homedir: String,
+    options: Option<Vec<String>>,
+    env: Option<HashMap<String, String>>,
+    input: &mut (dyn Read + Send),
+    signature: Option<Vec<u8>>,
+    ops: Operation,
+) -> Result<CmdResult, GPGError> {
+    // signature: the detached signature, None if the signature was embedded in the input
+
+    let mut args: Vec<String> = vec![];
+    if signature.is_some() {
+        args.push("--enable-special-filenames".to_string());
+    }
+    args.append(&mut cmd_args.unwrap_or_default());
+    if signature.is_some() {
+        // -&<fd> make gpg read the signature from the fd instead of a file
+        args.append(&mut vec!["--".to_string(), format!("-&{}", PASSPHRASE_FD), "-".to_string()]);
+    }
+    let cmd_args: Vec<String> = generate_cmd_args(Some(args), None, version, homedir, options);
+    let mut command: Command = build_command(cmd_args, env);
+
+    let process: Result<(Child, Option<Box<dyn Write + Send>>), Error> = match signature {
+        Some(_) => {
+            #[cfg(unix)]
+            {
+                match spawn_with_extra_fd(&mut command, PASSPHRASE_FD) {
+                    Ok((child, writer)) => {
+                        let writer: Box<dyn Write + Send> = Box::new(writer);
+                        Ok((child, Some(writer)))
+                    }
+                    Err(e) => Err(e),
+                }
+            }
+            #[cfg(not(unix))]
+            {
+                Err(Error::new(
+                    ErrorKind::Unsupported,
+                    "verifying a detached signature from memory was only supported on unix",
+                ))
+            }
+        }
+        None => match command.spawn() {
+            Ok(child) => Ok((child, None)),
+            Err(e) => Err(e),
+        },
+    };
+    let (mut cmd_process, signature_writer) = match process {
+        Ok(process) => process,
+        Err(e) => {
+            return Err(GPGError::new(
+                GPGErrorType::FailedToStartProcess(e.to_string()),
+                None,
+            ));
+        }
+    };
+
+    let stdin: ChildStdin = cmd_process.stdin.take().unwrap();
+    let mut result = CmdResult::init(ops);
+    let write_result: Result<(), GPGError> = thread::scope(|s| {
+        if let (Some(writer), Some(signature)) = (signature_writer, signature) {
+            // gpg read the whole signature before the data, so it was written on its own thread
+            s.spawn(move || {
+                return write_to_stdin(None, Some(signature), writer);
+            });
+        }
+        let write_thread = s.spawn(move || {
+            return write_to_stdin(Some(Box::new(input)), None, stdin);
+        });
+        let share_result: Arc<Mutex<&mut CmdResult>> = Arc::new(Mutex::new(&mut result));
+        collect_cmd_output_response(cmd_process, share_result, None, None, None);
+        return write_thread.join().unwrap_or(Ok(()));
+    });
+
+    // the process will only see the end of the input if the input could not be read,
+    // so the result should not be considered as verified
+    if let Err(e) = write_result {
+        if let GPGErrorType::ReadFailError(err) = e.error_type {
+            return Err(GPGError::new(
+                GPGErrorType::ReadFailError(err),
+                Some(result),
+            ));
+        }
+    }
+    if result.is_success() {
+        return Ok(result);
+    }
+    return Err(GPGError::new(
+        GPGErrorType::GPGProcessError(result.get_error_message()),
+        Some(result),
+    ));
+}
+
+// decrypt the input with the session key of the data instead of the secret key or passphrase,
+// the session key was delivered on its own channel ( fd 4 ) while the data was streamed through stdin
+pub fn handle_cmd_session_key(
+    cmd_args: Option<Vec<String>>,
+    version: f32,
+    homedir: String,
+    options: Option<Vec<String>>,
+    env: Option<HashMap<String, String>>,
+    file: Option<File>,
+    file_path: Option<String>,
+    session_key: String,
+    ops: Operation,
+) -> Result<CmdResult, GPGError> {
+    let input: Box<dyn Read + Send> = match get_file_obj(file, file_path) {
+        Ok(file) => Box::new(file),
+        Err(e) => {
+            return Err(e);
+        }
+    };
+
+    #[cfg(unix)]
+    {
+        let mut args: Vec<String> = vec![
+            "--override-session-key-fd".to_string(),
+            SESSION_KEY_FD.to_string(),
+        ];
+        args.append(&mut cmd_args.unwrap_or_default());
+        let cmd_args: Vec<String> = generate_cmd_args(Some(args), None, version, homedir, options);
+        let mut command: Command = build_command(cmd_args, env);
+        let (mut cmd_process, mut writer) = match spawn_with_extra_fd(&mut command, SESSION_KEY_FD) {
+            Ok(process) => process,
+            Err(e) => {
+                return Err(GPGError::new(
+                    GPGErrorType::FailedToStartProcess(e.to_string()),
+                    None,
+                ));
+            }
+        };
+        let _ = writer.write_all(session_key.as_bytes());
+        let _ = writer.write_all("\n".as_bytes());
+        drop(writer);
+        let stdin: ChildStdin = cmd_process.stdin.take().unwrap();
+        let write_thread: JoinHandle<Result<(), GPGError>> =
+            start_writing_process(Some(input), None, stdin);
+        let mut result = CmdResult::init(ops);
+        let share_result: Arc<Mutex<&mut CmdResult>> = Arc::new(Mutex::new(&mut result));
+        collect_cmd_output_response(cmd_process, share_result, Some(write_thread), None, None);
+        if result.is_success() {
+            return Ok(result);
+        }
+        return Err(GPGError::new(
+            GPGErrorType::GPGProcessError(result.get_error_message()),
+            Some(result),
+        ));
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = (cmd_args, version, homedir, options, env, input, session_key, ops);
+        return Err(GPGError::new(
+            GPGErrorType::FailedToStartProcess(
+                "decrypting with a session key was only supported on unix".to_string(),
+            ),
+            None,
+        ));
+    }
+}
+
+fn run_cmd_io(
+    cmd_args: Option<Vec<String>>,
+    passphrase: Option<String>,
+    version: f32,
+    homedir: String,
+    options: Option<Vec<String>>,
+    env: Option<HashMap<String, String>>,
+    reader: Option<Box<dyn Read + Send + '_>>,
+    byte_input: Option<Vec<u8>>,
+    write: bool,
+    ops: Operation,
+    provider: Option<PassphraseContext>,
+    output: Option<&mut (dyn Write + Send)>,
+) -> Result<CmdResult, GPGError> {
+    let mut prompt: Option<(PassphraseContext, Box<dyn Write + Send>)> = None;
+    let process: Result<Child, Error> = match provider {
+        Some(provider) => {
+            // the passphrase(s) will be answered through the provider when gpg prompt for it
+            match start_process_with_provider(cmd_args, version, homedir, options, env) {
+                Ok((child, channel)) => {
+                    prompt = Some((provider, channel));
+                    Ok(child)
+                }
+                Err(e) => Err(e),
+            }
+        }
+        None => {
+            let passphrase: Option<String> = if passphrase.is_some() {passphrase.clone()} else {Some("".to_string())};
+            start_process(cmd_args, passphrase, version, homedir, options, env, write)
+        }
+    };
+    let mut cmd_process = match process {
+        Ok(child) => child,
+        Err(e) => {
+            return Err(GPGError::new(
+                GPGErrorType::FailedToStartProcess(e.to_string()),
+                None,
+            ))
+        }
+    };
+    let stdin: ChildStdin = cmd_process.stdin.take().unwrap();
     let mut result = CmdResult::init(ops);
-    let share_result: Arc<Mutex<&mut CmdResult>> = Arc::new(Mutex::new(&mut result));
-    collect_cmd_output_response(cmd_process, share_result, write_thread);
+    // the input was written on a scoped thread, so the reader do not need to be 'static
+    let write_result: Result<(), GPGError> = thread::scope(|s| {
+        let mut write_thread = None;
+        if write {
+            write_thread = Some(s.spawn(move || {
+                return write_to_stdin(reader, byte_input, stdin);
+            }));
+        }
+        let share_result: Arc<Mutex<&mut CmdResult>> = Arc::new(Mutex::new(&mut result));
+        collect_cmd_output_response(cmd_process, share_result, None, prompt, output);
+        match write_thread {
+            Some(write_thread) => {
+                return write_thread.join().unwrap_or(Ok(()));
+            }
+            None => {
+                return Ok(());
+            }
+        }
+    });
+    check_write_result(&mut result, write_result);
+    check_decrypt_return_code(&mut result);
     if result.is_success() {
         return Ok(result);
     }
@@ -89,6 +375,320 @@ pub fn handle_cmd_io(
     ));
 }
 
+// not every failure of a decryption come with a status ( ex. CRC error of the armor ),
+// so the decryption was only a success if gpg also exited cleanly, same as GpgDecryptReader
+fn check_decrypt_return_code(result: &mut CmdResult) {
+    if result.operation != Operation::Decrypt || !result.is_success() {
+        return;
+    }
+    if result.return_code != Some(0) {
+        result.success = false;
+        result.status_message = Some(format!("gpg exited with {}", result.return_code.unwrap_or(-1)));
+    }
+}
+
+// settings of one side of the pipe in handle_cmd_pipe
+pub struct PipeCmd {
+    // arguments of the gpg command
+    pub args: Option<Vec<String>>,
+    // passphrase for the command, None if it was not needed
+    pub passphrase: Option<String>,
+    // passphrase provider, used instead of the passphrase when set ( only for the source )
+    pub provider: Option<PassphraseContext>,
+    // version, homedir, options and env of the GPG the command was run against
+    pub version: f32,
+    pub homedir: String,
+    pub options: Option<Vec<String>>,
+    pub env: Option<HashMap<String, String>>,
+    // operation recorded in the CmdResult
+    pub ops: Operation,
+}
+
+// spawn 2 Command(s) and pipe the output of the source process into the input of the target process
+// ex) export from one homedir and import into another, no intermediate file will be written
+pub fn handle_cmd_pipe(source: PipeCmd, target: PipeCmd) -> Result<(CmdResult, CmdResult), GPGError> {
+    // source: the command whose stdout was piped, its stdin will not be used
+    // target: the command that read the piped data from its stdin
+
+    let mut source_prompt: Option<(PassphraseContext, Box<dyn Write + Send>)> = None;
+    let process: Result<Child, Error> = match source.provider {
+        Some(provider) => {
+            // the passphrase(s) of the source will be answered through the provider when gpg prompt for it
+            match start_process_with_provider(
+                source.args,
+                source.version,
+                source.homedir,
+                source.options,
+                source.env,
+            ) {
+                Ok((child, channel)) => {
+                    source_prompt = Some((provider, channel));
+                    Ok(child)
+                }
+                Err(e) => Err(e),
+            }
+        }
+        None => {
+            let source_passphrase: Option<String> = if source.passphrase.is_some() {source.passphrase.clone()} else {Some("".to_string())};
+            start_process(
+                source.args,
+                source_passphrase,
+                source.version,
+                source.homedir,
+                source.options,
+                source.env,
+                false,
+            )
+        }
+    };
+    let mut source_process = match process {
+        Ok(child) => child,
+        Err(e) => {
+            return Err(GPGError::new(
+                GPGErrorType::FailedToStartProcess(e.to_string()),
+                None,
+            ))
+        }
+    };
+    let process: Result<Child, Error> = start_process(
+        target.args,
+        target.passphrase,
+        target.version,
+        target.homedir,
+        target.options,
+        target.env,
+        true,
+    );
+    let mut target_process = match process {
+        Ok(child) => child,
+        Err(e) => {
+            let _ = source_process.kill();
+            let _ = source_process.wait();
+            return Err(GPGError::new(
+                GPGErrorType::FailedToStartProcess(e.to_string()),
+                None,
+            ));
+        }
+    };
+
+    // the source process do not need any input
+    drop(source_process.stdin.take());
+
+    let mut source_stdout: ChildStdout = source_process.stdout.take().unwrap();
+    let source_stderr: ChildStderr = source_process.stderr.take().unwrap();
+    let mut target_stdin: ChildStdin = target_process.stdin.take().unwrap();
+    let write_thread: JoinHandle<Result<(), GPGError>> = thread::spawn(move || {
+        let _ = std::io::copy(&mut source_stdout, &mut target_stdin);
+        drop(target_stdin);
+        return Ok(());
+    });
+
+    let mut source_result = CmdResult::init(source.ops);
+    let mut target_result = CmdResult::init(target.ops);
+    thread::scope(|s| {
+        s.spawn(|| {
+            let share_result: Arc<Mutex<&mut CmdResult>> = Arc::new(Mutex::new(&mut source_result));
+            match source_prompt {
+                Some((provider, channel)) => {
+                    read_cmd_response_with_provider(source_stderr, share_result, provider, channel);
+                }
+                None => {
+                    read_cmd_response(source_stderr, share_result);
+                }
+            }
+        });
+        let share_result: Arc<Mutex<&mut CmdResult>> = Arc::new(Mutex::new(&mut target_result));
+        collect_cmd_output_response(target_process, share_result, Some(write_thread), None, None);
+    });
+    let exit_code: i32 = match source_process.wait() {
+        Ok(status) => status.code().unwrap_or(-1),
+        Err(_) => -1,
+    };
+    source_result.set_return_code(exit_code);
+
+    if !source_result.is_success() {
+        return Err(GPGError::new(
+            GPGErrorType::GPGProcessError(source_result.get_error_message()),
+            Some(source_result),
+        ));
+    }
+    if !target_result.is_success() {
+        return Err(GPGError::new(
+            GPGErrorType::GPGProcessError(target_result.get_error_message()),
+            Some(target_result),
+        ));
+    }
+    return Ok((source_result, target_result));
+}
+
+//*******************************************************
+
+//             RELATED TO GPGTAR PROCESS
+
+//*******************************************************
+//*****************************************************************************************
+//    NOTE: gpgtar do not pass the passphrase fd to the gpg it spawn, so gpgtar was only
+//          used for the tarball ( --skip-crypto ) and the encryption / decryption was done
+//          by gpg, the homedir, env and passphrase handling will stay the same as handle_cmd_io
+//******************************************************************************************
+
+// create a tarball with gpgtar and stream it into gpg
+pub fn handle_tar_create(
+    tar_args: Vec<String>,
+    cmd_args: Option<Vec<String>>,
+    passphrase: Option<String>,
+    version: f32,
+    homedir: String,
+    options: Option<Vec<String>>,
+    env: Option<HashMap<String, String>>,
+    ops: Operation,
+    provider: Option<PassphraseContext>,
+) -> Result<CmdResult, GPGError> {
+    // tar_args: arguments to be passed to gpgtar ( --create --skip-crypto will be added )
+
+    let mut args: Vec<String> = vec![
+        "gpgtar".to_string(),
+        "--create".to_string(),
+        "--skip-crypto".to_string(),
+    ];
+    args.append(&mut tar_args.clone());
+    let mut tar_process: Child = match build_command(args, env.clone()).spawn() {
+        Ok(child) => child,
+        Err(e) => {
+            return Err(GPGError::new(
+                GPGErrorType::FailedToStartProcess(e.to_string()),
+                None,
+            ))
+        }
+    };
+    drop(tar_process.stdin.take());
+    let mut tar_stdout: ChildStdout = tar_process.stdout.take().unwrap();
+    let tar_reader: JoinHandle<String> = read_tar_output(tar_process.stderr.take().unwrap());
+
+    let result: Result<CmdResult, GPGError> = handle_cmd_stream(
+        cmd_args,
+        passphrase,
+        version,
+        homedir,
+        options,
+        env,
+        &mut tar_stdout,
+        ops,
+        provider,
+        &mut std::io::sink(),
+    );
+    let (_, tar_error) = wait_tar_process(tar_process, tar_reader, None);
+    return check_tar_result(result, tar_error);
+}
+
+// decrypt with gpg and stream the decrypted tarball into gpgtar, the stdout of gpgtar will be returned
+pub fn handle_tar_extract(
+    tar_args: Vec<String>,
+    cmd_args: Option<Vec<String>>,
+    passphrase: Option<String>,
+    version: f32,
+    homedir: String,
+    options: Option<Vec<String>>,
+    env: Option<HashMap<String, String>>,
+    file_path: Option<String>,
+    ops: Operation,
+    provider: Option<PassphraseContext>,
+) -> Result<(CmdResult, String), GPGError> {
+    // tar_args: arguments to be passed to gpgtar ( --skip-crypto will be added and the tarball will be read from stdin )
+
+    let mut args: Vec<String> = vec!["gpgtar".to_string(), "--skip-crypto".to_string()];
+    args.append(&mut tar_args.clone());
+    args.push("-".to_string());
+    let mut tar_process: Child = match build_command(args, env.clone()).spawn() {
+        Ok(child) => child,
+        Err(e) => {
+            return Err(GPGError::new(
+                GPGErrorType::FailedToStartProcess(e.to_string()),
+                None,
+            ))
+        }
+    };
+    let mut tar_stdin: ChildStdin = tar_process.stdin.take().unwrap();
+    let tar_output: JoinHandle<String> = read_tar_output(tar_process.stdout.take().unwrap());
+    let tar_reader: JoinHandle<String> = read_tar_output(tar_process.stderr.take().unwrap());
+
+    let result: Result<CmdResult, GPGError> = handle_cmd_io_output(
+        cmd_args,
+        passphrase,
+        version,
+        homedir,
+        options,
+        env,
+        None,
+        file_path,
+        None,
+        true,
+        true,
+        ops,
+        provider,
+        Some(&mut tar_stdin),
+    );
+    drop(tar_stdin);
+    let (output, tar_error) = wait_tar_process(tar_process, tar_reader, Some(tar_output));
+    match check_tar_result(result, tar_error) {
+        Ok(result) => {
+            return Ok((result, output));
+        }
+        Err(e) => {
+            return Err(e);
+        }
+    }
+}
+
+fn read_tar_output(mut reader: impl Read + Send + 'static) -> JoinHandle<String> {
+    return thread::spawn(move || {
+        let mut buffer: Vec<u8> = Vec::new();
+        let _ = reader.read_to_end(&mut buffer);
+        return String::from_utf8_lossy(&buffer).to_string();
+    });
+}
+
+// wait for gpgtar to finish, return its output and the error message if gpgtar failed
+fn wait_tar_process(
+    mut tar_process: Child,
+    tar_reader: JoinHandle<String>,
+    tar_output: Option<JoinHandle<String>>,
+) -> (String, Option<String>) {
+    let output: String = match tar_output {
+        Some(tar_output) => tar_output.join().unwrap_or_default(),
+        None => "".to_string(),
+    };
+    let error: String = tar_reader.join().unwrap_or_default();
+    let exit_code: i32 = match tar_process.wait() {
+        Ok(status) => status.code().unwrap_or(-1),
+        Err(_) => -1,
+    };
+    if exit_code == 0 {
+        return (output, None);
+    }
+    return (
+        output,
+        Some(format!("gpgtar exited with {}: {}", exit_code, error.trim())),
+    );
+}
+
+fn check_tar_result(
+    result: Result<CmdResult, GPGError>,
+    tar_error: Option<String>,
+) -> Result<CmdResult, GPGError> {
+    match (result, tar_error) {
+        (Ok(result), Some(tar_error)) => {
+            return Err(GPGError::new(
+                GPGErrorType::GPGProcessError(tar_error),
+                Some(result),
+            ));
+        }
+        (result, _) => {
+            return result;
+        }
+    }
+}
+
 // generate a list of arguments to be passed to gpg process
 fn generate_cmd_args(
     cmd_args: Option<Vec<String>>,
@@ -122,7 +722,7 @@ fn generate_cmd_args(
     args.append(&mut vec!["--homedir".to_string(), homedir]);
     // TODO: add keyring and secret keyring support
     if passphrase.is_some() {
-        args.append(&mut vec!["--passphrase-fd".to_string(), "0".to_string()]);
+        args.append(&mut vec!["--passphrase-fd".to_string(), PASSPHRASE_FD.to_string()]);
     }
     if options.is_some() {
         args.append(&mut options.unwrap());
@@ -139,10 +739,99 @@ pub fn start_process(
     homedir: String,
     options: Option<Vec<String>>,
     env: Option<HashMap<String, String>>,
+    stdin_data: bool,
 ) -> Result<Child, Error> {
+    // stdin_data: whether data will be streamed into the stdin of the process after it started
+
+    //*****************************************************************************************
+    //    NOTE: on non-unix the passphrase can only be written to stdin ahead of the data, which will
+    //          mix the passphrase into the data, so it was refused when data was streamed through stdin.
+    //          An empty passphrase ( no passphrase needed ) was simply not passed to gpg in that case.
+    //******************************************************************************************
+    #[cfg(not(unix))]
+    let passphrase: Option<String> = match passphrase {
+        Some(passphrase) if stdin_data => {
+            if !passphrase.is_empty() {
+                return Err(Error::new(
+                    ErrorKind::Unsupported,
+                    "a passphrase together with data on stdin was only supported on unix",
+                ));
+            }
+            None
+        }
+        passphrase => passphrase,
+    };
+    #[cfg(unix)]
+    let _ = stdin_data;
+
+    let cmd_args: Vec<String> =
+        generate_cmd_args(cmd_args, passphrase.clone(), version, homedir.clone(), options);
+    let mut command: Command = build_command(cmd_args, env);
+
+    if passphrase.is_none() {
+        return command.spawn();
+    }
+    let passphrase: String = passphrase.unwrap();
+
+    //*****************************************************************************************
+    //    NOTE: the passphrase was delivered through a pipe that was set as fd 3 in the gpg process,
+    //          gpg will only read the passphrase from it when it was needed,
+    //          so the passphrase will not be mixed with the data streamed through stdin
+    //          ( ex: signing with a passphrase protected key while encrypting )
+    //******************************************************************************************
+    #[cfg(unix)]
+    {
+        let (cmd, mut writer) = spawn_with_extra_fd(&mut command, PASSPHRASE_FD)?;
+        let _ = writer.write_all(passphrase.as_bytes());
+        let _ = writer.write_all("\n".as_bytes());
+        drop(writer);
+        return Ok(cmd);
+    }
+    #[cfg(not(unix))]
+    {
+        let mut cmd: Child = command.spawn()?;
+        let stdin: &mut ChildStdin = cmd.stdin.as_mut().unwrap();
+        let _ = stdin.write_all(passphrase.as_bytes());
+        let _ = stdin.write_all("\n".as_bytes());
+        return Ok(cmd);
+    }
+}
+
+// start a process that prompt for passphrase(s) through the status fd,
+// the passphrase(s) can then be written to the command channel returned
+pub fn start_process_with_provider(
+    cmd_args: Option<Vec<String>>,
+    version: f32,
+    homedir: String,
+    options: Option<Vec<String>>,
+    env: Option<HashMap<String, String>>,
+) -> Result<(Child, Box<dyn Write + Send>), Error> {
+    let mut prompt_args: Vec<String> = vec![];
+    if version >= 2.1 {
+        prompt_args.append(&mut vec!["--pinentry-mode".to_string(), "loopback".to_string()]);
+    }
+    prompt_args.append(&mut vec!["--command-fd".to_string(), PASSPHRASE_FD.to_string()]);
+    prompt_args.append(&mut cmd_args.unwrap());
     let cmd_args: Vec<String> =
-        generate_cmd_args(cmd_args, passphrase, version, homedir.clone(), options);
+        generate_cmd_args(Some(prompt_args), None, version, homedir.clone(), options);
+    let mut command: Command = build_command(cmd_args, env);
+
+    #[cfg(unix)]
+    {
+        let (cmd, writer) = spawn_with_extra_fd(&mut command, PASSPHRASE_FD)?;
+        return Ok((cmd, Box::new(writer)));
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = command;
+        return Err(Error::new(
+            ErrorKind::Unsupported,
+            "passphrase provider was only supported on unix",
+        ));
+    }
+}
 
+fn build_command(cmd_args: Vec<String>, env: Option<HashMap<String, String>>) -> Command {
     let mut command = Command::new(&cmd_args[0]); // The first element of the vector is the command
     // Pass the rest of the arguments to the command
     command.args(&cmd_args[1..]);
@@ -151,34 +840,89 @@ pub fn start_process(
             command.env(key, value);
         }
     };
-    let cmd: Result<Child, Error> = command
+    command
         .stdin(Stdio::piped())
         .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .spawn();
+        .stderr(Stdio::piped());
+    return command;
+}
 
-    return cmd;
+// spawn the process with a pipe set as the fd provided, the write end of the pipe will be returned
+#[cfg(unix)]
+fn spawn_with_extra_fd(command: &mut Command, fd: i32) -> Result<(Child, File), Error> {
+    let (reader, writer) = create_pipe()?;
+    let reader_fd: i32 = reader.as_raw_fd();
+    unsafe {
+        command.pre_exec(move || {
+            // dup2 will not clear the close-on-exec flag if the fd was already the target fd
+            if reader_fd == fd {
+                if libc::fcntl(reader_fd, libc::F_SETFD, 0) == -1 {
+                    return Err(Error::last_os_error());
+                }
+            } else if libc::dup2(reader_fd, fd) == -1 {
+                return Err(Error::last_os_error());
+            }
+            return Ok(());
+        });
+    }
+    let cmd: Result<Child, Error> = command.spawn();
+    drop(reader);
+    return Ok((cmd?, writer));
+}
+
+// create a pipe with libc ( std::io::pipe was only stable since rust 1.87 ), both ends were set as
+// close-on-exec so only the end that was dup2 to the fd will be inherited by the process
+#[cfg(unix)]
+fn create_pipe() -> Result<(File, File), Error> {
+    let mut fds: [libc::c_int; 2] = [0; 2];
+    if unsafe { libc::pipe(fds.as_mut_ptr()) } == -1 {
+        return Err(Error::last_os_error());
+    }
+    // owned right away so both ends will be closed if anything below fail
+    let (reader, writer) = unsafe { (File::from_raw_fd(fds[0]), File::from_raw_fd(fds[1])) };
+    for fd in fds.iter() {
+        if unsafe { libc::fcntl(*fd, libc::F_SETFD, libc::FD_CLOEXEC) } == -1 {
+            return Err(Error::last_os_error());
+        }
+    }
+    return Ok((reader, writer));
 }
 
 // to collect output / response from the Command process
 pub fn collect_cmd_output_response(
     mut cmd_process: Child,
     result: Arc<Mutex<&mut CmdResult>>,
-    writer: Option<JoinHandle<()>>,
+    writer: Option<JoinHandle<Result<(), GPGError>>>,
+    prompt: Option<(PassphraseContext, Box<dyn Write + Send>)>,
+    output: Option<&mut (dyn Write + Send)>,
 ) {
     let stderr: ChildStderr = cmd_process.stderr.take().unwrap();
     let stdout: ChildStdout = cmd_process.stdout.take().unwrap();
 
-    thread::scope(|s| {
-        s.spawn(|| {
-            read_cmd_output(stdout, Arc::clone(&result));
+    let write_error: Option<String> = thread::scope(|s| {
+        let output_reader = s.spawn(|| {
+            return read_cmd_output(stdout, Arc::clone(&result), output);
         });
-        s.spawn(|| {
-            read_cmd_response(stderr, Arc::clone(&result));
+        s.spawn(|| match prompt {
+            Some((provider, channel)) => {
+                read_cmd_response_with_provider(stderr, Arc::clone(&result), provider, channel);
+            }
+            None => {
+                read_cmd_response(stderr, Arc::clone(&result));
+            }
         });
+        return output_reader.join().unwrap_or(None);
     });
+    if write_error.is_some() {
+        // the output could not be written to the output provided
+        let mut r = result.lock().unwrap();
+        r.success = false;
+        r.status_message = Some(format!("failed to write output: {}", write_error.unwrap()));
+    }
     if writer.is_some() {
-        let _ = writer.unwrap().join();
+        if let Ok(write_result) = writer.unwrap().join() {
+            check_write_result(&mut result.lock().unwrap(), write_result);
+        }
     }
     let exit_status: Result<ExitStatus, Error> = cmd_process.wait();
     let exit_code = match exit_status {
@@ -190,9 +934,25 @@ pub fn collect_cmd_output_response(
     result.lock().unwrap().set_return_code(exit_code);
 }
 
+// the process will only see the end of the input if the input could not be read,
+// so the result should not be considered as success
+fn check_write_result(result: &mut CmdResult, write_result: Result<(), GPGError>) {
+    if let Err(e) = write_result {
+        if let GPGErrorType::ReadFailError(err) = e.error_type {
+            result.success = false;
+            result.status_message = Some(format!("failed to read input: {}", err));
+        }
+    }
+}
+
 // read output from stdout
-fn read_cmd_output(mut stdout: ChildStdout, result: Arc<Mutex<&mut CmdResult>>) {
+fn read_cmd_output(
+    mut stdout: ChildStdout,
+    result: Arc<Mutex<&mut CmdResult>>,
+    mut output: Option<&mut (dyn Write + Send)>,
+) -> Option<String> {
     let mut output_lines: Vec<String> = Vec::new();
+    let mut write_error: Option<String> = None;
     loop {
         let mut buffer: [u8; BUFFER_SIZE] = [0; BUFFER_SIZE];
         let line: Result<usize, Error> = stdout.read(&mut buffer);
@@ -206,15 +966,39 @@ fn read_cmd_output(mut stdout: ChildStdout, result: Arc<Mutex<&mut CmdResult>>)
                 break;
             }
         }
-        let line_string = String::from_utf8_lossy(&buffer[..line.unwrap()]);
-        output_lines.push(line_string.to_string());
+        match output.as_mut() {
+            Some(output) => {
+                // keep reading after a failed write, so the process will not be blocked on a full pipe
+                if write_error.is_none() {
+                    if let Err(e) = output.write_all(&buffer[..line.unwrap()]) {
+                        write_error = Some(e.to_string());
+                    }
+                }
+            }
+            None => {
+                let line_string = String::from_utf8_lossy(&buffer[..line.unwrap()]);
+                output_lines.push(line_string.to_string());
+            }
+        }
+    }
+    match output {
+        Some(output) => {
+            if write_error.is_none() {
+                if let Err(e) = output.flush() {
+                    write_error = Some(e.to_string());
+                }
+            }
+        }
+        None => {
+            result.lock().unwrap().set_raw_data(output_lines.join(""));
+        }
     }
-    result.lock().unwrap().set_raw_data(output_lines.join(""));
     drop(stdout);
+    return write_error;
 }
 
 // read response from stderr
-fn read_cmd_response(mut stderr: ChildStderr, result: Arc<Mutex<&mut CmdResult>>) {
+pub fn read_cmd_response(mut stderr: ChildStderr, result: Arc<Mutex<&mut CmdResult>>) {
     let mut response_lines: Vec<String> = Vec::new();
     loop {
         let mut buffer: Vec<u8> = Vec::new();
@@ -233,6 +1017,110 @@ fn read_cmd_response(mut stderr: ChildStderr, result: Arc<Mutex<&mut CmdResult>>
         response_lines.push(response_line_string.to_string());
     }
     let data: String = response_lines.join("");
+    drop(stderr);
+    handle_cmd_response(data, result);
+}
+
+// read response from stderr while answering the passphrase prompt(s) through the provider
+pub fn read_cmd_response_with_provider(
+    stderr: ChildStderr,
+    result: Arc<Mutex<&mut CmdResult>>,
+    mut provider: PassphraseContext,
+    mut channel: Box<dyn Write + Send>,
+) {
+    // GET_HIDDEN passphrase.enter was preceded by
+    // USERID_HINT <long main keyid> <string>
+    // NEED_PASSPHRASE <long keyid> <long main keyid> <keytype> <keylength> or NEED_PASSPHRASE_SYM <cipher_algo> <s2k_mode> <s2k_hash>
+    let mut reader: BufReader<ChildStderr> = BufReader::new(stderr);
+    let mut data: String = String::new();
+    let mut uid_hint: Option<String> = None;
+    let mut request: Option<PassphraseRequest> = None;
+    let mut attempts: HashMap<String, u32> = HashMap::new();
+    loop {
+        let mut line: Vec<u8> = Vec::new();
+        match reader.read_until(b'\n', &mut line) {
+            Ok(n) => {
+                if n <= 0 {
+                    break;
+                }
+            }
+            Err(_) => {
+                break;
+            }
+        }
+        let line: String = String::from_utf8_lossy(&line).to_string();
+        data.push_str(&line);
+        if !line.starts_with("[GNUPG:] ") {
+            continue;
+        }
+        let mut parts = line[9..].trim_end().splitn(2, char::is_whitespace);
+        let keyword: &str = parts.next().unwrap_or("");
+        let value: &str = parts.next().unwrap_or("");
+        match keyword {
+            "USERID_HINT" => {
+                uid_hint = value.splitn(2, char::is_whitespace).nth(1).map(|x| x.to_string());
+            }
+            "NEED_PASSPHRASE" => {
+                let values: Vec<&str> = value.split_whitespace().collect();
+                request = Some(PassphraseRequest {
+                    keyid: values.get(0).unwrap_or(&"").to_string(),
+                    main_keyid: values.get(1).unwrap_or(&"").to_string(),
+                    keygrip: None,
+                    uid_hint: uid_hint.clone(),
+                    symmetric: false,
+                    attempt: 0,
+                });
+            }
+            "NEED_PASSPHRASE_SYM" => {
+                request = Some(PassphraseRequest {
+                    keyid: "".to_string(),
+                    main_keyid: "".to_string(),
+                    keygrip: None,
+                    uid_hint: None,
+                    symmetric: true,
+                    attempt: 0,
+                });
+            }
+            "GET_HIDDEN" => {
+                let mut passphrase: String = "".to_string();
+                if request.is_some() {
+                    let r: &mut PassphraseRequest = request.as_mut().unwrap();
+                    let attempt: &mut u32 = attempts.entry(r.keyid.clone()).or_insert(0);
+                    *attempt += 1;
+                    r.attempt = *attempt;
+                    if !r.symmetric {
+                        match provider.lookup_keygrip(&r.keyid) {
+                            Ok(keygrip) => {
+                                r.keygrip = keygrip;
+                            }
+                            Err(e) => {
+                                // the provider was still called, only without the keygrip
+                                result.lock().unwrap().capture_debug_log(format!(
+                                    "failed to look up the keygrip of {}: {}",
+                                    r.keyid, e.error_type
+                                ));
+                            }
+                        }
+                    }
+                    passphrase = provider.get_passphrase(r);
+                }
+                let _ = channel.write_all(format!("{}\n", passphrase).as_bytes());
+                let _ = channel.flush();
+            }
+            "GET_BOOL" | "GET_LINE" => {
+                // not expected in batch mode, answer with the default to prevent gpg from waiting
+                let _ = channel.write_all("\n".as_bytes());
+                let _ = channel.flush();
+            }
+            _ => {}
+        }
+    }
+    drop(channel);
+    handle_cmd_response(data, result);
+}
+
+// handle the response ( status line(s) and debug log ) collected from stderr
+fn handle_cmd_response(data: String, result: Arc<Mutex<&mut CmdResult>>) {
     result.lock().unwrap().set_raw_data(data.clone());
     // the following process was to handle the status line(s)
     for response_line_string in data.clone().split("\n") {
@@ -260,27 +1148,25 @@ fn read_cmd_response(mut stderr: ChildStderr, result: Arc<Mutex<&mut CmdResult>>
             }
         }
     }
-    drop(stderr);
 }
 
 // start writing process
 fn start_writing_process(
-    file: Option<File>,
+    reader: Option<Box<dyn Read + Send>>,
     byte_input: Option<Vec<u8>>,
     stdin: ChildStdin,
-) -> JoinHandle<()> {
-    // TODO: implement write to stdin
-    let write_process: JoinHandle<()> = thread::spawn(move || {
-        let _ = write_to_stdin(file, byte_input, stdin);
+) -> JoinHandle<Result<(), GPGError>> {
+    let write_process: JoinHandle<Result<(), GPGError>> = thread::spawn(move || {
+        return write_to_stdin(reader, byte_input, stdin);
     });
     return write_process;
 }
 
-// write to stdin
-fn write_to_stdin(
-    file: Option<File>,
+// write to stdin ( the reader can be a file or any other source ) in chunks of BUFFER_SIZE
+pub fn write_to_stdin(
+    reader: Option<Box<dyn Read + Send + '_>>,
     byte_input: Option<Vec<u8>>,
-    mut stdin: ChildStdin,
+    mut stdin: impl Write,
 ) -> Result<(), GPGError> {
     match byte_input {
         Some(byte_input) => {
@@ -300,10 +1186,10 @@ fn write_to_stdin(
         None => {}
     }
 
-    match file {
-        Some(mut file) => loop {
+    match reader {
+        Some(mut reader) => loop {
             let mut buffer: [u8; BUFFER_SIZE] = [0; BUFFER_SIZE];
-            let data: Result<usize, Error> = file.read(&mut buffer);
+            let data: Result<usize, Error> = reader.read(&mut buffer);
             match data {
                 Ok(n) => {
                     if n <= 0 {
@@ -311,6 +1197,9 @@ fn write_to_stdin(
                     }
                 }
                 Err(e) => {
+                    if e.kind() == ErrorKind::Interrupted {
+                        continue;
+                    }
                     return Err(GPGError::new(
                         GPGErrorType::ReadFailError(e.to_string()),
                         None,
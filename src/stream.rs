@@ -0,0 +1,355 @@
+use std::{
+    io::{Error, ErrorKind, Read, Write},
+    process::{Child, ChildStderr, ChildStdin, ChildStdout},
+    sync::{Arc, Mutex},
+    thread::{self, JoinHandle},
+};
+
+use crate::{
+    gnupg::GPG,
+    passphrase::PassphraseContext,
+    process::{
+        read_cmd_response, read_cmd_response_with_provider, start_process,
+        start_process_with_provider, write_to_stdin,
+    },
+    utils::{
+        enums::Operation,
+        errors::{GPGError, GPGErrorType},
+        response::{CmdResult, DecryptResult},
+        utils::decode_decrypt_result,
+    },
+};
+
+//*******************************************************
+
+//          RELATED TO STREAMING ENCRYPTION
+
+//*******************************************************
+// a writer that encrypt everything written to it and stream the encrypted data into the output
+// obtain it from GPG::encrypt_writer and call finish() once all data was written
+pub struct GpgEncryptWriter<W: Write + Send + 'static> {
+    // stdin: the input of the gpg process, closed once finished
+    // process: the gpg process
+    // output_thread: thread that stream the stdout of the process into the output
+    // response_thread: thread that collect the status of the process
+    stdin: Option<ChildStdin>,
+    process: Option<Child>,
+    output_thread: Option<JoinHandle<Result<W, Error>>>,
+    response_thread: Option<JoinHandle<CmdResult>>,
+}
+
+#[doc(hidden)]
+impl<W: Write + Send + 'static> GpgEncryptWriter<W> {
+    pub(crate) fn new(
+        gpg: &GPG,
+        args: Vec<String>,
+        passphrase: Option<String>,
+        provider: Option<PassphraseContext>,
+        mut output: W,
+    ) -> Result<GpgEncryptWriter<W>, GPGError> {
+        let passphrase: String = passphrase.unwrap_or("".to_string());
+        let (mut process, prompt) = match spawn_process(gpg, args, passphrase, provider) {
+            Ok(process) => process,
+            Err(e) => {
+                return Err(e);
+            }
+        };
+        let stdin: ChildStdin = process.stdin.take().unwrap();
+
+        let mut stdout: ChildStdout = process.stdout.take().unwrap();
+        let output_thread: JoinHandle<Result<W, Error>> = thread::spawn(move || {
+            std::io::copy(&mut stdout, &mut output)?;
+            output.flush()?;
+            return Ok(output);
+        });
+        let response_thread: JoinHandle<CmdResult> =
+            start_response_thread(process.stderr.take().unwrap(), prompt, Operation::Encrypt);
+
+        return Ok(GpgEncryptWriter {
+            stdin: Some(stdin),
+            process: Some(process),
+            output_thread: Some(output_thread),
+            response_thread: Some(response_thread),
+        });
+    }
+
+    // close the input, wait for gpg to finish and return the output with the result of the process
+    pub fn finish(mut self) -> Result<(W, CmdResult), GPGError> {
+        drop(self.stdin.take());
+        let output: Result<W, Error> = match self.output_thread.take().unwrap().join() {
+            Ok(output) => output,
+            Err(_) => Err(Error::new(ErrorKind::Other, "output thread panicked")),
+        };
+        let result: Result<CmdResult, GPGError> = collect_result(
+            self.process.take().unwrap(),
+            self.response_thread.take().unwrap(),
+            Operation::Encrypt,
+        );
+        match result {
+            Ok(result) => match output {
+                Ok(output) => {
+                    return Ok((output, result));
+                }
+                Err(e) => {
+                    return Err(GPGError::new(
+                        GPGErrorType::WriteFailError(e.to_string()),
+                        Some(result),
+                    ));
+                }
+            },
+            Err(e) => {
+                return Err(e);
+            }
+        }
+    }
+}
+
+impl<W: Write + Send + 'static> Write for GpgEncryptWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Error> {
+        return self.stdin.as_mut().unwrap().write(buf);
+    }
+
+    fn flush(&mut self) -> Result<(), Error> {
+        return self.stdin.as_mut().unwrap().flush();
+    }
+}
+
+impl<W: Write + Send + 'static> Drop for GpgEncryptWriter<W> {
+    fn drop(&mut self) {
+        // if not finished, close the input and let the process end
+        drop(self.stdin.take());
+        if let Some(mut process) = self.process.take() {
+            let _ = process.wait();
+        }
+    }
+}
+
+//*******************************************************
+
+//          RELATED TO STREAMING DECRYPTION
+
+//*******************************************************
+// a reader that stream the decrypted data of the input
+// obtain it from GPG::decrypt_reader, an error will be returned at the end of the data if the decryption failed
+
+//*****************************************************************************************
+//    WARNING: the decrypted data was returned as soon as gpg produce it, BEFORE the integrity
+//             check ( MDC / AEAD tag ) at the end of the message was done. The data read must
+//             not be trusted ( acted on, executed, committed ) until read return Ok(0) or
+//             finish() return Ok, a tampered message will only be reported at that point.
+//******************************************************************************************
+pub struct GpgDecryptReader {
+    // stdout: the output of the gpg process
+    // process: the gpg process
+    // input_thread: thread that stream the input into the stdin of the process
+    // response_thread: thread that collect the status of the process
+    // result: the result of the process once the output was fully read
+    stdout: Option<ChildStdout>,
+    process: Option<Child>,
+    input_thread: Option<JoinHandle<Result<(), GPGError>>>,
+    response_thread: Option<JoinHandle<CmdResult>>,
+    result: Option<Result<CmdResult, GPGError>>,
+}
+
+#[doc(hidden)]
+impl GpgDecryptReader {
+    pub(crate) fn new(
+        gpg: &GPG,
+        args: Vec<String>,
+        passphrase: Option<String>,
+        provider: Option<PassphraseContext>,
+        input: Box<dyn Read + Send>,
+    ) -> Result<GpgDecryptReader, GPGError> {
+        let passphrase: String = passphrase.unwrap_or("".to_string());
+        let (mut process, prompt) = match spawn_process(gpg, args, passphrase, provider) {
+            Ok(process) => process,
+            Err(e) => {
+                return Err(e);
+            }
+        };
+        let stdin: ChildStdin = process.stdin.take().unwrap();
+        let input_thread: JoinHandle<Result<(), GPGError>> = thread::spawn(move || {
+            return write_to_stdin(Some(input), None, stdin);
+        });
+        let response_thread: JoinHandle<CmdResult> =
+            start_response_thread(process.stderr.take().unwrap(), prompt, Operation::Decrypt);
+
+        return Ok(GpgDecryptReader {
+            stdout: Some(process.stdout.take().unwrap()),
+            process: Some(process),
+            input_thread: Some(input_thread),
+            response_thread: Some(response_thread),
+            result: None,
+        });
+    }
+
+    // read the remaining output ( if any ), wait for gpg to finish and return the metadata of the decryption,
+    // the data read can only be trusted once this return Ok
+    pub fn finish(mut self) -> Result<DecryptResult, GPGError> {
+        if let Some(mut stdout) = self.stdout.take() {
+            let _ = std::io::copy(&mut stdout, &mut std::io::sink());
+        }
+        self.collect();
+        match self.result.take().unwrap() {
+            Ok(result) => {
+                return Ok(decode_decrypt_result(&result));
+            }
+            Err(e) => {
+                return Err(e);
+            }
+        }
+    }
+
+    fn collect(&mut self) {
+        if self.result.is_some() {
+            return;
+        }
+        drop(self.stdout.take());
+        let input: Result<(), GPGError> = match self.input_thread.take().unwrap().join() {
+            Ok(input) => input,
+            Err(_) => Ok(()),
+        };
+        let mut result: Result<CmdResult, GPGError> = collect_result(
+            self.process.take().unwrap(),
+            self.response_thread.take().unwrap(),
+            Operation::Decrypt,
+        );
+        // the process will only see the end of the input if the input could not be read
+        if let (Ok(r), Err(e)) = (&result, input) {
+            if let GPGErrorType::ReadFailError(_) = e.error_type {
+                result = Err(GPGError::new(e.error_type, Some(r.clone())));
+            }
+        }
+        // not every failure come with a status ( ex. CRC error of the armor ), as the data read
+        // was only trusted once the reader finished, anything but a clean exit was a failure
+        if let Ok(r) = &result {
+            if r.return_code != Some(0) {
+                result = Err(GPGError::new(
+                    GPGErrorType::GPGProcessError(format!(
+                        "gpg exited with {}",
+                        r.return_code.unwrap_or(-1)
+                    )),
+                    Some(r.clone()),
+                ));
+            }
+        }
+        self.result = Some(result);
+    }
+}
+
+impl Read for GpgDecryptReader {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+        if let Some(stdout) = self.stdout.as_mut() {
+            let n: usize = stdout.read(buf)?;
+            if n > 0 || buf.len() == 0 {
+                return Ok(n);
+            }
+        }
+        // end of the output, report the failure of the process ( if any )
+        self.collect();
+        match self.result.as_ref().unwrap() {
+            Ok(_) => {
+                return Ok(0);
+            }
+            Err(e) => {
+                return Err(Error::new(ErrorKind::Other, e.error_type.to_string()));
+            }
+        }
+    }
+}
+
+impl Drop for GpgDecryptReader {
+    fn drop(&mut self) {
+        // if not finished, stop the process as the output will not be read anymore
+        drop(self.stdout.take());
+        if let Some(mut process) = self.process.take() {
+            let _ = process.kill();
+            let _ = process.wait();
+        }
+    }
+}
+
+// the passphrase prompt(s) will be answered through the command channel returned if provider was set
+fn spawn_process(
+    gpg: &GPG,
+    args: Vec<String>,
+    passphrase: String,
+    provider: Option<PassphraseContext>,
+) -> Result<(Child, Option<(PassphraseContext, Box<dyn Write + Send>)>), GPGError> {
+    let process: Result<(Child, Option<(PassphraseContext, Box<dyn Write + Send>)>), Error> =
+        match provider {
+            Some(provider) => start_process_with_provider(
+                Some(args),
+                gpg.version,
+                gpg.homedir.clone(),
+                gpg.options.clone(),
+                gpg.env.clone(),
+            )
+            .map(|(process, channel)| (process, Some((provider, channel)))),
+            None => start_process(
+                Some(args),
+                Some(passphrase),
+                gpg.version,
+                gpg.homedir.clone(),
+                gpg.options.clone(),
+                gpg.env.clone(),
+                true,
+            )
+            .map(|process| (process, None)),
+        };
+    match process {
+        Ok(process) => {
+            return Ok(process);
+        }
+        Err(e) => {
+            return Err(GPGError::new(
+                GPGErrorType::FailedToStartProcess(e.to_string()),
+                None,
+            ));
+        }
+    }
+}
+
+fn start_response_thread(
+    stderr: ChildStderr,
+    prompt: Option<(PassphraseContext, Box<dyn Write + Send>)>,
+    ops: Operation,
+) -> JoinHandle<CmdResult> {
+    return thread::spawn(move || {
+        let mut result: CmdResult = CmdResult::init(ops);
+        match prompt {
+            Some((provider, channel)) => {
+                read_cmd_response_with_provider(
+                    stderr,
+                    Arc::new(Mutex::new(&mut result)),
+                    provider,
+                    channel,
+                );
+            }
+            None => {
+                read_cmd_response(stderr, Arc::new(Mutex::new(&mut result)));
+            }
+        }
+        return result;
+    });
+}
+
+fn collect_result(
+    mut process: Child,
+    response_thread: JoinHandle<CmdResult>,
+    ops: Operation,
+) -> Result<CmdResult, GPGError> {
+    let mut result: CmdResult = response_thread.join().unwrap_or(CmdResult::init(ops));
+    let exit_code: i32 = match process.wait() {
+        Ok(status) => status.code().unwrap_or(-1),
+        Err(_) => -1,
+    };
+    result.set_return_code(exit_code);
+    if result.is_success() {
+        return Ok(result);
+    }
+    return Err(GPGError::new(
+        GPGErrorType::GPGProcessError(result.get_error_message()),
+        Some(result),
+    ));
+}
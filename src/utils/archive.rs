@@ -0,0 +1,132 @@
+use std::{
+    fs::{File, OpenOptions},
+    io::{Read, Write},
+};
+
+#[cfg(unix)]
+use std::os::unix::fs::OpenOptionsExt;
+
+use super::errors::{GPGError, GPGErrorType};
+
+const BLOCK_SIZE: usize = 512;
+
+//*******************************************************
+
+//           RELATED TO ARCHIVE (USTAR) HANDLING
+
+//*******************************************************
+// write the entries ( name, content ) into a single tar archive
+pub fn write_tar(path: &str, entries: &Vec<(String, Vec<u8>)>) -> Result<(), GPGError> {
+    let mut archive: Vec<u8> = Vec::new();
+    for (name, content) in entries {
+        if name.len() >= 100 {
+            return Err(GPGError::new(
+                GPGErrorType::InvalidArgumentError(format!("archive entry name too long: {}", name)),
+                None,
+            ));
+        }
+        archive.extend_from_slice(&gen_tar_header(name, content.len()));
+        archive.extend_from_slice(content);
+        let padding: usize = (BLOCK_SIZE - content.len() % BLOCK_SIZE) % BLOCK_SIZE;
+        archive.extend(vec![0u8; padding]);
+    }
+    // the end of the archive was marked by 2 empty blocks
+    archive.extend(vec![0u8; BLOCK_SIZE * 2]);
+
+    // the archive might hold secret materials, it was created readable by the owner only from the start
+    // and an existing file will not be overwritten
+    let mut options: OpenOptions = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    options.mode(0o600);
+    let file: Result<File, std::io::Error> = options.open(path);
+    match file {
+        Ok(mut file) => {
+            if let Err(e) = file.write_all(&archive) {
+                return Err(GPGError::new(
+                    GPGErrorType::WriteFailError(e.to_string()),
+                    None,
+                ));
+            }
+            return Ok(());
+        }
+        Err(e) => {
+            return Err(GPGError::new(
+                GPGErrorType::WriteFailError(e.to_string()),
+                None,
+            ));
+        }
+    }
+}
+
+// read the entries ( name, content ) of a tar archive
+pub fn read_tar(path: &str) -> Result<Vec<(String, Vec<u8>)>, GPGError> {
+    let mut archive: Vec<u8> = Vec::new();
+    let file: Result<File, std::io::Error> = File::open(path);
+    match file {
+        Ok(mut file) => {
+            if let Err(e) = file.read_to_end(&mut archive) {
+                return Err(GPGError::new(
+                    GPGErrorType::ReadFailError(e.to_string()),
+                    None,
+                ));
+            }
+        }
+        Err(_) => {
+            return Err(GPGError::new(
+                GPGErrorType::FileNotFoundError("File do not exist".to_string()),
+                None,
+            ));
+        }
+    }
+
+    let mut entries: Vec<(String, Vec<u8>)> = Vec::new();
+    let mut offset: usize = 0;
+    while offset + BLOCK_SIZE <= archive.len() {
+        let header: &[u8] = &archive[offset..offset + BLOCK_SIZE];
+        if header.iter().all(|b| *b == 0) {
+            break;
+        }
+        let name: String = read_tar_field(&header[0..100]);
+        let size: usize = usize::from_str_radix(read_tar_field(&header[124..136]).trim(), 8)
+            .unwrap_or(0);
+        offset += BLOCK_SIZE;
+        if offset + size > archive.len() {
+            return Err(GPGError::new(
+                GPGErrorType::ReadFailError("archive was truncated".to_string()),
+                None,
+            ));
+        }
+        // only regular file entries were expected
+        if header[156] == b'0' || header[156] == 0 {
+            entries.push((name, archive[offset..offset + size].to_vec()));
+        }
+        offset += size + (BLOCK_SIZE - size % BLOCK_SIZE) % BLOCK_SIZE;
+    }
+    return Ok(entries);
+}
+
+fn gen_tar_header(name: &str, size: usize) -> [u8; BLOCK_SIZE] {
+    let mut header: [u8; BLOCK_SIZE] = [0; BLOCK_SIZE];
+    header[0..name.len()].copy_from_slice(name.as_bytes());
+    header[100..108].copy_from_slice(b"0000600\0");
+    header[108..116].copy_from_slice(b"0000000\0");
+    header[116..124].copy_from_slice(b"0000000\0");
+    header[124..136].copy_from_slice(format!("{:011o}\0", size).as_bytes());
+    let mtime: i64 = chrono::Local::now().timestamp();
+    header[136..148].copy_from_slice(format!("{:011o}\0", mtime).as_bytes());
+    header[156] = b'0';
+    header[257..263].copy_from_slice(b"ustar\0");
+    header[263..265].copy_from_slice(b"00");
+
+    // checksum was calculated with the checksum field filled with spaces
+    header[148..156].copy_from_slice(b"        ");
+    let checksum: u32 = header.iter().map(|b| *b as u32).sum();
+    header[148..156].copy_from_slice(format!("{:06o}\0 ", checksum).as_bytes());
+    return header;
+}
+
+fn read_tar_field(field: &[u8]) -> String {
+    let end: usize = field.iter().position(|b| *b == 0).unwrap_or(field.len());
+    return String::from_utf8_lossy(&field[..end]).to_string();
+}
@@ -10,11 +10,20 @@ pub enum Operation {
     AddSubKey,
     RevokeKey,
     SearchKey,
+    ReceiveKey,
+    SendKey,
+    RefreshKey,
+    LocateKey,
     ImportKey,
     TrustKey,
     SignKey,
     ExportPublicKey,
     ExportSecretKey,
+    ExportSecretSubkey,
+    ExportOwnerTrust,
+    GenerateRevocation,
+    ListConfig,
+    InspectMessage,
     Encrypt,
     Decrypt,
     Sign,
@@ -33,11 +42,20 @@ impl Display for Operation {
             Operation::AddSubKey => write!(f, "AddSubKey"),
             Operation::RevokeKey => write!(f, "RevokeKey"),
             Operation::SearchKey => write!(f, "SearchKey"),
+            Operation::ReceiveKey => write!(f, "ReceiveKey"),
+            Operation::SendKey => write!(f, "SendKey"),
+            Operation::RefreshKey => write!(f, "RefreshKey"),
+            Operation::LocateKey => write!(f, "LocateKey"),
             Operation::ImportKey => write!(f, "ImportKey"),
             Operation::TrustKey => write!(f, "TrustKey"),
             Operation::SignKey => write!(f, "SignKey"),
             Operation::ExportPublicKey => write!(f, "ExportPublicKey"),
             Operation::ExportSecretKey => write!(f, "ExportSecretKey"),
+            Operation::ExportSecretSubkey => write!(f, "ExportSecretSubkey"),
+            Operation::ExportOwnerTrust => write!(f, "ExportOwnerTrust"),
+            Operation::GenerateRevocation => write!(f, "GenerateRevocation"),
+            Operation::ListConfig => write!(f, "ListConfig"),
+            Operation::InspectMessage => write!(f, "InspectMessage"),
             Operation::Encrypt => write!(f, "Encrypt"),
             Operation::Decrypt => write!(f, "Decrypt"),
             Operation::Sign => write!(f, "Sign"),
@@ -46,7 +64,7 @@ impl Display for Operation {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum TrustLevel {
     Expired,
     Undefined,
@@ -68,6 +86,379 @@ impl TrustLevel {
             TrustLevel::Ultimate => 6,
         }
     }
+
+    // from the TRUST_* status of a signature verification
+    pub fn from_status(keyword: &str) -> Option<TrustLevel> {
+        match keyword {
+            "TRUST_UNDEFINED" => Some(TrustLevel::Undefined),
+            "TRUST_NEVER" => Some(TrustLevel::Never),
+            "TRUST_MARGINAL" => Some(TrustLevel::Marginal),
+            "TRUST_FULLY" => Some(TrustLevel::Fully),
+            "TRUST_ULTIMATE" => Some(TrustLevel::Ultimate),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LocateMechanism {
+    Local,
+    Wkd,
+    Keyserver,
+    Dane,
+    Clear,
+    NoDefault,
+}
+
+#[doc(hidden)]
+impl LocateMechanism {
+    pub fn value(&self) -> String {
+        match &self {
+            LocateMechanism::Local => "local".to_string(),
+            LocateMechanism::Wkd => "wkd".to_string(),
+            LocateMechanism::Keyserver => "keyserver".to_string(),
+            LocateMechanism::Dane => "dane".to_string(),
+            LocateMechanism::Clear => "clear".to_string(),
+            LocateMechanism::NoDefault => "nodefault".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum WkdMethod {
+    Advanced,
+    Direct,
+}
+
+// the value was the name of the algorithm as advertised by gpg --list-config
+#[derive(Debug, Clone, PartialEq)]
+pub enum CipherAlgo {
+    IDEA,
+    TripleDES,
+    CAST5,
+    Blowfish,
+    AES,
+    AES192,
+    AES256,
+    Twofish,
+    Camellia128,
+    Camellia192,
+    Camellia256,
+}
+
+#[doc(hidden)]
+impl CipherAlgo {
+    pub fn value(&self) -> String {
+        match &self {
+            CipherAlgo::IDEA => "IDEA".to_string(),
+            CipherAlgo::TripleDES => "3DES".to_string(),
+            CipherAlgo::CAST5 => "CAST5".to_string(),
+            CipherAlgo::Blowfish => "BLOWFISH".to_string(),
+            CipherAlgo::AES => "AES".to_string(),
+            CipherAlgo::AES192 => "AES192".to_string(),
+            CipherAlgo::AES256 => "AES256".to_string(),
+            CipherAlgo::Twofish => "TWOFISH".to_string(),
+            CipherAlgo::Camellia128 => "CAMELLIA128".to_string(),
+            CipherAlgo::Camellia192 => "CAMELLIA192".to_string(),
+            CipherAlgo::Camellia256 => "CAMELLIA256".to_string(),
+        }
+    }
+
+    // from the OpenPGP algorithm id used in the status output of gpg
+    pub fn from_id(id: &str) -> Option<CipherAlgo> {
+        match id {
+            "1" => Some(CipherAlgo::IDEA),
+            "2" => Some(CipherAlgo::TripleDES),
+            "3" => Some(CipherAlgo::CAST5),
+            "4" => Some(CipherAlgo::Blowfish),
+            "7" => Some(CipherAlgo::AES),
+            "8" => Some(CipherAlgo::AES192),
+            "9" => Some(CipherAlgo::AES256),
+            "10" => Some(CipherAlgo::Twofish),
+            "11" => Some(CipherAlgo::Camellia128),
+            "12" => Some(CipherAlgo::Camellia192),
+            "13" => Some(CipherAlgo::Camellia256),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DigestAlgo {
+    SHA1,
+    RIPEMD160,
+    SHA224,
+    SHA256,
+    SHA384,
+    SHA512,
+}
+
+#[doc(hidden)]
+impl DigestAlgo {
+    pub fn value(&self) -> String {
+        match &self {
+            DigestAlgo::SHA1 => "SHA1".to_string(),
+            DigestAlgo::RIPEMD160 => "RIPEMD160".to_string(),
+            DigestAlgo::SHA224 => "SHA224".to_string(),
+            DigestAlgo::SHA256 => "SHA256".to_string(),
+            DigestAlgo::SHA384 => "SHA384".to_string(),
+            DigestAlgo::SHA512 => "SHA512".to_string(),
+        }
+    }
+
+    // from the OpenPGP algorithm id used in the status output of gpg
+    pub fn from_id(id: &str) -> Option<DigestAlgo> {
+        match id {
+            "2" => Some(DigestAlgo::SHA1),
+            "3" => Some(DigestAlgo::RIPEMD160),
+            "8" => Some(DigestAlgo::SHA256),
+            "9" => Some(DigestAlgo::SHA384),
+            "10" => Some(DigestAlgo::SHA512),
+            "11" => Some(DigestAlgo::SHA224),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PublicKeyAlgo {
+    RSA,
+    Elgamal,
+    DSA,
+    ECDH,
+    ECDSA,
+    EdDSA,
+    Ed25519,
+    Ed448,
+}
+
+#[doc(hidden)]
+impl PublicKeyAlgo {
+    pub fn value(&self) -> String {
+        match &self {
+            PublicKeyAlgo::RSA => "RSA".to_string(),
+            PublicKeyAlgo::Elgamal => "ELG".to_string(),
+            PublicKeyAlgo::DSA => "DSA".to_string(),
+            PublicKeyAlgo::ECDH => "ECDH".to_string(),
+            PublicKeyAlgo::ECDSA => "ECDSA".to_string(),
+            PublicKeyAlgo::EdDSA => "EDDSA".to_string(),
+            PublicKeyAlgo::Ed25519 => "ED25519".to_string(),
+            PublicKeyAlgo::Ed448 => "ED448".to_string(),
+        }
+    }
+
+    // from the OpenPGP algorithm id used in the status output of gpg
+    pub fn from_id(id: &str) -> Option<PublicKeyAlgo> {
+        match id {
+            "1" | "2" | "3" => Some(PublicKeyAlgo::RSA),
+            "16" | "20" => Some(PublicKeyAlgo::Elgamal),
+            "17" => Some(PublicKeyAlgo::DSA),
+            "18" => Some(PublicKeyAlgo::ECDH),
+            "19" => Some(PublicKeyAlgo::ECDSA),
+            "22" => Some(PublicKeyAlgo::EdDSA),
+            "27" => Some(PublicKeyAlgo::Ed25519),
+            "28" => Some(PublicKeyAlgo::Ed448),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CompressAlgo {
+    Uncompressed,
+    Zip,
+    Zlib,
+    Bzip2,
+}
+
+#[doc(hidden)]
+impl CompressAlgo {
+    pub fn value(&self) -> String {
+        match &self {
+            CompressAlgo::Uncompressed => "Uncompressed".to_string(),
+            CompressAlgo::Zip => "ZIP".to_string(),
+            CompressAlgo::Zlib => "ZLIB".to_string(),
+            CompressAlgo::Bzip2 => "BZIP2".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AeadAlgo {
+    OCB,
+    EAX,
+}
+
+#[doc(hidden)]
+impl AeadAlgo {
+    pub fn value(&self) -> String {
+        match &self {
+            AeadAlgo::OCB => "OCB".to_string(),
+            AeadAlgo::EAX => "EAX".to_string(),
+        }
+    }
+
+    // from the OpenPGP algorithm id used in the status output of gpg
+    pub fn from_id(id: &str) -> Option<AeadAlgo> {
+        match id {
+            "1" => Some(AeadAlgo::EAX),
+            "2" => Some(AeadAlgo::OCB),
+            _ => None,
+        }
+    }
+}
+
+// the packet format gpg will follow ( --compliance )
+#[derive(Debug, Clone, PartialEq)]
+pub enum Compliance {
+    GnuPG,
+    OpenPGP,
+    RFC4880,
+    RFC4880bis,
+}
+
+#[doc(hidden)]
+impl Compliance {
+    pub fn value(&self) -> String {
+        match &self {
+            Compliance::GnuPG => "gnupg".to_string(),
+            Compliance::OpenPGP => "openpgp".to_string(),
+            Compliance::RFC4880 => "rfc4880".to_string(),
+            Compliance::RFC4880bis => "rfc4880bis".to_string(),
+        }
+    }
+}
+
+// the type of an OpenPGP packet ( from the tag of the packet )
+#[derive(Debug, Clone, PartialEq)]
+pub enum PacketType {
+    PublicKeyEncryptedSessionKey,
+    Signature,
+    SymmetricKeyEncryptedSessionKey,
+    OnePassSignature,
+    SecretKey,
+    PublicKey,
+    SecretSubkey,
+    Compressed,
+    SymmetricallyEncryptedData,
+    Marker,
+    LiteralData,
+    Trust,
+    UserId,
+    PublicSubkey,
+    UserAttribute,
+    EncryptedIntegrityProtectedData,
+    ModificationDetectionCode,
+    AeadEncryptedData,
+    Control,
+    Unknown(u8),
+}
+
+#[doc(hidden)]
+impl PacketType {
+    pub fn value(&self) -> String {
+        match &self {
+            PacketType::PublicKeyEncryptedSessionKey => "pubkey enc".to_string(),
+            PacketType::Signature => "signature".to_string(),
+            PacketType::SymmetricKeyEncryptedSessionKey => "symkey enc".to_string(),
+            PacketType::OnePassSignature => "onepass_sig".to_string(),
+            PacketType::SecretKey => "secret key".to_string(),
+            PacketType::PublicKey => "public key".to_string(),
+            PacketType::SecretSubkey => "secret sub key".to_string(),
+            PacketType::Compressed => "compressed".to_string(),
+            PacketType::SymmetricallyEncryptedData => "encrypted data".to_string(),
+            PacketType::Marker => "marker".to_string(),
+            PacketType::LiteralData => "literal data".to_string(),
+            PacketType::Trust => "trust".to_string(),
+            PacketType::UserId => "user ID".to_string(),
+            PacketType::PublicSubkey => "public sub key".to_string(),
+            PacketType::UserAttribute => "attribute".to_string(),
+            PacketType::EncryptedIntegrityProtectedData => "encrypted data".to_string(),
+            PacketType::ModificationDetectionCode => "mdc".to_string(),
+            PacketType::AeadEncryptedData => "aead encrypted".to_string(),
+            PacketType::Control => "gpg control".to_string(),
+            PacketType::Unknown(tag) => format!("packet {}", tag),
+        }
+    }
+
+    // from the tag of the packet shown by gpg --list-packets
+    pub fn from_tag(tag: u8) -> PacketType {
+        match tag {
+            1 => PacketType::PublicKeyEncryptedSessionKey,
+            2 => PacketType::Signature,
+            3 => PacketType::SymmetricKeyEncryptedSessionKey,
+            4 => PacketType::OnePassSignature,
+            5 => PacketType::SecretKey,
+            6 => PacketType::PublicKey,
+            7 => PacketType::SecretSubkey,
+            8 => PacketType::Compressed,
+            9 => PacketType::SymmetricallyEncryptedData,
+            10 => PacketType::Marker,
+            11 => PacketType::LiteralData,
+            12 => PacketType::Trust,
+            13 => PacketType::UserId,
+            14 => PacketType::PublicSubkey,
+            17 => PacketType::UserAttribute,
+            18 => PacketType::EncryptedIntegrityProtectedData,
+            19 => PacketType::ModificationDetectionCode,
+            20 => PacketType::AeadEncryptedData,
+            63 => PacketType::Control,
+            _ => PacketType::Unknown(tag),
+        }
+    }
+}
+
+// the kind of signature produced when signing
+#[derive(Debug, Clone, PartialEq)]
+pub enum SignMode {
+    // the data and its signature in 1 ( binary or armored ) message
+    Inline,
+    // the data kept readable with the signature appended as ascii armor
+    Clear,
+    // the signature only, in a separate file
+    Detached,
+    // the signature only, made over the data as text with canonical line endings
+    DetachedText,
+}
+
+#[doc(hidden)]
+impl SignMode {
+    // used as the prefix of the default output file name
+    pub fn value(&self) -> String {
+        match &self {
+            SignMode::Inline => "embedded".to_string(),
+            SignMode::Clear => "clear".to_string(),
+            SignMode::Detached => "detach".to_string(),
+            SignMode::DetachedText => "detach_text".to_string(),
+        }
+    }
+}
+
+// the reason a signature was not counted by a verification policy
+#[derive(Debug, Clone, PartialEq)]
+pub enum PolicyRejection {
+    BadSignature,
+    VerificationError,
+    RevokedKey,
+    ExpiredSignature,
+    ExpiredKey,
+    NotAllowedSigner,
+    TrustTooLow,
+}
+
+#[doc(hidden)]
+impl PolicyRejection {
+    pub fn value(&self) -> String {
+        match &self {
+            PolicyRejection::BadSignature => "bad signature".to_string(),
+            PolicyRejection::VerificationError => "signature could not be verified".to_string(),
+            PolicyRejection::RevokedKey => "signer key was revoked".to_string(),
+            PolicyRejection::ExpiredSignature => "signature was expired".to_string(),
+            PolicyRejection::ExpiredKey => "signer key was expired".to_string(),
+            PolicyRejection::NotAllowedSigner => "signer was not allowed".to_string(),
+            PolicyRejection::TrustTooLow => "signer key was not trusted enough".to_string(),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
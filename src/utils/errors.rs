@@ -37,6 +37,7 @@ pub enum GPGErrorType {
     InvalidReasonCode(String),
     FileNotFoundError(String),
     FileNotProvidedError(String),
+    RecipientGroupError(String),
 }
 
 #[doc(hidden)]
@@ -60,6 +61,7 @@ impl Display for GPGErrorType {
             GPGErrorType::InvalidReasonCode(err) => write!(f, "[InvalidReasonCode] {}", err),
             GPGErrorType::FileNotFoundError(err) => write!(f, "[FileNotFoundError] {}", err),
             GPGErrorType::FileNotProvidedError(err) => write!(f, "[FileNotProvidedError] {}", err),
+            GPGErrorType::RecipientGroupError(err) => write!(f, "[RecipientGroupError] {}", err),
         }
     }
 }
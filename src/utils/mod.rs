@@ -1,3 +1,5 @@
+#[doc(hidden)]
+pub mod archive;
 pub mod enums;
 pub mod errors;
 pub mod response;
@@ -1,6 +1,9 @@
 use std::collections::HashMap;
 
-use super::enums::{DeleteProblem, Operation};
+use super::enums::{
+    AeadAlgo, CipherAlgo, DeleteProblem, DigestAlgo, LocateMechanism, Operation, PacketType,
+    PolicyRejection, PublicKeyAlgo, TrustLevel,
+};
 
 //*******************************************************
 
@@ -19,6 +22,8 @@ pub struct CmdResult {
     pub debug_log: Option<Vec<String>>,
     pub problem: Option<Vec<HashMap<String, String>>>,
     pub success: bool,
+    // a key was skipped due to its passphrase, it was only a failure if nothing else could decrypt the data
+    pub skipped_passphrase: bool,
 }
 
 #[doc(hidden)]
@@ -33,6 +38,7 @@ impl CmdResult {
             debug_log: None,
             problem: None,
             success: true,
+            skipped_passphrase: false,
         }
     }
 
@@ -56,11 +62,12 @@ impl CmdResult {
             // for export secret key, there can be failure at the end if there are 1 or more key no exported due to passphrase
             // in this case if there are any key that exported even just partially, we should still consider it as success
             // for it to not export anything, there will be gpg: WARNING: nothing exported in the output
-            if self.operation == Operation::ExportSecretKey {
+            if self.operation == Operation::ExportSecretKey || self.operation == Operation::ExportSecretSubkey {
                 if self.raw_data.as_ref().unwrap().contains("WARNING: nothing exported") {
                     self.success = false;
                 } else {
                     self.success = true;
+                    self.skipped_passphrase = false;
                 }
             } else{
                 self.success = false;
@@ -102,7 +109,7 @@ impl CmdResult {
             }
         } else if keyword == "NO_PASSPHRASE" {
             if !self.raw_data.as_ref().unwrap().contains("No passphrase given - skipped") {
-                self.success = false;
+                self.skipped_passphrase = true;
                 let mut problem: HashMap<String, String> = HashMap::new();
                 problem.insert("passphrase".to_string(), value);
                 if self.problem.is_none() {
@@ -120,8 +127,46 @@ impl CmdResult {
             } else {
                 self.problem.as_mut().unwrap().push(problem);
             }
-        } else if keyword == "BAD_PASSPHRASE" {
+        } else if keyword == "IMPORT_RES" {
+            // for receiving key from keyserver, gpg will not report a failure if the key was rejected
+            // so we check if anything was imported or unchanged
+            if self.operation == Operation::ReceiveKey {
+                let values = value.split_whitespace().collect::<Vec<&str>>();
+                if values.len() > 4 && values[2] == "0" && values[4] == "0" {
+                    self.success = false;
+                    let mut problem: HashMap<String, String> = HashMap::new();
+                    problem.insert("import".to_string(), "no key received from keyserver".to_string());
+                    if self.problem.is_none() {
+                        self.problem = Some(vec![problem]);
+                    } else {
+                        self.problem.as_mut().unwrap().push(problem);
+                    }
+                }
+            }
+        } else if keyword == "DECRYPTION_FAILED" {
+            // gpg exit with non-zero and emit DECRYPTION_FAILED ( ex: wrong passphrase for symmetric encryption ),
+            // the result is only decided by the status, so it was marked as a failure here
             self.success = false;
+            self.status_message = Some("decryption failed".to_string());
+            let mut problem: HashMap<String, String> = HashMap::new();
+            problem.insert("decrypt".to_string(), "decryption failed".to_string());
+            if self.problem.is_none() {
+                self.problem = Some(vec![problem]);
+            } else {
+                self.problem.as_mut().unwrap().push(problem);
+            }
+        } else if keyword == "DECRYPTION_OKAY" {
+            // for data encrypted to multiple keys, a key skipped due to its passphrase should not be a failure
+            // if the data was decrypted with one of the other keys, any other failure was kept as it is
+            self.skipped_passphrase = false;
+            if self.problem.is_some() {
+                self.problem.as_mut().unwrap().retain(|p| !p.contains_key("passphrase"));
+                if self.problem.as_ref().unwrap().len() == 0 {
+                    self.problem = None;
+                }
+            }
+        } else if keyword == "BAD_PASSPHRASE" {
+            self.skipped_passphrase = true;
             let mut problem: HashMap<String, String> = HashMap::new();
             problem.insert("passphrase".to_string(), value);
             if self.problem.is_none() {
@@ -134,6 +179,10 @@ impl CmdResult {
 
     pub fn set_return_code(&mut self, return_code: i32) {
         self.return_code = Some(return_code);
+        // the process has ended, a passphrase failure that was not recovered is a failure of the result
+        if self.skipped_passphrase {
+            self.success = false;
+        }
     }
 
     pub fn capture_debug_log(&mut self, debug_log: String) {
@@ -145,7 +194,7 @@ impl CmdResult {
     }
 
     pub fn is_success(&self) -> bool {
-        return self.success;
+        return self.success && !self.skipped_passphrase;
     }
 
     pub fn get_error_message(&mut self) -> String {
@@ -202,6 +251,8 @@ pub struct ListKeyResult {
     pub sigs: Vec<Vec<String>>,
     pub subkeys: Vec<Subkey>,
     pub fingerprint: String,
+    // true if only a stub of the secret key is available locally ( shown as sec# )
+    pub secret_stub: bool,
 }
 
 #[doc(hidden)]
@@ -234,6 +285,7 @@ impl ListKeyResult {
             sigs: vec![],
             subkeys: vec![],
             fingerprint: String::from(""),
+            secret_stub: false,
         };
         let mut idx: usize = 0;
         if idx < args.len() {
@@ -315,6 +367,7 @@ impl ListKeyResult {
         if idx < args.len() {
             result.comment = String::from(args[idx]);
         }
+        result.secret_stub = result.r#type == "sec" && result.token == "#";
         return result;
     }
 }
@@ -342,6 +395,8 @@ pub struct Subkey {
     pub updated: String,
     pub keygrip: String,
     pub fingerprint: String,
+    // true if only a stub of the secret subkey is available locally ( shown as ssb# )
+    pub secret_stub: bool,
 }
 
 #[doc(hidden)]
@@ -369,6 +424,7 @@ impl Subkey {
             updated: String::from("Unavailable"),
             keygrip: String::from(""),
             fingerprint: String::from(""),
+            secret_stub: false,
         };
         let mut idx: usize = 0;
         if idx < args.len() {
@@ -446,10 +502,339 @@ impl Subkey {
         if idx < args.len() {
             result.updated = String::from(args[idx]);
         }
+        result.secret_stub = result.r#type == "ssb" && result.token == "#";
         return result
     }
 }
 
+//*******************************************************
+
+//          RELATED TO KEYSERVER SEARCH RESULT
+
+//*******************************************************
+#[derive(Debug, Clone)]
+pub struct SearchKeyResult {
+    // https://github.com/gpg/gnupg/blob/master/doc/DETAILS ( machine readable keyserver listing )
+    pub keyid: String,
+    pub algo: String,
+    pub length: String,
+    pub date: String,
+    pub expires: String,
+    pub flags: String,
+    pub uids: Vec<String>,
+}
+
+#[doc(hidden)]
+impl SearchKeyResult {
+    pub fn new(args: Vec<&str>) -> Self {
+        // pub:<keyid>:<algo>:<keylen>:<creationdate>:<expirationdate>:<flags>
+        let field = |idx: usize| -> String { args.get(idx).unwrap_or(&"").to_string() };
+        return SearchKeyResult {
+            keyid: field(1),
+            algo: field(2),
+            length: field(3),
+            date: field(4),
+            expires: field(5),
+            flags: field(6),
+            uids: vec![],
+        };
+    }
+}
+
+//*******************************************************
+
+//            RELATED TO LOCATE KEY RESULT
+
+//*******************************************************
+#[derive(Debug, Clone)]
+pub struct LocateKeyResult {
+    // the email address that was looked up
+    pub email: String,
+    // the key resolved for the email address, None if no mechanism could find it
+    pub key: Option<ListKeyResult>,
+    // the mechanism that found the key
+    pub mechanism: Option<LocateMechanism>,
+}
+
+//*******************************************************
+
+//              RELATED TO WKD ENTRY RESULT
+
+//*******************************************************
+#[derive(Debug, Clone)]
+pub struct WkdEntry {
+    // the address that was published
+    pub address: String,
+    // the z-base-32 encoded SHA-1 hash of the local part of the address
+    pub hash: String,
+    // fingerprint(s) of the key(s) published for the address
+    pub fingerprints: Vec<String>,
+    // path to the file written in the WKD tree
+    pub path: String,
+}
+
+//*******************************************************
+
+//              RELATED TO COPY KEY RESULT
+
+//*******************************************************
+#[derive(Debug, Clone)]
+pub struct CopyKeyResult {
+    // fingerprint of the key that was copied, or the keyid as it was given when no key matched it
+    pub fingerprint: String,
+    // false if no key in this homedir matched the keyid, nothing was copied for it
+    pub found: bool,
+    // if the public key was new or updated ( new uids, signatures or subkeys ) in the target homedir
+    pub public: bool,
+    // if the secret key was new in the target homedir
+    pub secret: bool,
+    // the ownertrust value that was copied, None if no ownertrust was copied
+    pub ownertrust: Option<String>,
+    // if the target homedir already hold the key without any change
+    pub unchanged: bool,
+}
+
+//*******************************************************
+
+//              RELATED TO DECRYPT RESULT
+
+//*******************************************************
+#[derive(Debug, Clone)]
+pub struct DecryptResult {
+    // long keyid(s) that the data was encrypted to ( ENC_TO ), 0000000000000000 for hidden receipients
+    pub recipients: Vec<String>,
+    // if the data was encrypted symmetrically ( NEED_PASSPHRASE_SYM )
+    pub symmetric: bool,
+    // fingerprint of the ( sub )key that decrypted the data
+    pub decryption_key: Option<String>,
+    // fingerprint of the primary key of the ( sub )key that decrypted the data
+    pub decryption_primary_key: Option<String>,
+    // cipher algorithm used for the data
+    pub cipher_algo: Option<CipherAlgo>,
+    // AEAD mode used for the data, None if it was protected with MDC instead
+    pub aead_algo: Option<AeadAlgo>,
+    // if the integrity of the data was verified ( GOODMDC or AEAD )
+    pub integrity_protected: bool,
+    // the filename embedded in the data
+    pub filename: Option<String>,
+    // the timestamp embedded in the data
+    pub timestamp: Option<String>,
+    // the length of the decrypted data
+    pub plaintext_length: Option<u64>,
+    // the session key of the data in the format of <algo>:<hexdigits> [only if decrypted with show_session_key]
+    pub session_key: Option<String>,
+    // if gpg went through the whole decryption ( BEGIN_DECRYPTION to END_DECRYPTION )
+    pub completed: bool,
+    // if the data was decrypted successfully ( DECRYPTION_OKAY )
+    pub decrypted: bool,
+    // the signature(s) of the data, empty if the data was not signed
+    pub signatures: Vec<VerifyResult>,
+}
+
+//*******************************************************
+
+//              RELATED TO VERIFY RESULT
+
+//*******************************************************
+#[derive(Debug, Clone)]
+pub struct VerifyResult {
+    // status of the signature ( GOODSIG, BADSIG, ERRSIG, EXPSIG, EXPKEYSIG or REVKEYSIG )
+    pub status: String,
+    // long keyid ( or fingerprint ) of the key that made the signature
+    pub keyid: String,
+    // the user id of the key that made the signature
+    pub username: Option<String>,
+    // the user id the signer embedded in the signature ( NEWSIG )
+    pub signer_uid: Option<String>,
+    // fingerprint of the ( sub )key that made the signature ( VALIDSIG )
+    pub fingerprint: Option<String>,
+    // fingerprint of the primary key of the ( sub )key that made the signature
+    pub primary_fingerprint: Option<String>,
+    // the timestamp of when the signature was created
+    pub timestamp: Option<String>,
+    // the timestamp of when the signature expire, None if it do not expire
+    pub expire_timestamp: Option<String>,
+    // digest algorithm used for the signature
+    pub hash_algo: Option<DigestAlgo>,
+    // public key algorithm of the key that made the signature
+    pub pubkey_algo: Option<PublicKeyAlgo>,
+    // the trust level of the key that made the signature ( TRUST_* ), None if gpg did not report it
+    pub trust_level: Option<TrustLevel>,
+    // if the signature was cryptographically valid ( VALIDSIG ), the key may still be expired, revoked or untrusted
+    pub valid: bool,
+    // if the signature was good ( GOODSIG ) and made with a fully or ultimately trusted key
+    pub trusted: bool,
+    // notation(s) of the signature as (name, value, critical)
+    pub notations: Vec<(String, String, bool)>,
+    // url of the signature policy
+    pub policy_url: Option<String>,
+    // url of the preferred keyserver of the signer
+    pub keyserver_url: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct StreamVerifyResult {
+    // if gpg reported the verification as successful, false if any signature was bad ( ex: BADSIG )
+    pub verified: bool,
+    // result of each signature that was checked, including the one(s) that failed the verification
+    pub signatures: Vec<VerifyResult>,
+}
+
+//*******************************************************
+
+//              RELATED TO SIGN RESULT
+
+//*******************************************************
+#[derive(Debug, Clone)]
+pub struct SignResult {
+    // type of the signature created ( D for detached, C for cleartext, S for standard )
+    pub sig_type: String,
+    // public key algorithm of the key that made the signature
+    pub pubkey_algo: Option<PublicKeyAlgo>,
+    // digest algorithm used for the signature
+    pub hash_algo: Option<DigestAlgo>,
+    // class of the signature in hex ( ex: 00 for binary, 01 for text )
+    pub sig_class: String,
+    // the timestamp of when the signature was created
+    pub timestamp: String,
+    // fingerprint of the ( sub )key that made the signature
+    pub fingerprint: String,
+}
+
+//*******************************************************
+
+//            RELATED TO VERIFICATION POLICY
+
+//*******************************************************
+#[derive(Debug, Clone)]
+pub struct PolicyResult {
+    // if the number of accepted signer(s) reached the minimum count of the policy,
+    // each accepted signer already met the other requirement(s) ( allowed signer, trust and expiry )
+    pub passed: bool,
+    // primary fingerprint(s) of the allowed signer(s) whose signature met the policy ( counted once per signer )
+    pub accepted_signers: Vec<String>,
+    // signature(s) that were not counted and the reason
+    pub rejected: Vec<PolicyViolation>,
+    // the result of every signature found
+    pub signatures: Vec<VerifyResult>,
+}
+
+#[derive(Debug, Clone)]
+pub struct PolicyViolation {
+    // long keyid ( or fingerprint ) of the key that made the signature
+    pub keyid: String,
+    // fingerprint of the primary key that made the signature, None if the key was not found
+    pub primary_fingerprint: Option<String>,
+    // the requirement that the signature failed
+    pub reason: PolicyRejection,
+}
+
+//*******************************************************
+
+//            RELATED TO MESSAGE INSPECTION
+
+//*******************************************************
+#[derive(Debug, Clone)]
+pub struct MessageInfo {
+    // if the data was ascii armored
+    pub armored: bool,
+    // if the data was encrypted ( with key or passphrase )
+    pub encrypted: bool,
+    // long keyid(s) that the data was encrypted to, 0000000000000000 for hidden receipients
+    pub recipients: Vec<String>,
+    // if the data was encrypted symmetrically ( can be combined with recipients )
+    pub symmetric: bool,
+    // cipher algorithm of the symmetric passphrase, None if not encrypted symmetrically
+    pub cipher_algo: Option<CipherAlgo>,
+    // if the encrypted data was protected with MDC or AEAD
+    pub integrity_protected: bool,
+    // if the data was signed [not known for encrypted data, as the signature is inside the encrypted data]
+    pub signed: bool,
+    // if the data was a clearsigned message
+    pub clearsigned: bool,
+    // if the data was a detached signature ( signature without data )
+    pub detached_signature: bool,
+    // long keyid(s) of the key(s) that signed the data
+    pub signers: Vec<String>,
+    // the filename embedded in the data [not known for encrypted data]
+    pub filename: Option<String>,
+    // if the data was a key ( public or secret key )
+    pub key: bool,
+    // the packet(s) of the data in the order of gpg --list-packets
+    pub packets: Vec<PacketInfo>,
+}
+
+#[derive(Debug, Clone)]
+pub struct PacketInfo {
+    // the type of the packet
+    pub packet_type: PacketType,
+    // the tag of the packet
+    pub tag: u8,
+    // the offset of the packet in the ( dearmored ) data
+    pub offset: u64,
+    // the length of the packet body, None for partial or indeterminate length
+    pub length: Option<u64>,
+    // long keyid of the packet ( recipient, signer or key ), None if not related to a key
+    pub keyid: Option<String>,
+    // the description line(s) of the packet from gpg
+    pub details: Vec<String>,
+}
+
+//*******************************************************
+
+//              RELATED TO RECIPIENT GROUP
+
+//*******************************************************
+#[derive(Debug, Clone)]
+pub struct GroupResult {
+    // name of the group
+    pub name: String,
+    // fingerprint(s) of the usable member key(s), used as recipients when encrypting to the group
+    pub fingerprints: Vec<String>,
+    // member(s) that no key was found for
+    pub missing: Vec<String>,
+    // member(s) with an expired key
+    pub expired: Vec<String>,
+    // member(s) with a revoked key
+    pub revoked: Vec<String>,
+}
+
+//*******************************************************
+
+//              RELATED TO RE-ENCRYPTION
+
+//*******************************************************
+#[derive(Debug, Clone)]
+pub struct ReencryptResult {
+    // path of the file that was re-encrypted
+    pub input: String,
+    // path of the re-encrypted file
+    pub output: String,
+    // if the file was re-encrypted successfully
+    pub success: bool,
+    // the error message if the file failed to be re-encrypted
+    pub error: Option<String>,
+}
+
+//*******************************************************
+
+//              RELATED TO ARCHIVE LISTING
+
+//*******************************************************
+#[derive(Debug, Clone)]
+pub struct ArchiveEntry {
+    // path of the entry in the archive
+    pub path: String,
+    // if the entry was a directory
+    pub directory: bool,
+    // the permission of the entry ( ex: -rw-r--r-- )
+    pub mode: String,
+    // size of the entry in bytes
+    pub size: u64,
+    // the modification time of the entry ( YYYY-MM-DD HH:MM:SS )
+    pub modified: String,
+}
+
 //  a result handler for handling the result of keys action ( mainly of retrieve key list related action )
 pub struct ListKey {
     // in_subkey: include subkeys
@@ -1,4 +1,5 @@
 use std::{
+    collections::HashMap,
     fs::{metadata, File}, io::{Seek, Write}, path::{Path, PathBuf}, process::Command
 };
 
@@ -14,7 +15,10 @@ use regex::Regex;
 use crate::utils::response::ListKey;
 
 use super::errors::{GPGError, GPGErrorType};
-use super::response::{CmdResult, ListKeyResult};
+use super::enums::{AeadAlgo, CipherAlgo, DigestAlgo, PacketType, PublicKeyAlgo, TrustLevel};
+use super::response::{
+    ArchiveEntry, CmdResult, DecryptResult, ListKeyResult, MessageInfo, PacketInfo, SearchKeyResult, SignResult, VerifyResult,
+};
 
 const VERSION_REGEX: &str = r"^cfg:version:(\d+(\.\d+)*)";
 const LIST_KEY_KEYWORDS: [&str; 8] = ["pub", "uid", "sec", "fpr", "sub", "ssb", "sig", "grp"];
@@ -184,10 +188,583 @@ pub fn decode_list_key_result(result: CmdResult) -> Vec<ListKeyResult> {
     return r.get_list_key_result();
 }
 
+pub fn decode_search_key_result(result: CmdResult) -> Vec<SearchKeyResult> {
+    // the machine readable keyserver listing was made up of info, pub and uid records
+    // info:<version>:<count>
+    // pub:<keyid>:<algo>:<keylen>:<creationdate>:<expirationdate>:<flags>
+    // uid:<escaped uid string>:<creationdate>:<expirationdate>:<flags>
+    let output_lines = result.get_raw_data().unwrap_or_default();
+    let mut search_results: Vec<SearchKeyResult> = Vec::new();
+    for output in output_lines.split("\n") {
+        let l_key_pair: Vec<&str> = output.trim().split(":").collect();
+        match l_key_pair[0] {
+            "pub" => search_results.push(SearchKeyResult::new(l_key_pair)),
+            "uid" => {
+                if l_key_pair.len() > 1 && search_results.len() > 0 {
+                    let len: usize = search_results.len();
+                    search_results[len - 1]
+                        .uids
+                        .push(decode_percent_escape(l_key_pair[1]));
+                }
+            }
+            _ => {}
+        }
+    }
+    return search_results;
+}
+
+// retrieve the ( fingerprint, reason ) of each IMPORT_OK status line of an import
+pub fn decode_import_ok_result(result: &CmdResult) -> Vec<(String, u32)> {
+    // IMPORT_OK <reason> [<fingerprint>]
+    // reason was a bit field -> 0 not changed, 1 new key, 2 new uids, 4 new signatures, 8 new subkeys, 16 contains private key
+    let output_lines = result.get_raw_data().unwrap_or_default();
+    let mut import_ok: Vec<(String, u32)> = Vec::new();
+    for output in output_lines.split("\n") {
+        let output: &str = output.trim();
+        if !output.starts_with("[GNUPG:] IMPORT_OK ") {
+            continue;
+        }
+        let values: Vec<&str> = output["[GNUPG:] IMPORT_OK ".len()..].split_whitespace().collect();
+        if values.len() < 2 {
+            continue;
+        }
+        import_ok.push((values[1].to_string(), values[0].parse::<u32>().unwrap_or(0)));
+    }
+    return import_ok;
+}
+
+// retrieve the ( item, values ) of each cfg line of gpg --list-config --with-colons
+pub fn decode_list_config_result(result: &CmdResult) -> HashMap<String, Vec<String>> {
+    // cfg:<item>:<value>;<value>;...
+    let output_lines = result.get_raw_data().unwrap_or_default();
+    let mut config: HashMap<String, Vec<String>> = HashMap::new();
+    for output in output_lines.split("\n") {
+        let output: &str = output.trim();
+        if !output.starts_with("cfg:") {
+            continue;
+        }
+        let values: Vec<&str> = output["cfg:".len()..].splitn(2, ':').collect();
+        if values.len() < 2 {
+            continue;
+        }
+        config.insert(
+            values[0].to_string(),
+            values[1].split(";").map(|v| v.to_string()).collect(),
+        );
+    }
+    return config;
+}
+
+// retrieve the group(s) defined in gpg.conf from gpg --list-config --with-colons
+pub fn decode_group_config(result: &CmdResult) -> HashMap<String, Vec<String>> {
+    // cfg:group:<name>:<member>;<member>;...
+    let output_lines = result.get_raw_data().unwrap_or_default();
+    let mut groups: HashMap<String, Vec<String>> = HashMap::new();
+    for output in output_lines.split("\n") {
+        let output: &str = output.trim();
+        if !output.starts_with("cfg:group:") {
+            continue;
+        }
+        let values: Vec<&str> = output["cfg:group:".len()..].splitn(2, ':').collect();
+        if values.len() < 2 {
+            continue;
+        }
+        groups.insert(
+            values[0].to_string(),
+            values[1]
+                .split(";")
+                .filter(|v| v.len() > 0)
+                .map(|v| v.to_string())
+                .collect(),
+        );
+    }
+    return groups;
+}
+
+// retrieve the decryption metadata from the status line(s) of a decryption
+pub fn decode_decrypt_result(result: &CmdResult) -> DecryptResult {
+    let output_lines = result.get_raw_data().unwrap_or_default();
+    let mut decrypt_result: DecryptResult = DecryptResult {
+        recipients: vec![],
+        symmetric: false,
+        decryption_key: None,
+        decryption_primary_key: None,
+        cipher_algo: None,
+        aead_algo: None,
+        integrity_protected: false,
+        filename: None,
+        timestamp: None,
+        plaintext_length: None,
+        session_key: None,
+        completed: false,
+        decrypted: false,
+        signatures: decode_verify_result(result),
+    };
+    let mut begin: bool = false;
+    for output in output_lines.split("\n") {
+        let output: &str = output.trim_end();
+        if !output.starts_with("[GNUPG:] ") {
+            continue;
+        }
+        let mut parts = output["[GNUPG:] ".len()..].splitn(2, char::is_whitespace);
+        let keyword: &str = parts.next().unwrap_or("");
+        let value: &str = parts.next().unwrap_or("");
+        let values: Vec<&str> = value.split_whitespace().collect();
+        match keyword {
+            // ENC_TO <long_keyid> <keytype> <keylength>
+            "ENC_TO" => {
+                if values.len() > 0 {
+                    decrypt_result.recipients.push(values[0].to_string());
+                }
+            }
+            "NEED_PASSPHRASE_SYM" => {
+                decrypt_result.symmetric = true;
+            }
+            // DECRYPTION_KEY <fpr> <fpr2> <otrust>
+            "DECRYPTION_KEY" => {
+                decrypt_result.decryption_key = values.get(0).map(|v| v.to_string());
+                decrypt_result.decryption_primary_key = values.get(1).map(|v| v.to_string());
+            }
+            // DECRYPTION_INFO <mdc_method> <sym_algo> [<aead_algo>]
+            "DECRYPTION_INFO" => {
+                decrypt_result.cipher_algo = CipherAlgo::from_id(values.get(1).unwrap_or(&""));
+                decrypt_result.aead_algo = AeadAlgo::from_id(values.get(2).unwrap_or(&""));
+                if decrypt_result.aead_algo.is_some() {
+                    decrypt_result.integrity_protected = true;
+                }
+            }
+            // PLAINTEXT <format> <timestamp> <filename>
+            "PLAINTEXT" => {
+                decrypt_result.timestamp = values.get(1).map(|v| v.to_string());
+                let filename: String = decode_percent_escape(values.get(2).unwrap_or(&""));
+                if filename.len() > 0 {
+                    decrypt_result.filename = Some(filename);
+                }
+            }
+            "PLAINTEXT_LENGTH" => {
+                decrypt_result.plaintext_length = values.get(0).and_then(|v| v.parse::<u64>().ok());
+            }
+            "GOODMDC" => {
+                decrypt_result.integrity_protected = true;
+            }
+            // SESSION_KEY <algo>:<hexdigits>
+            "SESSION_KEY" => {
+                decrypt_result.session_key = values.get(0).map(|v| v.to_string());
+            }
+            "BEGIN_DECRYPTION" => {
+                begin = true;
+            }
+            "END_DECRYPTION" => {
+                decrypt_result.completed = begin;
+            }
+            "DECRYPTION_OKAY" => {
+                decrypt_result.decrypted = true;
+            }
+            "DECRYPTION_FAILED" => {
+                decrypt_result.decrypted = false;
+            }
+            _ => {}
+        }
+    }
+    return decrypt_result;
+}
+
+// retrieve the signature(s) from the status line(s) of a verification ( or decryption of signed data )
+pub fn decode_verify_result(result: &CmdResult) -> Vec<VerifyResult> {
+    // every signature start with NEWSIG, followed by its result and the VALIDSIG / TRUST_* of a good signature
+    let output_lines = result.get_raw_data().unwrap_or_default();
+    let mut signatures: Vec<VerifyResult> = vec![];
+    for output in output_lines.split("\n") {
+        let output: &str = output.trim_end();
+        if !output.starts_with("[GNUPG:] ") {
+            continue;
+        }
+        let mut parts = output["[GNUPG:] ".len()..].splitn(2, char::is_whitespace);
+        let keyword: &str = parts.next().unwrap_or("");
+        let value: &str = parts.next().unwrap_or("");
+        match keyword {
+            // NEWSIG [<signers_uid>]
+            "NEWSIG" => {
+                let mut signature: VerifyResult = new_verify_result();
+                if value.len() > 0 {
+                    signature.signer_uid = Some(value.to_string());
+                }
+                signatures.push(signature);
+            }
+            // KEY_CONSIDERED <fpr> <flags>
+            "KEY_CONSIDERED" => {
+                if let Some(signature) = signatures.last_mut() {
+                    if signature.status.len() == 0 {
+                        signature.primary_fingerprint =
+                            value.split_whitespace().next().map(|v| v.to_string());
+                    }
+                }
+            }
+            // <keyword> <long_keyid_or_fpr> <username>
+            "GOODSIG" | "BADSIG" | "EXPSIG" | "EXPKEYSIG" | "REVKEYSIG" => {
+                let values: Vec<&str> = value.splitn(2, char::is_whitespace).collect();
+                let signature: &mut VerifyResult = current_verify_result(&mut signatures);
+                signature.status = keyword.to_string();
+                signature.keyid = values[0].to_string();
+                signature.username = values.get(1).map(|v| v.to_string());
+            }
+            // ERRSIG <keyid> <pkalgo> <hashalgo> <sig_class> <time> <rc> [<fpr>]
+            "ERRSIG" => {
+                let values: Vec<&str> = value.split_whitespace().collect();
+                let signature: &mut VerifyResult = current_verify_result(&mut signatures);
+                signature.status = keyword.to_string();
+                signature.keyid = values.get(0).unwrap_or(&"").to_string();
+                signature.fingerprint = values.get(6).map(|v| v.to_string());
+                signature.timestamp = values.get(4).map(|v| v.to_string());
+                signature.hash_algo = DigestAlgo::from_id(values.get(2).unwrap_or(&""));
+                signature.pubkey_algo = PublicKeyAlgo::from_id(values.get(1).unwrap_or(&""));
+            }
+            // VALIDSIG <fpr> <sig_creation_date> <sig-timestamp> <expire-timestamp> <sig-version> <reserved>
+            //          <pubkey-algo> <hash-algo> <sig-class> [ <primary-key-fpr> ]
+            "VALIDSIG" => {
+                let values: Vec<&str> = value.split_whitespace().collect();
+                if let Some(signature) = signatures.last_mut() {
+                    signature.valid = true;
+                    signature.fingerprint = values.get(0).map(|v| v.to_string());
+                    signature.timestamp = values.get(2).map(|v| v.to_string());
+                    signature.expire_timestamp =
+                        values.get(3).filter(|v| **v != "0").map(|v| v.to_string());
+                    signature.pubkey_algo = PublicKeyAlgo::from_id(values.get(6).unwrap_or(&""));
+                    signature.hash_algo = DigestAlgo::from_id(values.get(7).unwrap_or(&""));
+                    signature.primary_fingerprint = values.get(9).map(|v| v.to_string());
+                }
+            }
+            // NOTATION_NAME <name>, followed by NOTATION_FLAGS <critical> <human_readable>
+            // and 1 or more NOTATION_DATA <string> ( long value will be split into multiple lines )
+            "NOTATION_NAME" => {
+                if let Some(signature) = signatures.last_mut() {
+                    signature.notations.push((value.to_string(), "".to_string(), false));
+                }
+            }
+            "NOTATION_FLAGS" => {
+                if let Some(notation) = signatures.last_mut().and_then(|s| s.notations.last_mut()) {
+                    notation.2 = value.split_whitespace().next() == Some("1");
+                }
+            }
+            "NOTATION_DATA" => {
+                if let Some(notation) = signatures.last_mut().and_then(|s| s.notations.last_mut()) {
+                    notation.1.push_str(&decode_percent_escape(value));
+                }
+            }
+            // POLICY_URL <string>
+            "POLICY_URL" => {
+                if let Some(signature) = signatures.last_mut() {
+                    signature.policy_url = Some(decode_percent_escape(value));
+                }
+            }
+            // SIG_SUBPACKET <type> <flags> <len> <data>, type 24 is the preferred keyserver
+            "SIG_SUBPACKET" => {
+                let values: Vec<&str> = value.splitn(4, char::is_whitespace).collect();
+                if let Some(signature) = signatures.last_mut() {
+                    if values[0] == "24" && values.len() == 4 {
+                        signature.keyserver_url = Some(decode_percent_escape(values[3]));
+                    }
+                }
+            }
+            // TRUST_<level> 0 [<validation_model>]
+            _ if keyword.starts_with("TRUST_") => {
+                if let Some(signature) = signatures.last_mut() {
+                    signature.trust_level = TrustLevel::from_status(keyword);
+                }
+            }
+            _ => {}
+        }
+    }
+    // a valid signature is only trusted if the key was fully or ultimately trusted
+    signatures.retain(|signature| signature.status.len() > 0);
+    for signature in signatures.iter_mut() {
+        signature.trusted = signature.status == "GOODSIG"
+            && signature.valid
+            && matches!(
+                signature.trust_level,
+                Some(TrustLevel::Fully) | Some(TrustLevel::Ultimate)
+            );
+    }
+    return signatures;
+}
+
+// retrieve the signature(s) created from the status line(s) of a signing, 1 for each signing key
+pub fn decode_sign_result(result: &CmdResult) -> Vec<SignResult> {
+    let output_lines = result.get_raw_data().unwrap_or_default();
+    let mut signatures: Vec<SignResult> = vec![];
+    for output in output_lines.split("\n") {
+        let output: &str = output.trim_end();
+        // SIG_CREATED <type> <pk_algo> <hash_algo> <class> <timestamp> <keyfpr>
+        if !output.starts_with("[GNUPG:] SIG_CREATED ") {
+            continue;
+        }
+        let values: Vec<&str> = output["[GNUPG:] SIG_CREATED ".len()..].split_whitespace().collect();
+        signatures.push(SignResult {
+            sig_type: values.get(0).unwrap_or(&"").to_string(),
+            pubkey_algo: PublicKeyAlgo::from_id(values.get(1).unwrap_or(&"")),
+            hash_algo: DigestAlgo::from_id(values.get(2).unwrap_or(&"")),
+            sig_class: values.get(3).unwrap_or(&"").to_string(),
+            timestamp: values.get(4).unwrap_or(&"").to_string(),
+            fingerprint: values.get(5).unwrap_or(&"").to_string(),
+        });
+    }
+    return signatures;
+}
+
+fn new_verify_result() -> VerifyResult {
+    return VerifyResult {
+        status: "".to_string(),
+        keyid: "".to_string(),
+        username: None,
+        signer_uid: None,
+        fingerprint: None,
+        primary_fingerprint: None,
+        timestamp: None,
+        expire_timestamp: None,
+        hash_algo: None,
+        pubkey_algo: None,
+        trust_level: None,
+        valid: false,
+        trusted: false,
+        notations: vec![],
+        policy_url: None,
+        keyserver_url: None,
+    };
+}
+
+// the signature started by the last NEWSIG, or a new one if gpg did not report NEWSIG
+fn current_verify_result(signatures: &mut Vec<VerifyResult>) -> &mut VerifyResult {
+    let started: bool = signatures.last().map(|s| s.status.len() == 0).unwrap_or(false);
+    if !started {
+        signatures.push(new_verify_result());
+    }
+    return signatures.last_mut().unwrap();
+}
+
+// retrieve the packet summary of a message from the output of gpg --list-packets
+pub fn decode_message_info(result: &CmdResult, armored: bool) -> MessageInfo {
+    // # off=<offset> ctb=<ctb> tag=<tag> hlen=<hlen> plen=<plen> [partial|indeterminate] [new-ctb]
+    // :<packet name>: <description>
+    //     <detail>
+    // NOTE: gpg does not always end the description of a packet with a newline ( ex: gpg control packet )
+    let output_lines = result
+        .get_raw_data()
+        .unwrap_or_default()
+        .replace("# off=", "\n# off=");
+    let keyid_regex: Regex = Regex::new(r"keyid:? ([0-9A-F]{16})").unwrap();
+    let cipher_regex: Regex = Regex::new(r"cipher (\d+)").unwrap();
+    let name_regex: Regex = Regex::new(r#"name="(.*)","#).unwrap();
+
+    let mut packets: Vec<PacketInfo> = vec![];
+    for output in output_lines.split("\n") {
+        let output: &str = output.trim_end();
+        if output.starts_with("# off=") {
+            let mut packet: PacketInfo = PacketInfo {
+                packet_type: PacketType::Unknown(0),
+                tag: 0,
+                offset: 0,
+                length: None,
+                keyid: None,
+                details: vec![],
+            };
+            let mut partial: bool = false;
+            for field in output["# ".len()..].split_whitespace() {
+                match field.split_once('=') {
+                    Some(("off", value)) => packet.offset = value.parse::<u64>().unwrap_or(0),
+                    Some(("tag", value)) => packet.tag = value.parse::<u8>().unwrap_or(0),
+                    Some(("plen", value)) => packet.length = value.parse::<u64>().ok(),
+                    None if field == "partial" || field == "indeterminate" => partial = true,
+                    _ => {}
+                }
+            }
+            if partial {
+                packet.length = None;
+            }
+            packet.packet_type = PacketType::from_tag(packet.tag);
+            packets.push(packet);
+        } else if output.starts_with(":") || output.starts_with("\t") {
+            if let Some(packet) = packets.last_mut() {
+                if packet.keyid.is_none() {
+                    packet.keyid = keyid_regex.captures(output).map(|c| c[1].to_string());
+                }
+                packet.details.push(output.trim().to_string());
+            }
+        }
+    }
+
+    let mut message_info: MessageInfo = MessageInfo {
+        armored: armored,
+        encrypted: false,
+        recipients: vec![],
+        symmetric: false,
+        cipher_algo: None,
+        integrity_protected: false,
+        signed: false,
+        clearsigned: false,
+        detached_signature: false,
+        signers: vec![],
+        filename: None,
+        key: false,
+        packets: vec![],
+    };
+    let mut literal: bool = false;
+    for packet in packets.iter() {
+        match packet.packet_type {
+            PacketType::PublicKeyEncryptedSessionKey => {
+                message_info.encrypted = true;
+                if let Some(keyid) = packet.keyid.clone() {
+                    message_info.recipients.push(keyid);
+                }
+            }
+            PacketType::SymmetricKeyEncryptedSessionKey => {
+                message_info.encrypted = true;
+                message_info.symmetric = true;
+                message_info.cipher_algo = cipher_regex
+                    .captures(&packet.details.join(" "))
+                    .and_then(|c| CipherAlgo::from_id(&c[1]));
+            }
+            PacketType::SymmetricallyEncryptedData => {
+                message_info.encrypted = true;
+            }
+            PacketType::EncryptedIntegrityProtectedData | PacketType::AeadEncryptedData => {
+                message_info.encrypted = true;
+                message_info.integrity_protected = true;
+            }
+            PacketType::Signature | PacketType::OnePassSignature => {
+                message_info.signed = true;
+                if let Some(keyid) = packet.keyid.clone() {
+                    if !message_info.signers.contains(&keyid) {
+                        message_info.signers.push(keyid);
+                    }
+                }
+            }
+            PacketType::LiteralData => {
+                literal = true;
+                message_info.filename = name_regex
+                    .captures(&packet.details.join(" "))
+                    .map(|c| c[1].to_string())
+                    .filter(|name| name.len() > 0);
+            }
+            PacketType::Control => {
+                message_info.clearsigned = true;
+                message_info.signed = true;
+            }
+            PacketType::PublicKey | PacketType::SecretKey => {
+                message_info.key = true;
+            }
+            _ => {}
+        }
+    }
+    if message_info.key {
+        // the signature(s) of a key are certifications of the key, not signature of data
+        message_info.signed = false;
+        message_info.signers = vec![];
+    }
+    if message_info.encrypted && message_info.recipients.is_empty() {
+        // data encrypted symmetrically without session key packet
+        message_info.symmetric = true;
+    }
+    message_info.detached_signature = message_info.signed
+        && !literal
+        && !message_info.clearsigned
+        && !message_info.encrypted;
+    message_info.packets = packets;
+    return message_info;
+}
+
+// retrieve the entries of an archive from the output of gpgtar --list-archive
+pub fn decode_archive_list(output: &str) -> Vec<ArchiveEntry> {
+    // <mode> <nlink> <uid>/<gid> <size> <YYYY-MM-DD> <HH:MM:SS> <path>
+    let mut entries: Vec<ArchiveEntry> = vec![];
+    for line in output.split("\n") {
+        let mut rest: &str = line.trim_end_matches('\r');
+        let mut values: Vec<&str> = vec![];
+        while values.len() < 6 {
+            rest = rest.trim_start();
+            match rest.find(char::is_whitespace) {
+                Some(idx) => {
+                    values.push(&rest[..idx]);
+                    rest = &rest[idx..];
+                }
+                None => break,
+            }
+        }
+        // the path was separated by a single space, as it can start with space
+        if values.len() < 6 || rest.len() < 2 {
+            continue;
+        }
+        entries.push(ArchiveEntry {
+            path: rest[1..].to_string(),
+            directory: values[0].starts_with("d"),
+            mode: values[0].to_string(),
+            size: values[3].parse::<u64>().unwrap_or(0),
+            modified: format!("{} {}", values[4], values[5]),
+        });
+    }
+    return entries;
+}
+
+// decode the %XX escaped string used by gpg in machine readable output
+pub fn decode_percent_escape(value: &str) -> String {
+    let bytes: &[u8] = value.as_bytes();
+    let mut decoded: Vec<u8> = Vec::new();
+    let mut idx: usize = 0;
+    while idx < bytes.len() {
+        if bytes[idx] == b'%' && idx + 2 < bytes.len() {
+            let hex: &str = std::str::from_utf8(&bytes[idx + 1..idx + 3]).unwrap_or("");
+            if let Ok(b) = u8::from_str_radix(hex, 16) {
+                decoded.push(b);
+                idx += 3;
+                continue;
+            }
+        }
+        decoded.push(bytes[idx]);
+        idx += 1;
+    }
+    return String::from_utf8_lossy(&decoded).to_string();
+}
+
+// if gpg failed only because no key matched ( No public key / No secret key / NODATA ), not for any other failure
+pub fn is_key_not_found(error: &GPGError) -> bool {
+    if !matches!(error.error_type, GPGErrorType::GPGProcessError(_)) {
+        return false;
+    }
+    let output: String = match &error.cmd_result {
+        Some(result) => result.get_raw_data().unwrap_or_default(),
+        None => {
+            return false;
+        }
+    };
+    return output.contains("No public key")
+        || output.contains("No secret key")
+        || output.contains("[GNUPG:] NODATA");
+}
+
 pub fn is_passphrase_valid(passhrase: &str) -> bool {
     return !passhrase.contains("\n") && !passhrase.contains("\r") && !passhrase.contains("\x00");
 }
 
+// list all the file(s) under a directory recursively, sorted by path
+pub fn list_files_in_dir(path: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = vec![];
+    let entries = match std::fs::read_dir(path) {
+        Ok(entries) => entries,
+        Err(_) => {
+            return files;
+        }
+    };
+    for entry in entries.flatten() {
+        let entry_path: PathBuf = entry.path();
+        if entry_path.is_dir() {
+            files.append(&mut list_files_in_dir(&entry_path));
+        } else if entry_path.is_file() {
+            files.push(entry_path);
+        }
+    }
+    files.sort();
+    return files;
+}
+
 pub fn set_output_without_confirmation(args: &mut Vec<String>, output: &str) {
     // prevent a confimation prompt when output provided exist
     if Path::new(output).exists() {
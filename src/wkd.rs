@@ -0,0 +1,253 @@
+use std::{
+    collections::HashMap,
+    fs::{create_dir_all, File},
+    path::PathBuf,
+};
+
+use crate::{
+    gnupg::GPG,
+    process::handle_cmd_io,
+    utils::{
+        enums::{Operation, WkdMethod},
+        errors::{GPGError, GPGErrorType},
+        response::{ListKeyResult, WkdEntry},
+        utils::set_output_without_confirmation,
+    },
+};
+
+const ZBASE32_ALPHABET: &[u8; 32] = b"ybndrfg8ejkmcpqxot1uwisza345h769";
+
+//*******************************************************
+
+//           RELATED TO WEB KEY DIRECTORY (WKD)
+
+//*******************************************************
+// build a WKD tree from the keys ( obtained from list_keys ) under the dest directory
+pub fn build_wkd(
+    gpg: &GPG,
+    keys: Vec<ListKeyResult>,
+    domain: String,
+    dest: String,
+    method: WkdMethod,
+) -> Result<Vec<WkdEntry>, GPGError> {
+    // gpg: the GPG object that hold the keys in its homedir
+    // keys: list of keys to publish, only the uid(s) that match the domain will be published
+    // domain: the domain the WKD was hosted for ex) example.org
+    // dest: path to the directory where the .well-known directory will be created
+    // method: advanced ( openpgpkey.<domain> ) or direct ( <domain> ) layout
+
+    //*****************************************************************************************
+    //    NOTE: advanced layout -> <dest>/.well-known/openpgpkey/<domain>/hu/<hash>
+    //          direct layout   -> <dest>/.well-known/openpgpkey/hu/<hash>
+    //          each file only hold the binary key(s) with the uid of the matching address
+    //          ( export-minimal ), which require gpg version >= 2.1.14 for --export-filter
+    //******************************************************************************************
+
+    let domain: String = domain.trim().to_lowercase();
+    if domain.is_empty() {
+        return Err(GPGError::new(
+            GPGErrorType::InvalidArgumentError("domain is required to build WKD".to_string()),
+            None,
+        ));
+    }
+
+    // group the fingerprints by the address of the uid(s) that match the domain
+    let mut addresses: Vec<String> = vec![];
+    let mut fingerprints: HashMap<String, Vec<String>> = HashMap::new();
+    for key in keys.iter() {
+        for uid in key.uids.iter() {
+            let address: Option<String> = get_uid_address(uid);
+            if address.is_none() {
+                continue;
+            }
+            // the address was normalized to lowercase as gpg does for the WKD hash
+            let address: String = address.unwrap().to_lowercase();
+            if get_address_domain(&address) != domain {
+                continue;
+            }
+            if !fingerprints.contains_key(&address) {
+                addresses.push(address.clone());
+            }
+            let fprs: &mut Vec<String> = fingerprints.entry(address).or_insert(vec![]);
+            if !fprs.contains(&key.fingerprint) {
+                fprs.push(key.fingerprint.clone());
+            }
+        }
+    }
+
+    let openpgpkey_dir: PathBuf = PathBuf::from(dest).join(".well-known").join("openpgpkey");
+    let wkd_dir: PathBuf = match method {
+        WkdMethod::Advanced => openpgpkey_dir.join(&domain),
+        WkdMethod::Direct => openpgpkey_dir,
+    };
+    let hu_dir: PathBuf = wkd_dir.join("hu");
+    if let Err(e) = create_dir_all(&hu_dir) {
+        return Err(GPGError::new(
+            GPGErrorType::OutputDirError(e.to_string()),
+            None,
+        ));
+    }
+
+    // an empty policy file was required for the WKD to be recognized
+    let policy: Result<File, std::io::Error> = File::create(wkd_dir.join("policy"));
+    match policy {
+        Ok(_) => {}
+        Err(e) => {
+            return Err(GPGError::new(
+                GPGErrorType::WriteFailError(e.to_string()),
+                None,
+            ));
+        }
+    }
+
+    let mut entries: Vec<WkdEntry> = vec![];
+    for address in addresses {
+        let local_part: &str = address.rsplitn(2, '@').last().unwrap_or("");
+        let hash: String = wkd_hash(local_part);
+        let path: String = hu_dir.join(&hash).to_string_lossy().to_string();
+        let fprs: Vec<String> = fingerprints.remove(&address).unwrap_or(vec![]);
+
+        let mut args: Vec<String> = vec![
+            "--export-options".to_string(),
+            "export-minimal".to_string(),
+            "--export-filter".to_string(),
+            format!("keep-uid=mbox = {}", address),
+            "--export".to_string(),
+        ];
+        set_output_without_confirmation(&mut args, &path);
+        args.append(&mut fprs.clone());
+
+        let result = handle_cmd_io(
+            Some(args),
+            None,
+            gpg.version,
+            gpg.homedir.clone(),
+            gpg.options.clone(),
+            gpg.env.clone(),
+            None,
+            None,
+            None,
+            false,
+            false,
+            Operation::ExportPublicKey,
+        );
+        if let Err(e) = result {
+            return Err(e);
+        }
+        entries.push(WkdEntry {
+            address: address,
+            hash: hash,
+            fingerprints: fprs,
+            path: path,
+        });
+    }
+    return Ok(entries);
+}
+
+// the WKD hash of the local part of an address ( z-base-32 encoded SHA-1 of the lowercased local part )
+pub fn wkd_hash(local_part: &str) -> String {
+    let digest: [u8; 20] = sha1(local_part.to_lowercase().as_bytes());
+    return zbase32_encode(&digest);
+}
+
+// retrieve the address from a uid ex) Joe Doe <joe.doe@example.org>
+fn get_uid_address(uid: &str) -> Option<String> {
+    let uid: &str = uid.trim();
+    let address: &str = match (uid.rfind('<'), uid.rfind('>')) {
+        (Some(start), Some(end)) if start < end => &uid[start + 1..end],
+        _ => uid,
+    };
+    let address: &str = address.trim();
+    if !address.contains('@') || address.contains(' ') {
+        return None;
+    }
+    return Some(address.to_string());
+}
+
+fn get_address_domain(address: &str) -> String {
+    return match address.rsplit_once('@') {
+        Some((_, domain)) => domain.to_lowercase(),
+        None => String::new(),
+    };
+}
+
+fn zbase32_encode(data: &[u8]) -> String {
+    let mut encoded: String = String::new();
+    let mut buffer: u32 = 0;
+    let mut bits: u32 = 0;
+    for byte in data {
+        buffer = (buffer << 8) | *byte as u32;
+        bits += 8;
+        while bits >= 5 {
+            let idx: u32 = (buffer >> (bits - 5)) & 0x1f;
+            encoded.push(ZBASE32_ALPHABET[idx as usize] as char);
+            bits -= 5;
+        }
+        buffer &= (1 << bits) - 1;
+    }
+    if bits > 0 {
+        let idx: u32 = (buffer << (5 - bits)) & 0x1f;
+        encoded.push(ZBASE32_ALPHABET[idx as usize] as char);
+    }
+    return encoded;
+}
+
+fn sha1(data: &[u8]) -> [u8; 20] {
+    let mut h: [u32; 5] = [0x67452301, 0xEFCDAB89, 0x98BADCFE, 0x10325476, 0xC3D2E1F0];
+
+    // pad the message to a multiple of 64 bytes with the bit length at the end
+    let mut message: Vec<u8> = data.to_vec();
+    let bit_len: u64 = (data.len() as u64) * 8;
+    message.push(0x80);
+    while message.len() % 64 != 56 {
+        message.push(0);
+    }
+    message.extend_from_slice(&bit_len.to_be_bytes());
+
+    for chunk in message.chunks(64) {
+        let mut w: [u32; 80] = [0; 80];
+        for i in 0..16 {
+            w[i] = u32::from_be_bytes([
+                chunk[i * 4],
+                chunk[i * 4 + 1],
+                chunk[i * 4 + 2],
+                chunk[i * 4 + 3],
+            ]);
+        }
+        for i in 16..80 {
+            w[i] = (w[i - 3] ^ w[i - 8] ^ w[i - 14] ^ w[i - 16]).rotate_left(1);
+        }
+
+        let (mut a, mut b, mut c, mut d, mut e) = (h[0], h[1], h[2], h[3], h[4]);
+        for (i, word) in w.iter().enumerate() {
+            let (f, k) = match i {
+                0..=19 => ((b & c) | (!b & d), 0x5A827999),
+                20..=39 => (b ^ c ^ d, 0x6ED9EBA1),
+                40..=59 => ((b & c) | (b & d) | (c & d), 0x8F1BBCDC),
+                _ => (b ^ c ^ d, 0xCA62C1D6),
+            };
+            let temp: u32 = a
+                .rotate_left(5)
+                .wrapping_add(f)
+                .wrapping_add(e)
+                .wrapping_add(k)
+                .wrapping_add(*word);
+            e = d;
+            d = c;
+            c = b.rotate_left(30);
+            b = a;
+            a = temp;
+        }
+        h[0] = h[0].wrapping_add(a);
+        h[1] = h[1].wrapping_add(b);
+        h[2] = h[2].wrapping_add(c);
+        h[3] = h[3].wrapping_add(d);
+        h[4] = h[4].wrapping_add(e);
+    }
+
+    let mut digest: [u8; 20] = [0; 20];
+    for (i, value) in h.iter().enumerate() {
+        digest[i * 4..i * 4 + 4].copy_from_slice(&value.to_be_bytes());
+    }
+    return digest;
+}
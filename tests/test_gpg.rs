@@ -8,7 +8,10 @@ use std::{
         PathBuf,
         Path
     },
-    io::{Read, Write},
+    io::{Read, Write, BufRead, BufReader, Cursor, Seek},
+    net::TcpListener,
+    sync::{Arc, Mutex},
+    thread,
 };
 
 use tempfile::tempfile;
@@ -20,13 +23,19 @@ use crab_gnupg::{
         GPG,
         EncryptOption,
         DecryptOption,
-        SignOption
+        SignOption,
+        VerifyPolicy
     },
     utils::{
         errors::{GPGError, GPGErrorType},
-        response::{CmdResult, ListKeyResult},
-        enums::TrustLevel
+        response::{ArchiveEntry, CmdResult, CopyKeyResult, DecryptResult, GroupResult, ListKeyResult, LocateKeyResult, MessageInfo, PolicyResult, ReencryptResult, SearchKeyResult, SignResult, StreamVerifyResult, VerifyResult, WkdEntry},
+        utils::{decode_decrypt_result, decode_verify_result},
+        archive::{read_tar, write_tar},
+        enums::{AeadAlgo, CipherAlgo, Compliance, CompressAlgo, DigestAlgo, LocateMechanism, Operation, PacketType, PolicyRejection, SignMode, TrustLevel, WkdMethod}
     },
+    passphrase::{PassphraseProvider, PassphraseRequest},
+    stream::{GpgDecryptReader, GpgEncryptWriter},
+    wkd::{build_wkd, wkd_hash},
 };
 
 
@@ -90,6 +99,12 @@ mod tests {
         let _ = gpg.gen_key(None, Some(args));
     }
 
+    fn gen_unprotected_key_with_email(gpg:GPG, email:&str){
+        let mut args: HashMap<String, String> = HashMap::new();
+        args.insert("Name-Email".to_string(), email.to_string());
+        let _ = gpg.gen_key(None, Some(args));
+    }
+
     fn list_keys(gpg:GPG, secret:bool, sig:bool) -> Vec<ListKeyResult> {
         let list_key_result:Result<Vec<ListKeyResult>, GPGError> = gpg.list_keys(secret, None, sig);
         let list_key_result_unwrap: Vec<ListKeyResult> = list_key_result.unwrap();
@@ -131,6 +146,88 @@ mod tests {
         return options;
     }
 
+    fn start_http_server(
+        handler: fn(&str, &str, &[u8], &HashMap<String, Vec<u8>>) -> (u16, Vec<u8>),
+        data: HashMap<String, Vec<u8>>,
+    ) -> (String, Arc<Mutex<Vec<String>>>) {
+        // a minimal local http stand-in ( for keyserver / wkd ), the handler receive (method, path, body, data)
+        // and return (status code, response body), all the requests received will be recorded
+        let listener: TcpListener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address: String = format!("127.0.0.1:{}", listener.local_addr().unwrap().port());
+        let requests: Arc<Mutex<Vec<String>>> = Arc::new(Mutex::new(Vec::new()));
+        let recorded: Arc<Mutex<Vec<String>>> = Arc::clone(&requests);
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = match stream {
+                    Ok(stream) => stream,
+                    Err(_) => continue,
+                };
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut request_line: String = String::new();
+                let _ = reader.read_line(&mut request_line);
+                let mut content_length: usize = 0;
+                loop {
+                    let mut header: String = String::new();
+                    if reader.read_line(&mut header).unwrap_or(0) == 0 || header.trim().is_empty() {
+                        break;
+                    }
+                    let lower: String = header.to_lowercase();
+                    if lower.starts_with("content-length:") {
+                        content_length = lower[15..].trim().parse::<usize>().unwrap_or(0);
+                    }
+                }
+                let mut body: Vec<u8> = vec![0; content_length];
+                let _ = reader.read_exact(&mut body);
+                let parts: Vec<&str> = request_line.split_whitespace().collect();
+                let (method, path) = (parts.get(0).unwrap_or(&""), parts.get(1).unwrap_or(&""));
+                recorded.lock().unwrap().push(format!("{} {}", method, path));
+                let (status, response) = handler(method, path, &body, &data);
+                let _ = write!(
+                    stream,
+                    "HTTP/1.0 {} OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                    status,
+                    response.len()
+                );
+                let _ = stream.write_all(&response);
+            }
+        });
+        return (address, requests);
+    }
+
+    fn hkp_handler(method: &str, path: &str, _body: &[u8], data: &HashMap<String, Vec<u8>>) -> (u16, Vec<u8>) {
+        if method == "POST" && path.starts_with("/pks/add") {
+            return (200, vec![]);
+        }
+        if path.contains("op=index") {
+            let uid: String = String::from_utf8_lossy(&data["uid"]).to_string();
+            let index: String = format!(
+                "info:1:1\npub:{}:1:2048:1700000000::\nuid:{}:1700000000::\n",
+                String::from_utf8_lossy(&data["fingerprint"]),
+                uid.replace(" ", "%20").replace("<", "%3C").replace(">", "%3E")
+            );
+            return (200, index.as_bytes().to_vec());
+        }
+        if path.contains("op=get") {
+            return (200, data["key"].clone());
+        }
+        return (404, vec![]);
+    }
+
+    fn start_hkp_server(gpg: GPG, name: &str) -> (String, Arc<Mutex<Vec<String>>>, String) {
+        // serve the first public key of the gpg as the only key on the stand-in keyserver
+        let key: ListKeyResult = list_keys(gpg.clone(), false, false)[0].clone();
+        let output: String = PathBuf::from(get_output_dir(name)).join("hkp_key.gpg").to_string_lossy().to_string();
+        let _ = gpg.export_public_key(Some(vec![key.fingerprint.clone()]), Some(output.clone()), None);
+        let mut exported: Vec<u8> = Vec::new();
+        let _ = File::open(output).unwrap().read_to_end(&mut exported);
+        let mut data: HashMap<String, Vec<u8>> = HashMap::new();
+        data.insert("fingerprint".to_string(), key.fingerprint.as_bytes().to_vec());
+        data.insert("uid".to_string(), key.uids[0].as_bytes().to_vec());
+        data.insert("key".to_string(), exported);
+        let (address, requests) = start_http_server(hkp_handler, data);
+        return (format!("hkp://{}", address), requests, key.fingerprint);
+    }
+
     fn cleanup_after_tests(name:&str) {
         let home_dir = if cfg!(unix) {
             std::env::var("HOME").unwrap()
@@ -319,6 +416,47 @@ mod tests {
         cleanup_after_tests(name);
     }
 
+    #[cfg(not(feature = "test_legacy"))]
+    #[test]
+    fn test_delete_primary_secret(){
+        // test deleting only the secret part of the primary key, leaving a stub
+
+        let name:String  = generate_random_string();
+        let name: &str = name.as_str();
+
+        let gpg: GPG = get_gpg_init(name);
+        gen_unprotected_key_with_subkeys(gpg.clone());
+        let fingerprint: String = list_keys(gpg.clone(), true, false)[0].fingerprint.clone();
+        assert_eq!(list_keys(gpg.clone(), true, false)[0].secret_stub, false);
+
+        let result:Result<CmdResult, GPGError>  = gpg.delete_primary_secret(fingerprint, None);
+        assert_eq!(result.unwrap().is_success(), true);
+        let secret_keys: Vec<ListKeyResult> = list_keys(gpg.clone(), true, false);
+        assert_eq!(secret_keys.len(), 1);
+        assert_eq!(secret_keys[0].secret_stub, true);
+        assert_eq!(secret_keys[0].subkeys[0].secret_stub, false);
+
+        cleanup_after_tests(name);
+    }
+
+    #[test]
+    fn test_delete_primary_secret_no_subkey(){
+        // test deleting the primary secret of a key without secret subkeys
+
+        let name:String  = generate_random_string();
+        let name: &str = name.as_str();
+
+        let gpg: GPG = get_gpg_init(name);
+        gen_unprotected_key(gpg.clone());
+        let fingerprint: String = list_keys(gpg.clone(), true, false)[0].fingerprint.clone();
+
+        let result:Result<CmdResult, GPGError>  = gpg.delete_primary_secret(fingerprint, None);
+        assert!(matches!(result.unwrap_err().error_type, GPGErrorType::InvalidArgumentError(_)));
+        assert_eq!(list_keys(gpg.clone(), true, false)[0].secret_stub, false);
+
+        cleanup_after_tests(name);
+    }
+
     #[test]
     fn test_delete_keys_not_found_fingerprint(){
         // test deleting with fingerprint not found in local
@@ -522,13 +660,38 @@ mod tests {
         let key_list: Vec<ListKeyResult> = list_keys(gpg.clone(), false, false);
         let key_id: String = key_list[0].keyid.clone();
         let output: String = PathBuf::from(get_output_dir(name)).join("test_export_public_key.asc").to_string_lossy().to_string();
-        let result: Result<CmdResult, GPGError> = gpg.export_public_key(Some(vec![key_id]), Some(output.clone()));
+        let result: Result<CmdResult, GPGError> = gpg.export_public_key(Some(vec![key_id]), Some(output.clone()), None);
         assert_eq!(result.unwrap().is_success(), true);
         assert_eq!(Path::new(&output).exists(), true);
 
         cleanup_after_tests(name);
     }
 
+    #[test]
+    fn test_export_public_key_armor_override(){
+        // test exporting the public key armored by default and binary with the armor override
+
+        let name:String  = generate_random_string();
+        let name: &str = name.as_str();
+
+        let gpg: GPG = get_gpg_init(name);
+        gen_unprotected_key(gpg.clone());
+        let key_list: Vec<ListKeyResult> = list_keys(gpg.clone(), false, false);
+        let output: String = PathBuf::from(get_output_dir(name)).join("test_export_public_key.asc").to_string_lossy().to_string();
+        let result: Result<CmdResult, GPGError> = gpg.export_public_key(Some(vec![key_list[0].keyid.clone()]), Some(output.clone()), None);
+        assert_eq!(result.unwrap().is_success(), true);
+        assert!(std::fs::read_to_string(&output).unwrap().starts_with("-----BEGIN PGP PUBLIC KEY BLOCK-----"));
+
+        let result: Result<CmdResult, GPGError> = gpg.export_public_key(Some(vec![key_list[0].keyid.clone()]), None, Some(false));
+        assert_eq!(result.unwrap().is_success(), true);
+        let exported: Vec<PathBuf> = std::fs::read_dir(PathBuf::from(get_output_dir(name)).join("exported_public_key")).unwrap().map(|e| e.unwrap().path()).collect();
+        assert_eq!(exported.len(), 1);
+        assert_eq!(exported[0].extension().unwrap(), "gpg");
+        assert!(!std::fs::read(&exported[0]).unwrap().starts_with(b"-----BEGIN PGP"));
+
+        cleanup_after_tests(name);
+    }
+
     #[test]
     fn test_export_secret_key(){
         // test exporting the secretkey
@@ -541,11 +704,41 @@ mod tests {
         let key_list: Vec<ListKeyResult> = list_keys(gpg.clone(), true, false);
         let key_id: String = key_list[0].keyid.clone();
         let output: String = PathBuf::from(get_output_dir(name)).join("test_export_secret_key.sec.asc").to_string_lossy().to_string();
-        let result: Result<CmdResult, GPGError> = gpg.export_secret_key(Some(vec![key_id]), Some(get_key_passphrass()), Some(output.clone()));
+        let result: Result<CmdResult, GPGError> = gpg.export_secret_key(Some(vec![key_id]), Some(get_key_passphrass()), Some(output.clone()), None);
+        assert_eq!(result.unwrap().is_success(), true);
+        assert_eq!(Path::new(&output).exists(), true);
+
+        cleanup_after_tests(name);
+    }
+
+    #[test]
+    fn test_export_secret_subkeys(){
+        // test exporting the secret subkeys only
+
+        let name:String  = generate_random_string();
+        let name: &str = name.as_str();
+
+        let gpg: GPG = get_gpg_init(name);
+        gen_unprotected_key_with_subkeys(gpg.clone());
+        let key_list: Vec<ListKeyResult> = list_keys(gpg.clone(), true, false);
+        let key_id: String = key_list[0].keyid.clone();
+        let output: String = PathBuf::from(get_output_dir(name)).join("test_export_secret_subkeys.sec.asc").to_string_lossy().to_string();
+        let result: Result<CmdResult, GPGError> = gpg.export_secret_subkeys(Some(vec![key_id]), None, Some(output.clone()), None);
         assert_eq!(result.unwrap().is_success(), true);
         assert_eq!(Path::new(&output).exists(), true);
 
+        // importing the exported subkeys into another homedir should give a stub of the primary key
+        let import_name:String  = generate_random_string();
+        let import_name: &str = import_name.as_str();
+        let import_gpg: GPG = get_gpg_init(import_name);
+        let result: Result<CmdResult, GPGError> = import_gpg.import_key(None, Some(output), false, None);
+        assert_eq!(result.unwrap().is_success(), true);
+        let secret_keys: Vec<ListKeyResult> = list_keys(import_gpg.clone(), true, false);
+        assert_eq!(secret_keys[0].secret_stub, true);
+        assert_eq!(secret_keys[0].subkeys[0].secret_stub, false);
+
         cleanup_after_tests(name);
+        cleanup_after_tests(import_name);
     }
 
     #[cfg(not(feature = "test_legacy"))]
@@ -561,7 +754,7 @@ mod tests {
         let key_list: Vec<ListKeyResult> = list_keys(gpg.clone(), true, false);
         let key_id: String = key_list[0].keyid.clone();
         let output: String = PathBuf::from(get_output_dir(name)).join("test_export_secret_key.sec.asc").to_string_lossy().to_string();
-        let result: Result<CmdResult, GPGError> = gpg.export_secret_key(Some(vec![key_id]), None, Some(output.clone()));
+        let result: Result<CmdResult, GPGError> = gpg.export_secret_key(Some(vec![key_id]), None, Some(output.clone()), None);
         assert!(matches!(result.unwrap_err().error_type, GPGErrorType::GPGProcessError(_)));
         assert_eq!(Path::new(&output).exists(), false);
 
@@ -580,13 +773,63 @@ mod tests {
         gen_unprotected_key(gpg.clone());
         gen_protected_key(gpg.clone());
         let output: String = PathBuf::from(get_output_dir(name)).join("test_export_secret_key.sec.asc").to_string_lossy().to_string();
-        let result: Result<CmdResult, GPGError> = gpg.export_secret_key(None, None, Some(output.clone()));
+        let result: Result<CmdResult, GPGError> = gpg.export_secret_key(None, None, Some(output.clone()), None);
         assert_eq!(result.unwrap().is_success(), true);
         assert_eq!(Path::new(&output).exists(), true);
 
         cleanup_after_tests(name);
     }
 
+    #[cfg(not(feature = "test_legacy"))]
+    #[test]
+    fn test_export_secret_key_with_provider(){
+        // test exporting seceret keys that are protected by different passphrase with a passphrase provider
+
+        let name:String  = generate_random_string();
+        let name: &str = name.as_str();
+
+        let gpg: GPG = get_gpg_init(name);
+        let _ = gpg.gen_key(Some("passphrase_a".to_string()), None);
+        let _ = gpg.gen_key(Some("passphrase_b".to_string()), None);
+        let key_list: Vec<ListKeyResult> = list_keys(gpg.clone(), true, false);
+        assert_eq!(key_list.len(), 2);
+
+        let mut passphrases: HashMap<String, String> = HashMap::new();
+        passphrases.insert(key_list[0].keyid.clone(), "passphrase_a".to_string());
+        passphrases.insert(key_list[1].keyid.clone(), "passphrase_b".to_string());
+        let requests: Arc<Mutex<Vec<PassphraseRequest>>> = Arc::new(Mutex::new(vec![]));
+        let requests_clone: Arc<Mutex<Vec<PassphraseRequest>>> = Arc::clone(&requests);
+        let provider: Arc<dyn PassphraseProvider> = Arc::new(move |request: &PassphraseRequest| {
+            requests_clone.lock().unwrap().push(request.clone());
+            return passphrases.get(&request.main_keyid).cloned();
+        });
+
+        let output: String = PathBuf::from(get_output_dir(name)).join("test_export_secret_key.sec.asc").to_string_lossy().to_string();
+        let result: Result<CmdResult, GPGError> = gpg.export_secret_key_with_provider(None, provider, Some(output.clone()), None);
+        assert_eq!(result.unwrap().is_success(), true);
+
+        // the provider should be called once for each key with its keygrip
+        let requests: Vec<PassphraseRequest> = requests.lock().unwrap().clone();
+        assert_eq!(requests.len(), 2);
+        for key in key_list.iter() {
+            let request: &PassphraseRequest = requests.iter().find(|r| r.main_keyid == key.keyid).unwrap();
+            assert_eq!(request.keygrip, Some(key.keygrip.clone()));
+            assert_eq!(request.symmetric, false);
+            assert_eq!(request.attempt, 1);
+        }
+
+        // both secret keys should be exported
+        let import_name:String  = generate_random_string();
+        let import_name: &str = import_name.as_str();
+        let import_gpg: GPG = get_gpg_init(import_name);
+        let result: Result<CmdResult, GPGError> = import_gpg.import_key(None, Some(output), false, None);
+        assert_eq!(result.unwrap().is_success(), true);
+        assert_eq!(list_keys(import_gpg.clone(), true, false).len(), 2);
+
+        cleanup_after_tests(name);
+        cleanup_after_tests(import_name);
+    }
+
     #[test] 
     fn test_export_key_no_key(){
         // test exporting keys but there are no keys in local gpg home directory
@@ -596,7 +839,7 @@ mod tests {
 
         let gpg: GPG = get_gpg_init(name);
         let output: String = PathBuf::from(get_output_dir(name)).join("test_export_public_key.asc").to_string_lossy().to_string();
-        let result: Result<CmdResult, GPGError> = gpg.export_public_key(None,Some(output.clone()));
+        let result: Result<CmdResult, GPGError> = gpg.export_public_key(None,Some(output.clone()), None);
         assert_eq!(result.unwrap().is_success(), true);
         assert_eq!(Path::new(&output).exists(), false);
 
@@ -624,7 +867,7 @@ mod tests {
         }
 
         let output: String = PathBuf::from(get_output_dir(name)).join("test_export_public_key.asc").to_string_lossy().to_string();
-        let result: Result<CmdResult, GPGError> = gpg.export_public_key(Some(vec![keyid]),Some(output.clone()));
+        let result: Result<CmdResult, GPGError> = gpg.export_public_key(Some(vec![keyid]),Some(output.clone()), None);
         assert_eq!(result.unwrap().is_success(), true);
         assert_eq!(Path::new(&output).exists(), false);
 
@@ -653,7 +896,7 @@ mod tests {
         }
 
         let output: String = PathBuf::from(get_output_dir(name)).join("test_export_public_key.asc").to_string_lossy().to_string();
-        let result: Result<CmdResult, GPGError> = gpg.export_public_key(Some(vec![keyid, result[1].keyid.clone()]),Some(output.clone()));
+        let result: Result<CmdResult, GPGError> = gpg.export_public_key(Some(vec![keyid, result[1].keyid.clone()]),Some(output.clone()), None);
         assert_eq!(result.unwrap().is_success(), true);
         assert_eq!(Path::new(&output).exists(), true);
 
@@ -675,7 +918,7 @@ mod tests {
         let other_gpg: GPG = other_gpg.unwrap();
         gen_unprotected_key(other_gpg.clone());
         let output: String = PathBuf::from(get_output_dir(name)).join("test_export_public_key.asc").to_string_lossy().to_string();
-        let _ = other_gpg.export_public_key(None, Some(output.clone()));
+        let _ = other_gpg.export_public_key(None, Some(output.clone()), None);
         assert_eq!(Path::new(&output).exists(), true);
 
         let result: Result<CmdResult, GPGError> = gpg.import_key(None, Some(output), false, None);
@@ -700,7 +943,7 @@ mod tests {
         let other_gpg: GPG = other_gpg.unwrap();
         gen_protected_key(other_gpg.clone());
         let output: String = PathBuf::from(get_output_dir(name)).join("test_export_secret_key.asc").to_string_lossy().to_string();
-        let _ = other_gpg.export_secret_key(None, Some(get_key_passphrass()), Some(output.clone()));
+        let _ = other_gpg.export_secret_key(None, Some(get_key_passphrass()), Some(output.clone()), None);
         assert_eq!(Path::new(&output).exists(), true);
 
         let result: Result<CmdResult, GPGError> = gpg.import_key(None, Some(output),  false, None);
@@ -986,261 +1229,522 @@ mod tests {
 
     
     #[test]
-    fn test_encrypt_file(){
-        // test encrypting file with just key (default)
+    fn test_search_keys(){
+        // test searching keys on a local keyserver
 
         let name:String  = generate_random_string();
         let name: &str = name.as_str();
 
         let gpg: GPG = get_gpg_init(name);
-        gen_protected_key(gpg.clone());
-
-        let mut file = tempfile().unwrap();
-        writeln!(file, "testing encryption").unwrap();
-        file.flush().unwrap();
-
-        let result: Vec<ListKeyResult> = list_keys(gpg.clone(), true, false);
-        let output: String = PathBuf::from(get_output_dir(name)).join("test_encrypt.txt").to_string_lossy().to_string();
-        let option = gen_encrypt_default_option(file, vec![result[0].keyid.clone()], Some(output.clone()));
+        gen_unprotected_key(gpg.clone());
+        let (keyserver, requests, fingerprint) = start_hkp_server(gpg.clone(), name);
+        let uid: String = list_keys(gpg.clone(), false, false)[0].uids[0].clone();
+
+        let search_name:String  = generate_random_string();
+        let search_name: &str = search_name.as_str();
+        let search_gpg: GPG = get_gpg_init(search_name);
+        let result: Result<Vec<SearchKeyResult>, GPGError> = search_gpg.search_keys(uid.clone(), Some(keyserver));
+        let result: Vec<SearchKeyResult> = result.unwrap();
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].keyid, fingerprint);
+        assert_eq!(result[0].uids, vec![uid]);
+        assert!(requests.lock().unwrap()[0].contains("op=index"));
 
-        let result: Result<CmdResult, GPGError> = gpg.encrypt(option);
-        assert_eq!(result.unwrap().is_success(), true);
-        assert_eq!(Path::new(&output).exists(), true);
-    
         cleanup_after_tests(name);
+        cleanup_after_tests(search_name);
     }
 
     #[test]
-    fn test_encrypt_file_symmetric(){
-        // test encrypting file with just passphrase (symmetric)
+    fn test_recv_keys(){
+        // test receiving keys from a local keyserver
 
         let name:String  = generate_random_string();
         let name: &str = name.as_str();
 
         let gpg: GPG = get_gpg_init(name);
-        gen_protected_key(gpg.clone());
-
-        let mut file = tempfile().unwrap();
-        writeln!(file, "testing encryption").unwrap();
-        file.flush().unwrap();
+        gen_unprotected_key(gpg.clone());
+        let (keyserver, _, fingerprint) = start_hkp_server(gpg.clone(), name);
 
-        let output: String = PathBuf::from(get_output_dir(name)).join("test_encrypt.txt").to_string_lossy().to_string();
-        let option = gen_encrypt_symmetric_option(file, None, "1234".to_string(), Some(output.clone()));
+        let recv_name:String  = generate_random_string();
+        let recv_name: &str = recv_name.as_str();
+        let recv_gpg: GPG = get_gpg_init(recv_name);
+        let result: Result<CmdResult, GPGError> = recv_gpg.recv_keys(vec![fingerprint.clone()], Some(keyserver.clone()));
+        assert_eq!(result.unwrap().is_success(), true);
+        assert_eq!(list_keys(recv_gpg.clone(), false, false)[0].fingerprint, fingerprint);
 
-        let result: Result<CmdResult, GPGError> = gpg.encrypt(option);
+        let result: Result<CmdResult, GPGError> = recv_gpg.refresh_keys(Some(keyserver));
         assert_eq!(result.unwrap().is_success(), true);
-        assert_eq!(Path::new(&output).exists(), true);
-    
+
         cleanup_after_tests(name);
+        cleanup_after_tests(recv_name);
     }
 
     #[test]
-    fn test_encrypt_file_key_and_symmetric(){
-        // test encrypting file with both key and passphrase (key and symmetric)
+    fn test_recv_keys_not_matching(){
+        // test receiving keys from a local keyserver which return a key that was not requested
 
         let name:String  = generate_random_string();
         let name: &str = name.as_str();
 
         let gpg: GPG = get_gpg_init(name);
-        gen_protected_key(gpg.clone());
-
-        let mut file = tempfile().unwrap();
-        writeln!(file, "testing encryption").unwrap();
-        file.flush().unwrap();
+        gen_unprotected_key(gpg.clone());
+        let (keyserver, _, _) = start_hkp_server(gpg.clone(), name);
 
-        let result: Vec<ListKeyResult> = list_keys(gpg.clone(), true, false);
-        let output: String = PathBuf::from(get_output_dir(name)).join("test_encrypt.txt").to_string_lossy().to_string();
-        let option = gen_encrypt_key_and_symmetric_option(file, vec![result[0].keyid.clone()], None, "1234".to_string(), Some(output.clone()));
+        let recv_name:String  = generate_random_string();
+        let recv_name: &str = recv_name.as_str();
+        let recv_gpg: GPG = get_gpg_init(recv_name);
+        let result: Result<CmdResult, GPGError> = recv_gpg.recv_keys(vec!["0000000000000000000000000000000000000000".to_string()], Some(keyserver));
+        assert!(matches!(result.unwrap_err().error_type, GPGErrorType::GPGProcessError(_)));
+        assert_eq!(list_keys(recv_gpg.clone(), false, false).len(), 0);
 
-        let result: Result<CmdResult, GPGError> = gpg.encrypt(option);
-        assert_eq!(result.unwrap().is_success(), true);
-        assert_eq!(Path::new(&output).exists(), true);
-    
         cleanup_after_tests(name);
+        cleanup_after_tests(recv_name);
     }
 
     #[test]
-    fn test_encrypt_file_default_fail_no_receipient(){
-        // test encrypting file with just key (default) but without providing recipient (list of key id)
+    fn test_send_keys(){
+        // test sending keys to a local keyserver
 
         let name:String  = generate_random_string();
         let name: &str = name.as_str();
 
         let gpg: GPG = get_gpg_init(name);
-        gen_protected_key(gpg.clone());
-
-        let mut file = tempfile().unwrap();
-        writeln!(file, "testing encryption").unwrap();
-        file.flush().unwrap();
+        gen_unprotected_key(gpg.clone());
+        let (keyserver, requests, fingerprint) = start_hkp_server(gpg.clone(), name);
 
-        let output: String = PathBuf::from(get_output_dir(name)).join("test_encrypt.txt").to_string_lossy().to_string();
-        let option = EncryptOption{
-            file: Some(file),
-            file_path: None,
-            recipients: None,
-            sign: false,
-            sign_key: None,
-            symmetric: false,
-            symmetric_algo: None,
-            always_trust: true,
-            passphrase: None,
-            output: Some(output.clone()),
-            extra_args: None,
-        };
+        let result: Result<CmdResult, GPGError> = gpg.send_keys(vec![fingerprint], Some(keyserver));
+        assert_eq!(result.unwrap().is_success(), true);
+        assert!(requests.lock().unwrap().contains(&"POST /pks/add".to_string()));
 
-        let result: Result<CmdResult, GPGError> = gpg.encrypt(option);
-        assert!(matches!(result.unwrap_err().error_type, GPGErrorType::InvalidArgumentError(_)));
-        assert_eq!(Path::new(&output).exists(), false);
-    
         cleanup_after_tests(name);
     }
 
     #[test]
-    fn test_encrypt_file_symmetric_fail_no_passphrase(){
-        // test encrypting file with just passphrase (symmetric) but without providing passphrase
+    fn test_locate_keys_local(){
+        // test locating keys that were already in the local keyring
 
         let name:String  = generate_random_string();
         let name: &str = name.as_str();
 
         let gpg: GPG = get_gpg_init(name);
-        gen_protected_key(gpg.clone());
-
-        let mut file = tempfile().unwrap();
-        writeln!(file, "testing encryption").unwrap();
-        file.flush().unwrap();
+        gen_unprotected_key_with_email(gpg.clone(), "alice@example.invalid");
+        let fingerprint: String = list_keys(gpg.clone(), false, false)[0].fingerprint.clone();
 
-        let output: String = PathBuf::from(get_output_dir(name)).join("test_encrypt.txt").to_string_lossy().to_string();
-        let option = EncryptOption{
-            file: Some(file),
-            file_path: None,
-            recipients: None,
-            sign: false,
-            sign_key: None,
-            symmetric: false,
-            symmetric_algo: None,
-            always_trust: true,
-            passphrase: None,
-            output: Some(output.clone()),
-            extra_args: None,
-        };
+        let result: Result<Vec<LocateKeyResult>, GPGError> = gpg.locate_keys(
+            vec!["alice@example.invalid".to_string(), "bob@example.invalid".to_string()],
+            vec![LocateMechanism::Local]
+        );
+        let result: Vec<LocateKeyResult> = result.unwrap();
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0].key.as_ref().unwrap().fingerprint, fingerprint);
+        assert_eq!(result[0].mechanism, Some(LocateMechanism::Local));
+        assert!(result[1].key.is_none());
+        assert!(result[1].mechanism.is_none());
 
-        let result: Result<CmdResult, GPGError> = gpg.encrypt(option);
-        assert!(matches!(result.unwrap_err().error_type, GPGErrorType::InvalidArgumentError(_)));
-        assert_eq!(Path::new(&output).exists(), false);
-    
         cleanup_after_tests(name);
     }
 
     #[test]
-    fn test_encrypt_file_key_and_symmetric_fail_no_passphrase(){
-        // test encrypting file with both key and passphrase (symmetric) but without providing both
+    fn test_locate_keys_keyserver(){
+        // test locating keys through a local keyserver after the other mechanisms failed
 
         let name:String  = generate_random_string();
         let name: &str = name.as_str();
 
         let gpg: GPG = get_gpg_init(name);
-        gen_protected_key(gpg.clone());
-
-        let mut file = tempfile().unwrap();
-        writeln!(file, "testing encryption").unwrap();
-        file.flush().unwrap();
-
-        let output: String = PathBuf::from(get_output_dir(name)).join("test_encrypt.txt").to_string_lossy().to_string();
-        let option = EncryptOption{
-            file: Some(file),
-            file_path: None,
-            recipients: None,
-            sign: false,
-            sign_key: None,
-            symmetric: false,
-            symmetric_algo: None,
-            always_trust: true,
-            passphrase: None,
-            output: Some(output.clone()),
-            extra_args: None,
-        };
+        gen_unprotected_key_with_email(gpg.clone(), "alice@example.invalid");
+        let (keyserver, _, fingerprint) = start_hkp_server(gpg.clone(), name);
+
+        let locate_name:String  = generate_random_string();
+        let locate_name: &str = locate_name.as_str();
+        let mut locate_gpg: GPG = get_gpg_init(locate_name);
+        locate_gpg.options = Some(vec!["--keyserver".to_string(), keyserver]);
+        let result: Result<Vec<LocateKeyResult>, GPGError> = locate_gpg.locate_keys(
+            vec!["alice@example.invalid".to_string()],
+            vec![LocateMechanism::Clear, LocateMechanism::Wkd, LocateMechanism::Keyserver]
+        );
+        let result: Vec<LocateKeyResult> = result.unwrap();
+        assert_eq!(result[0].key.as_ref().unwrap().fingerprint, fingerprint);
+        assert_eq!(result[0].mechanism, Some(LocateMechanism::Keyserver));
+
+        // once imported, the local lookup that was tried first will find it
+        let result: Result<Vec<LocateKeyResult>, GPGError> = locate_gpg.locate_keys(
+            vec!["alice@example.invalid".to_string()],
+            vec![LocateMechanism::Keyserver]
+        );
+        assert_eq!(result.unwrap()[0].mechanism, Some(LocateMechanism::Local));
 
-        let result: Result<CmdResult, GPGError> = gpg.encrypt(option);
-        assert!(matches!(result.unwrap_err().error_type, GPGErrorType::InvalidArgumentError(_)));
-        assert_eq!(Path::new(&output).exists(), false);
-    
         cleanup_after_tests(name);
+        cleanup_after_tests(locate_name);
     }
 
     #[test]
-    fn test_decrypt_file_with_key(){
-        // test decrypting file with key
+    fn test_locate_keys_failure(){
+        // test that a failure of the lookup was returned instead of being reported as not found
 
         let name:String  = generate_random_string();
         let name: &str = name.as_str();
 
-        let gpg: GPG = get_gpg_init(name);
-        gen_protected_key(gpg.clone());
-
-        let mut file = tempfile().unwrap();
-        write!(file, "testing decryption").unwrap();
-        file.flush().unwrap();
-
-        let key_result: Vec<ListKeyResult> = list_keys(gpg.clone(), true, false);
-        let output: String = PathBuf::from(get_output_dir(name)).join("test_encrypt.txt").to_string_lossy().to_string();
-        let option = gen_encrypt_default_option(file, vec![key_result[0].keyid.clone()], Some(output.clone()));
-
-        let result: Result<CmdResult, GPGError> = gpg.encrypt(option);
-        assert_eq!(result.unwrap().is_success(), true);
-        assert_eq!(Path::new(&output).exists(), true);
-
-        let decrypt_output: String = PathBuf::from(get_output_dir(name)).join("test_decrypt.txt").to_string_lossy().to_string();
-        let option = gen_decrypt_default_option(output, key_result[0].keyid.clone(), Some(get_key_passphrass()), Some(decrypt_output.clone()));
-        let result = gpg.decrypt(option);
-        assert_eq!(result.unwrap().is_success(), true);
-        assert_eq!(Path::new(&decrypt_output).exists(), true);
+        // the keyserver could not be reached
+        let mut gpg: GPG = get_gpg_init(name);
+        gpg.options = Some(vec!["--keyserver".to_string(), "hkp://127.0.0.1:1".to_string()]);
+        let result: Result<Vec<LocateKeyResult>, GPGError> = gpg.locate_keys(
+            vec!["alice@example.invalid".to_string()],
+            vec![LocateMechanism::Keyserver]
+        );
+        assert!(matches!(result.unwrap_err().error_type, GPGErrorType::GPGProcessError(_)));
 
-        let mut decrypt_file: File = File::open(&decrypt_output).unwrap();
-        let mut buffer: Vec<u8> = Vec::new();
-        decrypt_file.read_to_end(&mut buffer).unwrap();
-        assert_eq!(String::from_utf8_lossy(&buffer), "testing decryption");
+        // gpg itself failed
+        gpg.options = Some(vec!["--no-such-option".to_string()]);
+        let result: Result<Vec<LocateKeyResult>, GPGError> = gpg.locate_keys(
+            vec!["alice@example.invalid".to_string()],
+            vec![LocateMechanism::Local]
+        );
+        assert!(matches!(result.unwrap_err().error_type, GPGErrorType::GPGProcessError(_)));
 
         cleanup_after_tests(name);
     }
 
     #[test]
-    fn test_decrypt_file_with_passphrase(){
-        // test decrypting file with passphrase
+    fn test_locate_keys_no_mechanism(){
+        // test locating keys without any mechanism to try
 
         let name:String  = generate_random_string();
         let name: &str = name.as_str();
 
         let gpg: GPG = get_gpg_init(name);
-        gen_protected_key(gpg.clone());
+        let result: Result<Vec<LocateKeyResult>, GPGError> = gpg.locate_keys(
+            vec!["alice@example.invalid".to_string()],
+            vec![LocateMechanism::Clear, LocateMechanism::NoDefault]
+        );
+        assert!(matches!(result.unwrap_err().error_type, GPGErrorType::InvalidArgumentError(_)));
 
-        let mut file = tempfile().unwrap();
-        write!(file, "testing decryption").unwrap();
-        file.flush().unwrap();
+        cleanup_after_tests(name);
+    }
 
-        let output: String = PathBuf::from(get_output_dir(name)).join("test_encrypt.txt").to_string_lossy().to_string();
-        let option = gen_encrypt_symmetric_option(file, None, "1234".to_string(), Some(output.clone()));
+    #[test]
+    fn test_wkd_hash(){
+        // test the WKD hash against the example of draft-koch-openpgp-webkey-service
+        // ( Joe.Doe@Example.ORG ) and the z-base-32 encoded SHA-1 of an empty local part
+
+        assert_eq!(wkd_hash("Joe.Doe"), "iy9q119eutrkn8s1mk4r39qejnbu3n5q");
+        assert_eq!(wkd_hash("joe.doe"), "iy9q119eutrkn8s1mk4r39qejnbu3n5q");
+        assert_eq!(wkd_hash(""), "5eh48516ppfo4c1iz9z3kaya1nz7obaj");
+        // a local part long enough to need a second SHA-1 block
+        assert_eq!(wkd_hash(&"a".repeat(64)), "yncmiy1mmomrr66zwrjnws1rfe16a3np");
+    }
 
-        let result: Result<CmdResult, GPGError> = gpg.encrypt(option);
+    #[test]
+    fn test_build_wkd_mixed_case_address(){
+        // test that the addresses differing only by case were published together in the same file
+
+        let name:String  = generate_random_string();
+        let name: &str = name.as_str();
+
+        let gpg: GPG = get_gpg_init(name);
+        gen_unprotected_key_with_email(gpg.clone(), "Joe.Doe@Example.org");
+        gen_unprotected_key_with_email(gpg.clone(), "joe.doe@example.org");
+        let keys: Vec<ListKeyResult> = list_keys(gpg.clone(), false, false);
+
+        let dest: String = PathBuf::from(get_output_dir(name)).join("wkd").to_string_lossy().to_string();
+        let result: Result<Vec<WkdEntry>, GPGError> = build_wkd(&gpg, keys, "example.org".to_string(), dest.clone(), WkdMethod::Direct);
+        let result: Vec<WkdEntry> = result.unwrap();
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].address, "joe.doe@example.org");
+        // example from the WKD specification
+        assert_eq!(result[0].hash, "iy9q119eutrkn8s1mk4r39qejnbu3n5q");
+        assert_eq!(result[0].fingerprints.len(), 2);
+
+        let import_name:String  = generate_random_string();
+        let import_name: &str = import_name.as_str();
+        let import_gpg: GPG = get_gpg_init(import_name);
+        let result: Result<CmdResult, GPGError> = import_gpg.import_key(None, Some(result[0].path.clone()), false, None);
         assert_eq!(result.unwrap().is_success(), true);
-        assert_eq!(Path::new(&output).exists(), true);
+        assert_eq!(list_keys(import_gpg.clone(), false, false).len(), 2);
 
-        let decrypt_output: String = PathBuf::from(get_output_dir(name)).join("test_decrypt.txt").to_string_lossy().to_string();
-        let option = gen_decrypt_passphrase_option(output, "1234".to_string(), Some(decrypt_output.clone()));
-        let result = gpg.decrypt(option);
+        cleanup_after_tests(name);
+        cleanup_after_tests(import_name);
+    }
+
+    #[test]
+    fn test_build_wkd_advanced(){
+        // test building the advanced WKD tree with only the keys matching the domain
+
+        let name:String  = generate_random_string();
+        let name: &str = name.as_str();
+
+        let gpg: GPG = get_gpg_init(name);
+        gen_unprotected_key_with_email(gpg.clone(), "Alice@Example.org");
+        gen_unprotected_key_with_email(gpg.clone(), "bob@other.org");
+        let keys: Vec<ListKeyResult> = list_keys(gpg.clone(), false, false);
+
+        let dest: String = PathBuf::from(get_output_dir(name)).join("wkd").to_string_lossy().to_string();
+        let result: Result<Vec<WkdEntry>, GPGError> = build_wkd(&gpg, keys, "example.org".to_string(), dest.clone(), WkdMethod::Advanced);
+        let result: Vec<WkdEntry> = result.unwrap();
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].address, "alice@example.org");
+        assert_eq!(result[0].hash, "kei1q4tipxxu1yj79k9kfukdhfy631xe");
+        let expected: PathBuf = PathBuf::from(dest.clone()).join(".well-known/openpgpkey/example.org/hu").join("kei1q4tipxxu1yj79k9kfukdhfy631xe");
+        assert_eq!(result[0].path, expected.to_string_lossy().to_string());
+        assert_eq!(expected.exists(), true);
+        assert_eq!(PathBuf::from(dest).join(".well-known/openpgpkey/example.org/policy").exists(), true);
+
+        // the published key should only hold the uid of the address
+        let import_name:String  = generate_random_string();
+        let import_name: &str = import_name.as_str();
+        let import_gpg: GPG = get_gpg_init(import_name);
+        let result: Result<CmdResult, GPGError> = import_gpg.import_key(None, Some(expected.to_string_lossy().to_string()), false, None);
         assert_eq!(result.unwrap().is_success(), true);
-        assert_eq!(Path::new(&decrypt_output).exists(), true);
+        let imported: Vec<ListKeyResult> = list_keys(import_gpg.clone(), false, false);
+        assert_eq!(imported.len(), 1);
+        assert!(imported[0].uids[0].contains("Alice@Example.org"));
 
-        let mut decrypt_file: File = File::open(&decrypt_output).unwrap();
-        let mut buffer: Vec<u8> = Vec::new();
-        decrypt_file.read_to_end(&mut buffer).unwrap();
-        assert_eq!(String::from_utf8_lossy(&buffer), "testing decryption");
+        cleanup_after_tests(name);
+        cleanup_after_tests(import_name);
+    }
+
+    #[test]
+    fn test_build_wkd_direct(){
+        // test building the direct WKD tree
+
+        let name:String  = generate_random_string();
+        let name: &str = name.as_str();
+
+        let gpg: GPG = get_gpg_init(name);
+        gen_unprotected_key_with_email(gpg.clone(), "alice@example.org");
+        let keys: Vec<ListKeyResult> = list_keys(gpg.clone(), false, false);
+
+        let dest: String = PathBuf::from(get_output_dir(name)).join("wkd").to_string_lossy().to_string();
+        let result: Result<Vec<WkdEntry>, GPGError> = build_wkd(&gpg, keys, "example.org".to_string(), dest.clone(), WkdMethod::Direct);
+        assert_eq!(result.unwrap().len(), 1);
+        assert_eq!(PathBuf::from(dest.clone()).join(".well-known/openpgpkey/hu").join("kei1q4tipxxu1yj79k9kfukdhfy631xe").exists(), true);
+        assert_eq!(PathBuf::from(dest).join(".well-known/openpgpkey/policy").exists(), true);
 
         cleanup_after_tests(name);
     }
 
+    #[cfg(not(feature = "test_legacy"))]
     #[test]
-    fn test_decrypt_file_with_passphrase_or_key(){
-        // test decrypting file with passphrase
+    fn test_backup_restore_identity(){
+        // test backing up a passphrase protected identity and restoring it into another homedir
+
+        let name:String  = generate_random_string();
+        let name: &str = name.as_str();
+
+        let gpg: GPG = get_gpg_init(name);
+        gen_protected_key(gpg.clone());
+        let key_list: Vec<ListKeyResult> = list_keys(gpg.clone(), true, false);
+        let fingerprint: String = key_list[0].fingerprint.clone();
+        let result: Result<CmdResult, GPGError> = gpg.trust_key(vec![fingerprint.clone()], TrustLevel::Fully);
+        assert_eq!(result.unwrap().is_success(), true);
+
+        let result: Result<String, GPGError> = gpg.backup_identity(fingerprint.clone(), Some(get_key_passphrass()), None);
+        let archive: String = result.unwrap();
+        assert_eq!(Path::new(&archive).exists(), true);
+
+        let restore_name:String  = generate_random_string();
+        let restore_name: &str = restore_name.as_str();
+        let restore_gpg: GPG = get_gpg_init(restore_name);
+        let result: Result<ListKeyResult, GPGError> = restore_gpg.restore_identity(archive);
+        let key: ListKeyResult = result.unwrap();
+        assert_eq!(key.fingerprint, fingerprint);
+        assert_eq!(key.secret_stub, false);
+        assert_eq!(list_keys(restore_gpg.clone(), false, false)[0].ownertrust, "f".to_string());
+        let revocation: PathBuf = PathBuf::from(get_homedir(restore_name)).join("openpgp-revocs.d").join(format!("{}.rev", fingerprint));
+        assert_eq!(revocation.exists(), true);
+
+        cleanup_after_tests(name);
+        cleanup_after_tests(restore_name);
+    }
+
+    #[test]
+    fn test_backup_identity_dest_exists(){
+        // test backing up identity into a file that already exist, the archive must not overwrite it
+
+        let name:String  = generate_random_string();
+        let name: &str = name.as_str();
+
+        let gpg: GPG = get_gpg_init(name);
+        gen_unprotected_key(gpg.clone());
+        let fingerprint: String = list_keys(gpg.clone(), true, false)[0].fingerprint.clone();
+
+        let dest: String = PathBuf::from(get_output_dir(name)).join("backup.tar").to_string_lossy().to_string();
+        let archive: String = gpg.backup_identity(fingerprint.clone(), None, Some(dest.clone())).unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(std::fs::metadata(&archive).unwrap().permissions().mode() & 0o777, 0o600);
+        }
+
+        let result: Result<String, GPGError> = gpg.backup_identity(fingerprint, None, Some(dest));
+        assert!(matches!(result.unwrap_err().error_type, GPGErrorType::WriteFailError(_)));
+
+        cleanup_after_tests(name);
+    }
+
+    #[test]
+    fn test_restore_identity_invalid_manifest(){
+        // test restoring identity from an archive with a crafted fingerprint in the manifest
+
+        let name:String  = generate_random_string();
+        let name: &str = name.as_str();
+
+        let gpg: GPG = get_gpg_init(name);
+        gen_unprotected_key(gpg.clone());
+        let fingerprint: String = list_keys(gpg.clone(), true, false)[0].fingerprint.clone();
+        let archive: String = gpg.backup_identity(fingerprint.clone(), None, None).unwrap();
+        let entries: Vec<(String, Vec<u8>)> = read_tar(&archive).unwrap();
+
+        let restore_name:String  = generate_random_string();
+        let restore_name: &str = restore_name.as_str();
+        let restore_gpg: GPG = get_gpg_init(restore_name);
+        let other_fingerprint: String = "0".repeat(40);
+        for crafted in ["../../x".to_string(), other_fingerprint.clone()] {
+            let crafted_entries: Vec<(String, Vec<u8>)> = entries.iter().map(|(entry, content)| {
+                if entry == "manifest.txt" {
+                    let manifest: String = String::from_utf8_lossy(content).replace(&fingerprint, &crafted);
+                    return (entry.clone(), manifest.into_bytes());
+                }
+                return (entry.clone(), content.clone());
+            }).collect();
+            let crafted_archive: String = PathBuf::from(get_output_dir(name)).join("crafted.tar").to_string_lossy().to_string();
+            let _ = std::fs::remove_file(&crafted_archive);
+            write_tar(&crafted_archive, &crafted_entries).unwrap();
+
+            let result: Result<ListKeyResult, GPGError> = restore_gpg.restore_identity(crafted_archive);
+            assert!(matches!(result.unwrap_err().error_type, GPGErrorType::InvalidArgumentError(_)));
+        }
+        let revocs_dir: PathBuf = PathBuf::from(get_homedir(restore_name)).join("openpgp-revocs.d");
+        assert_eq!(revocs_dir.join(format!("{}.rev", other_fingerprint)).exists(), false);
+        assert_eq!(PathBuf::from(get_homedir(restore_name)).join("x.rev").exists(), false);
+
+        cleanup_after_tests(name);
+        cleanup_after_tests(restore_name);
+    }
+
+    #[test]
+    fn test_restore_identity_archive_not_found(){
+        // test restoring identity from an archive that do not exist
+
+        let name:String  = generate_random_string();
+        let name: &str = name.as_str();
+
+        let gpg: GPG = get_gpg_init(name);
+        let archive: String = PathBuf::from(get_output_dir(name)).join("not_exist.tar").to_string_lossy().to_string();
+        let result: Result<ListKeyResult, GPGError> = gpg.restore_identity(archive);
+        assert!(matches!(result.unwrap_err().error_type, GPGErrorType::FileNotFoundError(_)));
+
+        cleanup_after_tests(name);
+    }
+
+    #[cfg(not(feature = "test_legacy"))]
+    #[test]
+    fn test_copy_keys_to(){
+        // test copying public key, secret key and ownertrust into another homedir
+
+        let name:String  = generate_random_string();
+        let name: &str = name.as_str();
+
+        let gpg: GPG = get_gpg_init(name);
+        gen_protected_key(gpg.clone());
+        let key_list: Vec<ListKeyResult> = list_keys(gpg.clone(), true, false);
+        let fingerprint: String = key_list[0].fingerprint.clone();
+
+        let target_name:String  = generate_random_string();
+        let target_name: &str = target_name.as_str();
+        let target_gpg: GPG = get_gpg_init(target_name);
+
+        let result: Result<Vec<CopyKeyResult>, GPGError> = gpg.copy_keys_to(&target_gpg, Some(vec![fingerprint.clone()]), true, true, Some(get_key_passphrass()));
+        let copied: Vec<CopyKeyResult> = result.unwrap();
+        assert_eq!(copied.len(), 1);
+        assert_eq!(copied[0].fingerprint, fingerprint);
+        assert_eq!(copied[0].found, true);
+        assert_eq!(copied[0].public, true);
+        assert_eq!(copied[0].secret, true);
+        assert_eq!(copied[0].ownertrust, Some("6".to_string()));
+        assert_eq!(copied[0].unchanged, false);
+
+        let secret_keys: Vec<ListKeyResult> = list_keys(target_gpg.clone(), true, false);
+        assert_eq!(secret_keys[0].fingerprint, fingerprint);
+        assert_eq!(list_keys(target_gpg.clone(), false, false)[0].ownertrust, "u".to_string());
+        // nothing should be written to the output dir
+        assert_eq!(std::fs::read_dir(get_output_dir(name)).unwrap().count(), 0);
+
+        cleanup_after_tests(name);
+        cleanup_after_tests(target_name);
+    }
+
+    #[test]
+    fn test_copy_keys_to_unchanged(){
+        // test copying public key that was already in the other homedir
+
+        let name:String  = generate_random_string();
+        let name: &str = name.as_str();
+
+        let gpg: GPG = get_gpg_init(name);
+        gen_unprotected_key(gpg.clone());
+
+        let target_name:String  = generate_random_string();
+        let target_name: &str = target_name.as_str();
+        let target_gpg: GPG = get_gpg_init(target_name);
+
+        let result: Result<Vec<CopyKeyResult>, GPGError> = gpg.copy_keys_to(&target_gpg, None, false, false, None);
+        let copied: Vec<CopyKeyResult> = result.unwrap();
+        assert_eq!(copied[0].public, true);
+        assert_eq!(copied[0].secret, false);
+        assert_eq!(copied[0].ownertrust, None);
+        assert_eq!(list_keys(target_gpg.clone(), true, false).len(), 0);
+
+        let result: Result<Vec<CopyKeyResult>, GPGError> = gpg.copy_keys_to(&target_gpg, None, false, false, None);
+        let copied: Vec<CopyKeyResult> = result.unwrap();
+        assert_eq!(copied[0].public, false);
+        assert_eq!(copied[0].unchanged, true);
+
+        cleanup_after_tests(name);
+        cleanup_after_tests(target_name);
+    }
+
+    #[test]
+    fn test_copy_keys_to_not_found(){
+        // test copying with keyid(s) that matched no key
+
+        let name:String  = generate_random_string();
+        let name: &str = name.as_str();
+
+        let gpg: GPG = get_gpg_init(name);
+        gen_unprotected_key(gpg.clone());
+        let fingerprint: String = list_keys(gpg.clone(), false, false)[0].fingerprint.clone();
+
+        let target_name:String  = generate_random_string();
+        let target_name: &str = target_name.as_str();
+        let target_gpg: GPG = get_gpg_init(target_name);
+
+        let missing: String = "0123456789ABCDEF0123456789ABCDEF01234567".to_string();
+        let result: Result<Vec<CopyKeyResult>, GPGError> = gpg.copy_keys_to(&target_gpg, Some(vec![fingerprint.clone(), missing.clone()]), false, false, None);
+        let copied: Vec<CopyKeyResult> = result.unwrap();
+        assert_eq!(copied.len(), 2);
+        assert_eq!(copied[0].fingerprint, fingerprint);
+        assert_eq!(copied[0].found, true);
+        assert_eq!(copied[0].public, true);
+        assert_eq!(copied[1].fingerprint, missing);
+        assert_eq!(copied[1].found, false);
+        assert_eq!(copied[1].public, false);
+        assert_eq!(copied[1].unchanged, false);
+
+        // nothing to copy at all
+        let result: Result<Vec<CopyKeyResult>, GPGError> = gpg.copy_keys_to(&target_gpg, Some(vec![missing.clone()]), true, true, None);
+        let copied: Vec<CopyKeyResult> = result.unwrap();
+        assert_eq!(copied.len(), 1);
+        assert_eq!(copied[0].found, false);
+        assert_eq!(list_keys(target_gpg.clone(), false, false).len(), 1);
+
+        cleanup_after_tests(name);
+        cleanup_after_tests(target_name);
+    }
+
+    #[test]
+    fn test_encrypt_file(){
+        // test encrypting file with just key (default)
 
         let name:String  = generate_random_string();
         let name: &str = name.as_str();
@@ -1249,47 +1753,73 @@ mod tests {
         gen_protected_key(gpg.clone());
 
         let mut file = tempfile().unwrap();
-        write!(file, "testing decryption").unwrap();
+        writeln!(file, "testing encryption").unwrap();
         file.flush().unwrap();
 
-        let key_result: Vec<ListKeyResult> = list_keys(gpg.clone(), true, false);
+        let result: Vec<ListKeyResult> = list_keys(gpg.clone(), true, false);
         let output: String = PathBuf::from(get_output_dir(name)).join("test_encrypt.txt").to_string_lossy().to_string();
-        let option = gen_encrypt_key_and_symmetric_option(file, vec![key_result[0].keyid.clone()],None, "1234".to_string(), Some(output.clone()));
+        let option = gen_encrypt_default_option(file, vec![result[0].keyid.clone()], Some(output.clone()));
 
         let result: Result<CmdResult, GPGError> = gpg.encrypt(option);
         assert_eq!(result.unwrap().is_success(), true);
         assert_eq!(Path::new(&output).exists(), true);
+    
+        cleanup_after_tests(name);
+    }
 
-        let decrypt_output: String = PathBuf::from(get_output_dir(name)).join("test_decrypt.txt").to_string_lossy().to_string();
-        // decrypt with key
-        let option = gen_decrypt_default_option(output.clone(), key_result[0].keyid.clone(), Some(get_key_passphrass()), Some(decrypt_output.clone()));
-        let result = gpg.decrypt(option);
-        assert_eq!(result.unwrap().is_success(), true);
-        assert_eq!(Path::new(&decrypt_output).exists(), true);
+    #[test]
+    fn test_encrypt_file_symmetric(){
+        // test encrypting file with just passphrase (symmetric)
 
-        let mut decrypt_file: File = File::open(&decrypt_output).unwrap();
-        let mut buffer: Vec<u8> = Vec::new();
-        decrypt_file.read_to_end(&mut buffer).unwrap();
-        assert_eq!(String::from_utf8_lossy(&buffer), "testing decryption");
+        let name:String  = generate_random_string();
+        let name: &str = name.as_str();
 
-        // decrypt with passphrase
-        let option = gen_decrypt_passphrase_option(output, "1234".to_string(), Some(decrypt_output.clone()));
-        let result = gpg.decrypt(option);
+        let gpg: GPG = get_gpg_init(name);
+        gen_protected_key(gpg.clone());
+
+        let mut file = tempfile().unwrap();
+        writeln!(file, "testing encryption").unwrap();
+        file.flush().unwrap();
+
+        let output: String = PathBuf::from(get_output_dir(name)).join("test_encrypt.txt").to_string_lossy().to_string();
+        let option = gen_encrypt_symmetric_option(file, None, "1234".to_string(), Some(output.clone()));
+
+        let result: Result<CmdResult, GPGError> = gpg.encrypt(option);
         assert_eq!(result.unwrap().is_success(), true);
-        assert_eq!(Path::new(&decrypt_output).exists(), true);
+        assert_eq!(Path::new(&output).exists(), true);
+    
+        cleanup_after_tests(name);
+    }
 
-        let mut decrypt_file: File = File::open(&decrypt_output).unwrap();
-        let mut buffer: Vec<u8> = Vec::new();
-        decrypt_file.read_to_end(&mut buffer).unwrap();
-        assert_eq!(String::from_utf8_lossy(&buffer), "testing decryption");
+    #[test]
+    fn test_encrypt_file_key_and_symmetric(){
+        // test encrypting file with both key and passphrase (key and symmetric)
+
+        let name:String  = generate_random_string();
+        let name: &str = name.as_str();
+
+        let gpg: GPG = get_gpg_init(name);
+        gen_protected_key(gpg.clone());
+
+        let mut file = tempfile().unwrap();
+        writeln!(file, "testing encryption").unwrap();
+        file.flush().unwrap();
+
+        let result: Vec<ListKeyResult> = list_keys(gpg.clone(), true, false);
+        let output: String = PathBuf::from(get_output_dir(name)).join("test_encrypt.txt").to_string_lossy().to_string();
+        let option = gen_encrypt_key_and_symmetric_option(file, vec![result[0].keyid.clone()], None, "1234".to_string(), Some(output.clone()));
 
+        let result: Result<CmdResult, GPGError> = gpg.encrypt(option);
+        assert_eq!(result.unwrap().is_success(), true);
+        assert_eq!(Path::new(&output).exists(), true);
+    
         cleanup_after_tests(name);
     }
 
     #[cfg(not(feature = "test_legacy"))]
     #[test]
-    fn test_decrypt_file_with_passphrase_or_key_fail(){
-        // test decrypting file with passphrase
+    fn test_encrypt_file_and_sign_with_protected_key(){
+        // test encrypting file and signing it with a passphrase protected key
 
         let name:String  = generate_random_string();
         let name: &str = name.as_str();
@@ -1298,29 +1828,1530 @@ mod tests {
         gen_protected_key(gpg.clone());
 
         let mut file = tempfile().unwrap();
-        write!(file, "testing decryption").unwrap();
+        write!(file, "testing encryption with signing").unwrap();
         file.flush().unwrap();
 
         let key_result: Vec<ListKeyResult> = list_keys(gpg.clone(), true, false);
         let output: String = PathBuf::from(get_output_dir(name)).join("test_encrypt.txt").to_string_lossy().to_string();
-        let option = gen_encrypt_key_and_symmetric_option(file, vec![key_result[0].keyid.clone()],None, "1234".to_string(), Some(output.clone()));
+        let mut option: EncryptOption = gen_encrypt_default_option(file, vec![key_result[0].keyid.clone()], Some(output.clone()));
+        option.sign = true;
+        option.sign_key = Some(key_result[0].keyid.clone());
+        option.key_passphrase = Some(get_key_passphrass());
 
         let result: Result<CmdResult, GPGError> = gpg.encrypt(option);
         assert_eq!(result.unwrap().is_success(), true);
         assert_eq!(Path::new(&output).exists(), true);
 
         let decrypt_output: String = PathBuf::from(get_output_dir(name)).join("test_decrypt.txt").to_string_lossy().to_string();
-        // decrypt with key, but didn't provide key passphrase
-        let option = gen_decrypt_default_option(output.clone(), key_result[0].keyid.clone(), None, Some(decrypt_output.clone()));
-        let result = gpg.decrypt(option);
-        assert!(matches!(result.unwrap_err().error_type, GPGErrorType::GPGProcessError(_)));
-        assert_eq!(Path::new(&decrypt_output).exists(), false);
+        let option = gen_decrypt_default_option(output, key_result[0].keyid.clone(), Some(get_key_passphrass()), Some(decrypt_output.clone()));
+        let result: CmdResult = gpg.decrypt(option).unwrap();
+        assert_eq!(result.get_raw_data().unwrap().contains("[GNUPG:] GOODSIG"), true);
+        let mut buffer: String = String::new();
+        File::open(&decrypt_output).unwrap().read_to_string(&mut buffer).unwrap();
+        assert_eq!(buffer, "testing encryption with signing");
 
-        // decrypt with passphrase, but wrong passphrase
-        let option = gen_decrypt_passphrase_option(output, "123".to_string(), Some(decrypt_output.clone()));
-        let result = gpg.decrypt(option);
-        assert!(matches!(result.unwrap_err().error_type, GPGErrorType::GPGProcessError(_)));
-        assert_eq!(Path::new(&decrypt_output).exists(), false);
+        cleanup_after_tests(name);
+    }
+
+    #[test]
+    fn test_encrypt_file_default_fail_no_receipient(){
+        // test encrypting file with just key (default) but without providing recipient (list of key id)
+
+        let name:String  = generate_random_string();
+        let name: &str = name.as_str();
+
+        let gpg: GPG = get_gpg_init(name);
+        gen_protected_key(gpg.clone());
+
+        let mut file = tempfile().unwrap();
+        writeln!(file, "testing encryption").unwrap();
+        file.flush().unwrap();
+
+        let output: String = PathBuf::from(get_output_dir(name)).join("test_encrypt.txt").to_string_lossy().to_string();
+        let option = EncryptOption{
+            file: Some(file),
+            file_path: None,
+            recipients: None,
+            hidden_recipients: None,
+            recipient_files: None,
+            throw_keyids: false,
+            encrypt_to: None,
+            no_encrypt_to: false,
+            sign: false,
+            sign_key: None,
+            symmetric: false,
+            symmetric_algo: None,
+            cipher_algo: None,
+            digest_algo: None,
+            compress_algo: None,
+            compress_level: None,
+            aead_algo: None,
+            force_ocb: false,
+            compliance: None,
+            always_trust: true,
+            passphrase: None,
+            key_passphrase: None,
+            passphrase_provider: None,
+            armor: None,
+            textmode: false,
+            notations: vec![],
+            policy_url: None,
+            keyserver_url: None,
+            output: Some(output.clone()),
+            extra_args: None,
+        };
+
+        let result: Result<CmdResult, GPGError> = gpg.encrypt(option);
+        assert!(matches!(result.unwrap_err().error_type, GPGErrorType::InvalidArgumentError(_)));
+        assert_eq!(Path::new(&output).exists(), false);
+    
+        cleanup_after_tests(name);
+    }
+
+    #[test]
+    fn test_encrypt_hidden_recipient_throw_keyids(){
+        // test encrypting with hidden recipient and throw keyids, the keyid should not be shown in the encrypted data
+
+        let name:String  = generate_random_string();
+        let name: &str = name.as_str();
+
+        let gpg: GPG = get_gpg_init(name);
+        gen_unprotected_key(gpg.clone());
+        gen_unprotected_key(gpg.clone());
+        let key_list: Vec<ListKeyResult> = list_keys(gpg.clone(), true, false);
+
+        let mut option: EncryptOption = EncryptOption::default(None, None, vec![key_list[0].keyid.clone()], None);
+        option.hidden_recipients = Some(vec![key_list[1].keyid.clone()]);
+        option.throw_keyids = true;
+        let encrypted: Vec<u8> = gpg.encrypt_bytes(b"testing hidden recipient", option).unwrap();
+
+        let mut option: DecryptOption = DecryptOption::default(None, None, key_list[1].keyid.clone(), None, None);
+        option.recipient = None;
+        let mut decrypted: Vec<u8> = vec![];
+        let result: CmdResult = gpg.decrypt_stream(Cursor::new(encrypted), &mut decrypted, option).unwrap();
+        assert_eq!(decrypted, b"testing hidden recipient".to_vec());
+        let raw_data: String = result.raw_data.unwrap();
+        assert!(raw_data.contains("ENC_TO 0000000000000000"));
+        assert!(!raw_data.contains(&format!("ENC_TO {}", key_list[0].keyid)));
+        assert!(!raw_data.contains(&format!("ENC_TO {}", key_list[1].keyid)));
+
+        cleanup_after_tests(name);
+    }
+
+    #[test]
+    fn test_encrypt_recipient_file(){
+        // test encrypting to a public key file that is not in the keyring
+
+        let name:String  = generate_random_string();
+        let name: &str = name.as_str();
+        let other_name:String  = generate_random_string();
+        let other_name: &str = other_name.as_str();
+
+        let gpg: GPG = get_gpg_init(name);
+        gen_unprotected_key(gpg.clone());
+        let key_list: Vec<ListKeyResult> = list_keys(gpg.clone(), true, false);
+        let public_key: String = PathBuf::from(get_output_dir(name)).join("public_key.gpg").to_string_lossy().to_string();
+        let _ = gpg.export_public_key(Some(vec![key_list[0].keyid.clone()]), Some(public_key.clone()), None);
+
+        let other_gpg: GPG = get_gpg_init(other_name);
+        let mut option: EncryptOption = EncryptOption::default(None, None, vec![], None);
+        option.recipients = None;
+        option.recipient_files = Some(vec![public_key]);
+        let encrypted: Vec<u8> = other_gpg.encrypt_bytes(b"testing recipient file", option).unwrap();
+        // the key should not be imported into the keyring
+        assert_eq!(list_keys(other_gpg.clone(), false, false).len(), 0);
+
+        let option: DecryptOption = DecryptOption::default(None, None, key_list[0].keyid.clone(), None, None);
+        let result: Result<Vec<u8>, GPGError> = gpg.decrypt_bytes(&encrypted, option);
+        assert_eq!(result.unwrap(), b"testing recipient file".to_vec());
+
+        let mut option: EncryptOption = EncryptOption::default(None, None, vec![], None);
+        option.recipients = None;
+        option.recipient_files = Some(vec![PathBuf::from(get_output_dir(other_name)).join("not_exist.gpg").to_string_lossy().to_string()]);
+        let result: Result<Vec<u8>, GPGError> = other_gpg.encrypt_bytes(b"testing recipient file", option);
+        assert!(matches!(result.unwrap_err().error_type, GPGErrorType::FileNotFoundError(_)));
+
+        cleanup_after_tests(name);
+        cleanup_after_tests(other_name);
+    }
+
+    #[test]
+    fn test_encrypt_to(){
+        // test always adding a self recipient with encrypt_to, and disabling it with no_encrypt_to
+
+        let name:String  = generate_random_string();
+        let name: &str = name.as_str();
+
+        let gpg: GPG = get_gpg_init(name);
+        gen_unprotected_key(gpg.clone());
+        gen_unprotected_key(gpg.clone());
+        let key_list: Vec<ListKeyResult> = list_keys(gpg.clone(), true, false);
+
+        let mut option: EncryptOption = EncryptOption::default(None, None, vec![key_list[0].keyid.clone()], None);
+        option.encrypt_to = Some(vec![key_list[1].keyid.clone()]);
+        let encrypted: Vec<u8> = gpg.encrypt_bytes(b"testing encrypt to", option).unwrap();
+        let option: DecryptOption = DecryptOption::default(None, None, key_list[1].keyid.clone(), None, None);
+        let mut decrypted: Vec<u8> = vec![];
+        let result: CmdResult = gpg.decrypt_stream(Cursor::new(encrypted), &mut decrypted, option).unwrap();
+        assert_eq!(result.raw_data.as_ref().unwrap().matches("ENC_TO ").count(), 2);
+
+        // the encrypt-to set in gpg.conf is disabled by no_encrypt_to
+        std::fs::write(PathBuf::from(get_homedir(name)).join("gpg.conf"), format!("encrypt-to {}\n", key_list[1].keyid)).unwrap();
+        let option: EncryptOption = EncryptOption::default(None, None, vec![key_list[0].keyid.clone()], None);
+        let encrypted: Vec<u8> = gpg.encrypt_bytes(b"testing encrypt to", option).unwrap();
+        assert_eq!(gpg.inspect_message_bytes(&encrypted).unwrap().recipients.len(), 2);
+        let mut option: EncryptOption = EncryptOption::default(None, None, vec![key_list[0].keyid.clone()], None);
+        option.no_encrypt_to = true;
+        let encrypted: Vec<u8> = gpg.encrypt_bytes(b"testing encrypt to", option).unwrap();
+        let option: DecryptOption = DecryptOption::default(None, None, key_list[0].keyid.clone(), None, None);
+        let mut decrypted: Vec<u8> = vec![];
+        let result: CmdResult = gpg.decrypt_stream(Cursor::new(encrypted), &mut decrypted, option).unwrap();
+        assert_eq!(result.raw_data.as_ref().unwrap().matches("ENC_TO ").count(), 1);
+
+        // no_encrypt_to would also drop the encrypt_to key(s)
+        let mut option: EncryptOption = EncryptOption::default(None, None, vec![key_list[0].keyid.clone()], None);
+        option.encrypt_to = Some(vec![key_list[1].keyid.clone()]);
+        option.no_encrypt_to = true;
+        let result: Result<Vec<u8>, GPGError> = gpg.encrypt_bytes(b"testing encrypt to", option);
+        assert!(matches!(result.unwrap_err().error_type, GPGErrorType::InvalidArgumentError(_)));
+
+        // encrypt_to alone is not enough to encrypt
+        let mut option: EncryptOption = EncryptOption::default(None, None, vec![], None);
+        option.recipients = None;
+        option.encrypt_to = Some(vec![key_list[1].keyid.clone()]);
+        let result: Result<Vec<u8>, GPGError> = gpg.encrypt_bytes(b"testing encrypt to", option);
+        assert!(matches!(result.unwrap_err().error_type, GPGErrorType::InvalidArgumentError(_)));
+
+        cleanup_after_tests(name);
+    }
+
+    #[test]
+    fn test_encrypt_with_algo(){
+        // test encrypting with forced cipher, compression and compliance
+
+        let name:String  = generate_random_string();
+        let name: &str = name.as_str();
+
+        let gpg: GPG = get_gpg_init(name);
+        gen_unprotected_key(gpg.clone());
+        let key_list: Vec<ListKeyResult> = list_keys(gpg.clone(), true, false);
+        let config: HashMap<String, Vec<String>> = gpg.list_config().unwrap();
+        assert!(config.get("ciphername").unwrap().contains(&"CAMELLIA128".to_string()));
+
+        let mut option: EncryptOption = EncryptOption::default(None, None, vec![key_list[0].keyid.clone()], None);
+        option.cipher_algo = Some(CipherAlgo::Camellia128);
+        option.compress_algo = Some(CompressAlgo::Zip);
+        option.compress_level = Some(9);
+        option.compliance = Some(Compliance::RFC4880);
+        let encrypted: Vec<u8> = gpg.encrypt_bytes(b"testing algo", option).unwrap();
+
+        let option: DecryptOption = DecryptOption::default(None, None, key_list[0].keyid.clone(), None, None);
+        let mut decrypted: Vec<u8> = vec![];
+        let result: CmdResult = gpg.decrypt_stream(Cursor::new(encrypted), &mut decrypted, option).unwrap();
+        assert_eq!(decrypted, b"testing algo".to_vec());
+        // DECRYPTION_INFO <mdc_method> <sym_algo>, 11 is CAMELLIA128
+        assert!(result.raw_data.unwrap().contains("DECRYPTION_INFO 2 11"));
+
+        cleanup_after_tests(name);
+    }
+
+    #[test]
+    fn test_encrypt_with_algo_invalid(){
+        // test encrypting with invalid compress level
+
+        let name:String  = generate_random_string();
+        let name: &str = name.as_str();
+
+        let gpg: GPG = get_gpg_init(name);
+        gen_unprotected_key(gpg.clone());
+        let key_list: Vec<ListKeyResult> = list_keys(gpg.clone(), true, false);
+
+        let mut option: EncryptOption = EncryptOption::default(None, None, vec![key_list[0].keyid.clone()], None);
+        option.compress_level = Some(10);
+        let result: Result<Vec<u8>, GPGError> = gpg.encrypt_bytes(b"testing algo", option);
+        assert!(matches!(result.unwrap_err().error_type, GPGErrorType::InvalidArgumentError(_)));
+
+        cleanup_after_tests(name);
+    }
+
+    #[test]
+    fn test_encrypt_with_aead_algo(){
+        // test encrypting with AEAD, which is only supported from gpg 2.3
+
+        let name:String  = generate_random_string();
+        let name: &str = name.as_str();
+
+        let gpg: GPG = get_gpg_init(name);
+        gen_unprotected_key(gpg.clone());
+        let key_list: Vec<ListKeyResult> = list_keys(gpg.clone(), true, false);
+
+        let mut option: EncryptOption = EncryptOption::default(None, None, vec![key_list[0].keyid.clone()], None);
+        option.aead_algo = Some(AeadAlgo::EAX);
+        let result: Result<Vec<u8>, GPGError> = gpg.encrypt_bytes(b"testing aead", option);
+        if gpg.version < 2.3 {
+            assert!(matches!(result.unwrap_err().error_type, GPGErrorType::InvalidArgumentError(_)));
+        } else {
+            assert!(result.is_ok());
+        }
+
+        // pretend to be gpg 2.3 so the option will always be passed to gpg
+        let mut aead_gpg: GPG = gpg.clone();
+        if aead_gpg.version < 2.3 {
+            aead_gpg.version = 2.3;
+        }
+        let mut option: EncryptOption = EncryptOption::default(None, None, vec![key_list[0].keyid.clone()], None);
+        option.aead_algo = Some(AeadAlgo::EAX);
+        let result: Result<Vec<u8>, GPGError> = aead_gpg.encrypt_bytes(b"testing aead", option);
+        if gpg.version < 2.3 {
+            let error: GPGError = result.unwrap_err();
+            assert!(error.cmd_result.unwrap().raw_data.unwrap().contains("--aead-algo"));
+        } else {
+            let option: DecryptOption = DecryptOption::default(None, None, key_list[0].keyid.clone(), None, None);
+            let mut decrypted: Vec<u8> = vec![];
+            let result: CmdResult = gpg.decrypt_stream(Cursor::new(result.unwrap()), &mut decrypted, option).unwrap();
+            assert_eq!(decode_decrypt_result(&result).aead_algo, Some(AeadAlgo::EAX));
+        }
+
+        cleanup_after_tests(name);
+    }
+
+    #[test]
+    fn test_encrypt_file_symmetric_fail_no_passphrase(){
+        // test encrypting file with just passphrase (symmetric) but without providing passphrase
+
+        let name:String  = generate_random_string();
+        let name: &str = name.as_str();
+
+        let gpg: GPG = get_gpg_init(name);
+        gen_protected_key(gpg.clone());
+
+        let mut file = tempfile().unwrap();
+        writeln!(file, "testing encryption").unwrap();
+        file.flush().unwrap();
+
+        let output: String = PathBuf::from(get_output_dir(name)).join("test_encrypt.txt").to_string_lossy().to_string();
+        let option = EncryptOption{
+            file: Some(file),
+            file_path: None,
+            recipients: None,
+            hidden_recipients: None,
+            recipient_files: None,
+            throw_keyids: false,
+            encrypt_to: None,
+            no_encrypt_to: false,
+            sign: false,
+            sign_key: None,
+            symmetric: false,
+            symmetric_algo: None,
+            cipher_algo: None,
+            digest_algo: None,
+            compress_algo: None,
+            compress_level: None,
+            aead_algo: None,
+            force_ocb: false,
+            compliance: None,
+            always_trust: true,
+            passphrase: None,
+            key_passphrase: None,
+            passphrase_provider: None,
+            armor: None,
+            textmode: false,
+            notations: vec![],
+            policy_url: None,
+            keyserver_url: None,
+            output: Some(output.clone()),
+            extra_args: None,
+        };
+
+        let result: Result<CmdResult, GPGError> = gpg.encrypt(option);
+        assert!(matches!(result.unwrap_err().error_type, GPGErrorType::InvalidArgumentError(_)));
+        assert_eq!(Path::new(&output).exists(), false);
+    
+        cleanup_after_tests(name);
+    }
+
+    #[test]
+    fn test_encrypt_file_key_and_symmetric_fail_no_passphrase(){
+        // test encrypting file with both key and passphrase (symmetric) but without providing both
+
+        let name:String  = generate_random_string();
+        let name: &str = name.as_str();
+
+        let gpg: GPG = get_gpg_init(name);
+        gen_protected_key(gpg.clone());
+
+        let mut file = tempfile().unwrap();
+        writeln!(file, "testing encryption").unwrap();
+        file.flush().unwrap();
+
+        let output: String = PathBuf::from(get_output_dir(name)).join("test_encrypt.txt").to_string_lossy().to_string();
+        let option = EncryptOption{
+            file: Some(file),
+            file_path: None,
+            recipients: None,
+            hidden_recipients: None,
+            recipient_files: None,
+            throw_keyids: false,
+            encrypt_to: None,
+            no_encrypt_to: false,
+            sign: false,
+            sign_key: None,
+            symmetric: false,
+            symmetric_algo: None,
+            cipher_algo: None,
+            digest_algo: None,
+            compress_algo: None,
+            compress_level: None,
+            aead_algo: None,
+            force_ocb: false,
+            compliance: None,
+            always_trust: true,
+            passphrase: None,
+            key_passphrase: None,
+            passphrase_provider: None,
+            armor: None,
+            textmode: false,
+            notations: vec![],
+            policy_url: None,
+            keyserver_url: None,
+            output: Some(output.clone()),
+            extra_args: None,
+        };
+
+        let result: Result<CmdResult, GPGError> = gpg.encrypt(option);
+        assert!(matches!(result.unwrap_err().error_type, GPGErrorType::InvalidArgumentError(_)));
+        assert_eq!(Path::new(&output).exists(), false);
+    
+        cleanup_after_tests(name);
+    }
+
+    #[test]
+    fn test_decrypt_file_with_key(){
+        // test decrypting file with key
+
+        let name:String  = generate_random_string();
+        let name: &str = name.as_str();
+
+        let gpg: GPG = get_gpg_init(name);
+        gen_protected_key(gpg.clone());
+
+        let mut file = tempfile().unwrap();
+        write!(file, "testing decryption").unwrap();
+        file.flush().unwrap();
+
+        let key_result: Vec<ListKeyResult> = list_keys(gpg.clone(), true, false);
+        let output: String = PathBuf::from(get_output_dir(name)).join("test_encrypt.txt").to_string_lossy().to_string();
+        let option = gen_encrypt_default_option(file, vec![key_result[0].keyid.clone()], Some(output.clone()));
+
+        let result: Result<CmdResult, GPGError> = gpg.encrypt(option);
+        assert_eq!(result.unwrap().is_success(), true);
+        assert_eq!(Path::new(&output).exists(), true);
+
+        let decrypt_output: String = PathBuf::from(get_output_dir(name)).join("test_decrypt.txt").to_string_lossy().to_string();
+        let option = gen_decrypt_default_option(output, key_result[0].keyid.clone(), Some(get_key_passphrass()), Some(decrypt_output.clone()));
+        let result = gpg.decrypt(option);
+        assert_eq!(result.unwrap().is_success(), true);
+        assert_eq!(Path::new(&decrypt_output).exists(), true);
+
+        let mut decrypt_file: File = File::open(&decrypt_output).unwrap();
+        let mut buffer: Vec<u8> = Vec::new();
+        decrypt_file.read_to_end(&mut buffer).unwrap();
+        assert_eq!(String::from_utf8_lossy(&buffer), "testing decryption");
+
+        cleanup_after_tests(name);
+    }
+
+    #[test]
+    fn test_decrypt_file_with_passphrase(){
+        // test decrypting file with passphrase
+
+        let name:String  = generate_random_string();
+        let name: &str = name.as_str();
+
+        let gpg: GPG = get_gpg_init(name);
+        gen_protected_key(gpg.clone());
+
+        let mut file = tempfile().unwrap();
+        write!(file, "testing decryption").unwrap();
+        file.flush().unwrap();
+
+        let output: String = PathBuf::from(get_output_dir(name)).join("test_encrypt.txt").to_string_lossy().to_string();
+        let option = gen_encrypt_symmetric_option(file, None, "1234".to_string(), Some(output.clone()));
+
+        let result: Result<CmdResult, GPGError> = gpg.encrypt(option);
+        assert_eq!(result.unwrap().is_success(), true);
+        assert_eq!(Path::new(&output).exists(), true);
+
+        let decrypt_output: String = PathBuf::from(get_output_dir(name)).join("test_decrypt.txt").to_string_lossy().to_string();
+        let option = gen_decrypt_passphrase_option(output, "1234".to_string(), Some(decrypt_output.clone()));
+        let result = gpg.decrypt(option);
+        assert_eq!(result.unwrap().is_success(), true);
+        assert_eq!(Path::new(&decrypt_output).exists(), true);
+
+        let mut decrypt_file: File = File::open(&decrypt_output).unwrap();
+        let mut buffer: Vec<u8> = Vec::new();
+        decrypt_file.read_to_end(&mut buffer).unwrap();
+        assert_eq!(String::from_utf8_lossy(&buffer), "testing decryption");
+
+        cleanup_after_tests(name);
+    }
+
+    #[test]
+    fn test_decrypt_file_with_passphrase_or_key(){
+        // test decrypting file with passphrase
+
+        let name:String  = generate_random_string();
+        let name: &str = name.as_str();
+
+        let gpg: GPG = get_gpg_init(name);
+        gen_protected_key(gpg.clone());
+
+        let mut file = tempfile().unwrap();
+        write!(file, "testing decryption").unwrap();
+        file.flush().unwrap();
+
+        let key_result: Vec<ListKeyResult> = list_keys(gpg.clone(), true, false);
+        let output: String = PathBuf::from(get_output_dir(name)).join("test_encrypt.txt").to_string_lossy().to_string();
+        let option = gen_encrypt_key_and_symmetric_option(file, vec![key_result[0].keyid.clone()],None, "1234".to_string(), Some(output.clone()));
+
+        let result: Result<CmdResult, GPGError> = gpg.encrypt(option);
+        assert_eq!(result.unwrap().is_success(), true);
+        assert_eq!(Path::new(&output).exists(), true);
+
+        let decrypt_output: String = PathBuf::from(get_output_dir(name)).join("test_decrypt.txt").to_string_lossy().to_string();
+        // decrypt with key
+        let option = gen_decrypt_default_option(output.clone(), key_result[0].keyid.clone(), Some(get_key_passphrass()), Some(decrypt_output.clone()));
+        let result = gpg.decrypt(option);
+        assert_eq!(result.unwrap().is_success(), true);
+        assert_eq!(Path::new(&decrypt_output).exists(), true);
+
+        let mut decrypt_file: File = File::open(&decrypt_output).unwrap();
+        let mut buffer: Vec<u8> = Vec::new();
+        decrypt_file.read_to_end(&mut buffer).unwrap();
+        assert_eq!(String::from_utf8_lossy(&buffer), "testing decryption");
+
+        // decrypt with passphrase
+        let option = gen_decrypt_passphrase_option(output, "1234".to_string(), Some(decrypt_output.clone()));
+        let result = gpg.decrypt(option);
+        assert_eq!(result.unwrap().is_success(), true);
+        assert_eq!(Path::new(&decrypt_output).exists(), true);
+
+        let mut decrypt_file: File = File::open(&decrypt_output).unwrap();
+        let mut buffer: Vec<u8> = Vec::new();
+        decrypt_file.read_to_end(&mut buffer).unwrap();
+        assert_eq!(String::from_utf8_lossy(&buffer), "testing decryption");
+
+        cleanup_after_tests(name);
+    }
+
+    #[cfg(not(feature = "test_legacy"))]
+    #[test]
+    fn test_decrypt_file_with_passphrase_or_key_fail(){
+        // test decrypting file with passphrase
+
+        let name:String  = generate_random_string();
+        let name: &str = name.as_str();
+
+        let gpg: GPG = get_gpg_init(name);
+        gen_protected_key(gpg.clone());
+
+        let mut file = tempfile().unwrap();
+        write!(file, "testing decryption").unwrap();
+        file.flush().unwrap();
+
+        let key_result: Vec<ListKeyResult> = list_keys(gpg.clone(), true, false);
+        let output: String = PathBuf::from(get_output_dir(name)).join("test_encrypt.txt").to_string_lossy().to_string();
+        let option = gen_encrypt_key_and_symmetric_option(file, vec![key_result[0].keyid.clone()],None, "1234".to_string(), Some(output.clone()));
+
+        let result: Result<CmdResult, GPGError> = gpg.encrypt(option);
+        assert_eq!(result.unwrap().is_success(), true);
+        assert_eq!(Path::new(&output).exists(), true);
+
+        let decrypt_output: String = PathBuf::from(get_output_dir(name)).join("test_decrypt.txt").to_string_lossy().to_string();
+        // decrypt with key, but didn't provide key passphrase
+        let option = gen_decrypt_default_option(output.clone(), key_result[0].keyid.clone(), None, Some(decrypt_output.clone()));
+        let result = gpg.decrypt(option);
+        assert!(matches!(result.unwrap_err().error_type, GPGErrorType::GPGProcessError(_)));
+        assert_eq!(Path::new(&decrypt_output).exists(), false);
+
+        // decrypt with passphrase, but wrong passphrase
+        let option = gen_decrypt_passphrase_option(output, "123".to_string(), Some(decrypt_output.clone()));
+        let result = gpg.decrypt(option);
+        assert!(matches!(result.unwrap_err().error_type, GPGErrorType::GPGProcessError(_)));
+        assert_eq!(Path::new(&decrypt_output).exists(), false);
+
+        cleanup_after_tests(name);
+    }
+
+    #[test]
+    fn test_decrypt_with_result(){
+        // test decrypting signed data and retrieving the metadata of the decryption
+
+        let name:String  = generate_random_string();
+        let name: &str = name.as_str();
+
+        let gpg: GPG = get_gpg_init(name);
+        gen_unprotected_key(gpg.clone());
+        let key_result: Vec<ListKeyResult> = list_keys(gpg.clone(), true, false);
+
+        let mut file = tempfile().unwrap();
+        write!(file, "testing decryption result").unwrap();
+        file.flush().unwrap();
+        let output: String = PathBuf::from(get_output_dir(name)).join("test_encrypt.txt").to_string_lossy().to_string();
+        let mut option: EncryptOption = gen_encrypt_default_option(file, vec![key_result[0].keyid.clone()], Some(output.clone()));
+        option.sign = true;
+        option.sign_key = Some(key_result[0].keyid.clone());
+        option.cipher_algo = Some(CipherAlgo::AES256);
+        option.extra_args = Some(vec!["--set-filename".to_string(), "plain.txt".to_string()]);
+        let result: Result<CmdResult, GPGError> = gpg.encrypt(option);
+        assert_eq!(result.unwrap().is_success(), true);
+
+        let decrypt_output: String = PathBuf::from(get_output_dir(name)).join("test_decrypt.txt").to_string_lossy().to_string();
+        let option = gen_decrypt_default_option(output, key_result[0].keyid.clone(), None, Some(decrypt_output.clone()));
+        let result: DecryptResult = gpg.decrypt_with_result(option).unwrap();
+        assert_eq!(result.recipients.len(), 1);
+        assert_eq!(result.symmetric, false);
+        assert_eq!(result.decryption_primary_key, Some(key_result[0].fingerprint.clone()));
+        assert_eq!(result.cipher_algo, Some(CipherAlgo::AES256));
+        assert_eq!(result.integrity_protected, true);
+        assert_eq!(result.filename, Some("plain.txt".to_string()));
+        assert_eq!(result.plaintext_length, Some("testing decryption result".len() as u64));
+        assert_eq!(result.completed, true);
+        assert_eq!(result.decrypted, true);
+        assert_eq!(result.signatures.len(), 1);
+        assert_eq!(result.signatures[0].status, "GOODSIG");
+        assert_eq!(result.signatures[0].primary_fingerprint, Some(key_result[0].fingerprint.clone()));
+        assert!(result.signatures[0].hash_algo.is_some());
+
+        cleanup_after_tests(name);
+    }
+
+    #[test]
+    fn test_decrypt_with_session_key(){
+        // test retrieving the session key of a file and decrypting it with the session key in another homedir
+
+        let name:String  = generate_random_string();
+        let name: &str = name.as_str();
+        let other_name:String  = generate_random_string();
+        let other_name: &str = other_name.as_str();
+
+        let gpg: GPG = get_gpg_init(name);
+        gen_unprotected_key(gpg.clone());
+        let key_result: Vec<ListKeyResult> = list_keys(gpg.clone(), true, false);
+
+        let mut file = tempfile().unwrap();
+        write!(file, "testing session key").unwrap();
+        file.flush().unwrap();
+        let output: String = PathBuf::from(get_output_dir(name)).join("test_encrypt.txt").to_string_lossy().to_string();
+        let option: EncryptOption = gen_encrypt_default_option(file, vec![key_result[0].keyid.clone()], Some(output.clone()));
+        let result: Result<CmdResult, GPGError> = gpg.encrypt(option);
+        assert_eq!(result.unwrap().is_success(), true);
+
+        let decrypt_output: String = PathBuf::from(get_output_dir(name)).join("test_decrypt.txt").to_string_lossy().to_string();
+        let mut option: DecryptOption = gen_decrypt_default_option(output.clone(), key_result[0].keyid.clone(), None, Some(decrypt_output));
+        option.show_session_key = true;
+        let result: DecryptResult = gpg.decrypt_with_result(option).unwrap();
+        let session_key: String = result.session_key.unwrap();
+
+        // the other homedir do not hold the secret key
+        let other_gpg: GPG = get_gpg_init(other_name);
+        let decrypt_output: String = PathBuf::from(get_output_dir(other_name)).join("test_decrypt.txt").to_string_lossy().to_string();
+        let mut option: DecryptOption = gen_decrypt_default_option(output.clone(), key_result[0].keyid.clone(), None, Some(decrypt_output.clone()));
+        option.recipient = None;
+        let result: Result<CmdResult, GPGError> = other_gpg.decrypt_with_session_key(option, session_key.clone());
+        assert_eq!(result.unwrap().is_success(), true);
+        assert_eq!(std::fs::read_to_string(&decrypt_output).unwrap(), "testing session key");
+
+        // a wrong session key should not decrypt the file
+        let (algo, key) = session_key.split_once(":").unwrap();
+        let wrong_key: String = format!("{}:{}", algo, key.chars().rev().collect::<String>());
+        let mut option: DecryptOption = gen_decrypt_default_option(output.clone(), key_result[0].keyid.clone(), None, None);
+        option.recipient = None;
+        let result: Result<CmdResult, GPGError> = other_gpg.decrypt_with_session_key(option, wrong_key);
+        assert!(matches!(result.unwrap_err().error_type, GPGErrorType::GPGProcessError(_)));
+
+        let mut option: DecryptOption = gen_decrypt_default_option(output, key_result[0].keyid.clone(), None, None);
+        option.recipient = None;
+        let result: Result<CmdResult, GPGError> = other_gpg.decrypt_with_session_key(option, format!("{}\n", session_key));
+        assert!(matches!(result.unwrap_err().error_type, GPGErrorType::InvalidArgumentError(_)));
+
+        cleanup_after_tests(name);
+        cleanup_after_tests(other_name);
+    }
+
+    #[test]
+    fn test_decrypt_with_result_symmetric_fail(){
+        // test retrieving the metadata of a failed symmetric decryption
+
+        let name:String  = generate_random_string();
+        let name: &str = name.as_str();
+
+        let gpg: GPG = get_gpg_init(name);
+        let mut file = tempfile().unwrap();
+        write!(file, "testing decryption result").unwrap();
+        file.flush().unwrap();
+        let output: String = PathBuf::from(get_output_dir(name)).join("test_encrypt.txt").to_string_lossy().to_string();
+        let option: EncryptOption = gen_encrypt_symmetric_option(file, None, "pass1234".to_string(), Some(output.clone()));
+        let result: Result<CmdResult, GPGError> = gpg.encrypt(option);
+        assert_eq!(result.unwrap().is_success(), true);
+
+        let decrypt_output: String = PathBuf::from(get_output_dir(name)).join("test_decrypt.txt").to_string_lossy().to_string();
+        let option = gen_decrypt_passphrase_option(output, "wrong_pass".to_string(), Some(decrypt_output.clone()));
+        let error: GPGError = gpg.decrypt_with_result(option).unwrap_err();
+        let result: DecryptResult = decode_decrypt_result(&error.cmd_result.unwrap());
+        assert_eq!(result.symmetric, true);
+        assert_eq!(result.recipients.len(), 0);
+        assert_eq!(result.decrypted, false);
+        assert_eq!(result.signatures.len(), 0);
+
+        cleanup_after_tests(name);
+    }
+
+    #[test]
+    fn test_encrypt_to_group(){
+        // test encrypting to a recipient group that expand to the fingerprints of its members
+
+        let name:String  = generate_random_string();
+        let name: &str = name.as_str();
+
+        let mut gpg: GPG = get_gpg_init(name);
+        gen_unprotected_key_with_email(gpg.clone(), "member1@test.com");
+        gen_unprotected_key_with_email(gpg.clone(), "member2@test.com");
+        gpg.add_group("ops-team".to_string(), vec!["member1@test.com".to_string(), "member2@test.com".to_string()]);
+
+        let group: GroupResult = gpg.expand_group("ops-team".to_string()).unwrap();
+        assert_eq!(group.fingerprints.len(), 2);
+        assert_eq!(group.missing.len(), 0);
+
+        let option: EncryptOption = EncryptOption::default(None, None, vec!["ops-team".to_string()], None);
+        let encrypted: Vec<u8> = gpg.encrypt_bytes(b"testing group", option).unwrap();
+        let info: MessageInfo = gpg.inspect_message_bytes(&encrypted).unwrap();
+        assert_eq!(info.recipients.len(), 2);
+
+        assert_eq!(gpg.remove_group("ops-team".to_string()).unwrap().len(), 2);
+        assert!(gpg.expand_group("ops-team".to_string()).is_err());
+
+        cleanup_after_tests(name);
+    }
+
+    #[test]
+    fn test_encrypt_to_group_unusable_member(){
+        // test encrypting to a recipient group with missing and expired member
+
+        let name:String  = generate_random_string();
+        let name: &str = name.as_str();
+
+        let mut gpg: GPG = get_gpg_init(name);
+        gen_unprotected_key_with_email(gpg.clone(), "member1@test.com");
+        // generate a key that already expired
+        let mut old_gpg: GPG = gpg.clone();
+        old_gpg.options = Some(vec!["--faked-system-time".to_string(), "20200101T000000!".to_string()]);
+        let mut args: HashMap<String, String> = HashMap::new();
+        args.insert("Name-Email".to_string(), "expired@test.com".to_string());
+        args.insert("Expire-Date".to_string(), "1d".to_string());
+        old_gpg.gen_key(None, Some(args)).unwrap();
+
+        gpg.add_group(
+            "ops-team".to_string(),
+            vec!["member1@test.com".to_string(), "expired@test.com".to_string(), "missing@test.com".to_string()]
+        );
+        let group: GroupResult = gpg.expand_group("ops-team".to_string()).unwrap();
+        assert_eq!(group.fingerprints.len(), 1);
+        assert_eq!(group.expired, vec!["expired@test.com".to_string()]);
+        assert_eq!(group.missing, vec!["missing@test.com".to_string()]);
+
+        let option: EncryptOption = EncryptOption::default(None, None, vec!["ops-team".to_string()], None);
+        let result: Result<Vec<u8>, GPGError> = gpg.encrypt_bytes(b"testing group", option);
+        assert!(matches!(result.unwrap_err().error_type, GPGErrorType::RecipientGroupError(_)));
+
+        cleanup_after_tests(name);
+    }
+
+    #[test]
+    fn test_group_member_exact_match(){
+        // test expanding group members that must match exactly 1 key
+
+        let name:String  = generate_random_string();
+        let name: &str = name.as_str();
+
+        let mut gpg: GPG = get_gpg_init(name);
+        gen_unprotected_key_with_email(gpg.clone(), "bob@test.com");
+        gen_unprotected_key_with_email(gpg.clone(), "jimbob@test.com");
+        let keys: Vec<ListKeyResult> = list_keys(gpg.clone(), false, false);
+        let bob: &ListKeyResult = keys.iter().find(|k| k.uids.iter().any(|u| u.contains("<bob@test.com>"))).unwrap();
+
+        // bob@test.com is a substring of jimbob@test.com, but only bob should be included
+        gpg.add_group("ops-team".to_string(), vec!["bob@test.com".to_string()]);
+        let group: GroupResult = gpg.expand_group("ops-team".to_string()).unwrap();
+        assert_eq!(group.fingerprints, vec![bob.fingerprint.clone()]);
+
+        // a member matching more than 1 key is ambiguous
+        gen_unprotected_key_with_email(gpg.clone(), "bob@test.com");
+        let result: Result<GroupResult, GPGError> = gpg.expand_group("ops-team".to_string());
+        assert!(matches!(result.unwrap_err().error_type, GPGErrorType::RecipientGroupError(_)));
+
+        // a failure of gpg is not reported as a missing member
+        let mut broken_gpg: GPG = gpg.clone();
+        broken_gpg.options = Some(vec!["--no-such-option".to_string()]);
+        broken_gpg.add_group("other-team".to_string(), vec!["missing@test.com".to_string()]);
+        let result: Result<GroupResult, GPGError> = broken_gpg.expand_group("other-team".to_string());
+        assert!(matches!(result.unwrap_err().error_type, GPGErrorType::GPGProcessError(_)));
+
+        cleanup_after_tests(name);
+    }
+
+    #[test]
+    fn test_group_from_gpg_conf(){
+        // test loading the recipient groups defined in gpg.conf
+
+        let name:String  = generate_random_string();
+        let name: &str = name.as_str();
+
+        let _ = get_gpg_init(name);
+        std::fs::write(PathBuf::from(get_homedir(name)).join("gpg.conf"), "group ops-team = member1@test.com member2@test.com\n").unwrap();
+        let gpg: GPG = get_gpg_init(name);
+        let mut members: Vec<String> = gpg.groups.get("ops-team").unwrap().clone();
+        members.sort();
+        assert_eq!(members, vec!["member1@test.com".to_string(), "member2@test.com".to_string()]);
+
+        cleanup_after_tests(name);
+    }
+
+    #[test]
+    fn test_inspect_message_with_key(){
+        // test inspecting a file encrypted with key and decrypting it with the option chosen from the inspection
+
+        let name:String  = generate_random_string();
+        let name: &str = name.as_str();
+
+        let gpg: GPG = get_gpg_init(name);
+        gen_unprotected_key(gpg.clone());
+        let key_result: Vec<ListKeyResult> = list_keys(gpg.clone(), true, false);
+
+        let mut file = tempfile().unwrap();
+        write!(file, "testing inspection").unwrap();
+        file.flush().unwrap();
+        let output: String = PathBuf::from(get_output_dir(name)).join("test_encrypt.txt").to_string_lossy().to_string();
+        let option: EncryptOption = gen_encrypt_default_option(file, vec![key_result[0].keyid.clone()], Some(output.clone()));
+        let result: Result<CmdResult, GPGError> = gpg.encrypt(option);
+        assert_eq!(result.unwrap().is_success(), true);
+
+        let info: MessageInfo = gpg.inspect_message(None, Some(output.clone())).unwrap();
+        assert_eq!(info.armored, gpg.armor);
+        assert_eq!(info.encrypted, true);
+        assert_eq!(info.symmetric, false);
+        assert_eq!(info.recipients.len(), 1);
+        assert_eq!(info.integrity_protected, true);
+        assert_eq!(info.key, false);
+        assert_eq!(info.packets[0].packet_type, PacketType::PublicKeyEncryptedSessionKey);
+
+        let decrypt_output: String = PathBuf::from(get_output_dir(name)).join("test_decrypt.txt").to_string_lossy().to_string();
+        let option: DecryptOption = DecryptOption::from_message(&info, None, Some(output), None, Some(decrypt_output.clone()));
+        assert_eq!(option.passphrase, None);
+        assert_eq!(option.recipient, Some(info.recipients[0].clone()));
+        let result: Result<CmdResult, GPGError> = gpg.decrypt(option);
+        assert_eq!(result.unwrap().is_success(), true);
+        assert_eq!(std::fs::read_to_string(decrypt_output).unwrap(), "testing inspection");
+
+        cleanup_after_tests(name);
+    }
+
+    #[test]
+    fn test_inspect_message_hidden_recipient(){
+        // test that a hidden recipient was not passed to the decryption option chosen from the inspection
+
+        let name:String  = generate_random_string();
+        let name: &str = name.as_str();
+
+        let gpg: GPG = get_gpg_init(name);
+        gen_unprotected_key(gpg.clone());
+        let key_result: Vec<ListKeyResult> = list_keys(gpg.clone(), true, false);
+
+        let mut file = tempfile().unwrap();
+        write!(file, "testing inspection").unwrap();
+        file.flush().unwrap();
+        let output: String = PathBuf::from(get_output_dir(name)).join("test_encrypt.txt").to_string_lossy().to_string();
+        let mut option: EncryptOption = gen_encrypt_default_option(file, vec![key_result[0].keyid.clone()], Some(output.clone()));
+        option.extra_args = Some(vec!["--throw-keyids".to_string()]);
+        let result: Result<CmdResult, GPGError> = gpg.encrypt(option);
+        assert_eq!(result.unwrap().is_success(), true);
+
+        let info: MessageInfo = gpg.inspect_message(None, Some(output.clone())).unwrap();
+        assert_eq!(info.recipients, vec!["0000000000000000".to_string()]);
+
+        let decrypt_output: String = PathBuf::from(get_output_dir(name)).join("test_decrypt.txt").to_string_lossy().to_string();
+        let option: DecryptOption = DecryptOption::from_message(&info, None, Some(output), None, Some(decrypt_output.clone()));
+        assert_eq!(option.recipient, None);
+        let result: Result<CmdResult, GPGError> = gpg.decrypt(option);
+        assert_eq!(result.unwrap().is_success(), true);
+        assert_eq!(std::fs::read_to_string(decrypt_output).unwrap(), "testing inspection");
+
+        cleanup_after_tests(name);
+    }
+
+    #[test]
+    fn test_inspect_message_read_fail(){
+        // test inspecting a file that could not be read
+
+        let name:String  = generate_random_string();
+        let name: &str = name.as_str();
+
+        let gpg: GPG = get_gpg_init(name);
+        // a file opened for writing only
+        let path: PathBuf = PathBuf::from(get_output_dir(name)).join("write_only.txt");
+        let file: File = File::create(&path).unwrap();
+        let result: Result<MessageInfo, GPGError> = gpg.inspect_message(Some(file), None);
+        assert!(matches!(result.unwrap_err().error_type, GPGErrorType::ReadFailError(_)));
+
+        cleanup_after_tests(name);
+    }
+
+    #[test]
+    fn test_inspect_message_symmetric(){
+        // test inspecting bytes encrypted symmetrically and decrypting it with the option chosen from the inspection
+
+        let name:String  = generate_random_string();
+        let name: &str = name.as_str();
+
+        let gpg: GPG = get_gpg_init(name);
+        let mut option: EncryptOption = EncryptOption::with_symmetric(None, None, None, "pass1234".to_string(), None);
+        option.cipher_algo = Some(CipherAlgo::AES256);
+        let encrypted: Vec<u8> = gpg.encrypt_bytes(b"testing inspection", option).unwrap();
+
+        let info: MessageInfo = gpg.inspect_message_bytes(&encrypted).unwrap();
+        assert_eq!(info.encrypted, true);
+        assert_eq!(info.symmetric, true);
+        assert_eq!(info.recipients.len(), 0);
+        assert_eq!(info.cipher_algo, Some(CipherAlgo::AES256));
+
+        let option: DecryptOption = DecryptOption::from_message(&info, None, None, Some("pass1234".to_string()), None);
+        assert_eq!(option.passphrase, Some("pass1234".to_string()));
+        let result: Result<Vec<u8>, GPGError> = gpg.decrypt_bytes(&encrypted, option);
+        assert_eq!(result.unwrap(), b"testing inspection".to_vec());
+
+        cleanup_after_tests(name);
+    }
+
+    #[test]
+    fn test_inspect_message_signature(){
+        // test inspecting clearsigned file and detached signature
+
+        let name:String  = generate_random_string();
+        let name: &str = name.as_str();
+
+        let gpg: GPG = get_gpg_init(name);
+        gen_unprotected_key(gpg.clone());
+        let key_result: Vec<ListKeyResult> = list_keys(gpg.clone(), true, false);
+
+        let mut file = tempfile().unwrap();
+        write!(file, "testing inspection").unwrap();
+        file.flush().unwrap();
+        let output: String = PathBuf::from(get_output_dir(name)).join("test_sign.txt").to_string_lossy().to_string();
+        let option: SignOption = gen_sign_default_option(file.try_clone().unwrap(), key_result[0].keyid.clone(), None, Some(output.clone()));
+        assert_eq!(gpg.sign(option).unwrap().is_success(), true);
+
+        let info: MessageInfo = gpg.inspect_message(None, Some(output)).unwrap();
+        assert_eq!(info.armored, true);
+        assert_eq!(info.clearsigned, true);
+        assert_eq!(info.signed, true);
+        assert_eq!(info.encrypted, false);
+        assert_eq!(info.detached_signature, false);
+
+        let output: String = PathBuf::from(get_output_dir(name)).join("test_sign.sig").to_string_lossy().to_string();
+        let option: SignOption = gen_sign_detached_option(file, key_result[0].keyid.clone(), None, Some(output.clone()));
+        assert_eq!(gpg.sign(option).unwrap().is_success(), true);
+
+        let info: MessageInfo = gpg.inspect_message(None, Some(output)).unwrap();
+        assert_eq!(info.signed, true);
+        assert_eq!(info.detached_signature, true);
+        assert_eq!(info.signers, vec![key_result[0].keyid.clone()]);
+        assert_eq!(info.packets.len(), 1);
+        assert_eq!(info.packets[0].packet_type, PacketType::Signature);
+
+        cleanup_after_tests(name);
+    }
+
+    #[test]
+    fn test_inspect_message_invalid(){
+        // test inspecting data that is not an OpenPGP message
+
+        let name:String  = generate_random_string();
+        let name: &str = name.as_str();
+
+        let gpg: GPG = get_gpg_init(name);
+        let result: Result<MessageInfo, GPGError> = gpg.inspect_message_bytes(b"not a message");
+        assert!(result.is_err());
+
+        cleanup_after_tests(name);
+    }
+
+    #[test]
+    fn test_encrypt_decrypt_directory_with_key(){
+        // test encrypting a directory into an archive, listing and extracting it
+
+        let name:String  = generate_random_string();
+        let name: &str = name.as_str();
+
+        let gpg: GPG = get_gpg_init(name);
+        gen_unprotected_key(gpg.clone());
+        let key_result: Vec<ListKeyResult> = list_keys(gpg.clone(), true, false);
+
+        let dir: PathBuf = PathBuf::from(get_output_dir(name)).join("test_dir");
+        std::fs::create_dir_all(dir.join("sub")).unwrap();
+        std::fs::write(dir.join("a.txt"), "testing directory a").unwrap();
+        std::fs::write(dir.join("sub").join("b.txt"), "testing directory b").unwrap();
+
+        let output: String = PathBuf::from(get_output_dir(name)).join("test_dir.gpg").to_string_lossy().to_string();
+        let option: EncryptOption = EncryptOption::default(None, None, vec![key_result[0].keyid.clone()], Some(output.clone()));
+        let result: Result<CmdResult, GPGError> = gpg.encrypt_directory(dir.to_string_lossy().to_string(), option);
+        assert_eq!(result.unwrap().is_success(), true);
+
+        let option: DecryptOption = DecryptOption::default(None, None, key_result[0].keyid.clone(), None, None);
+        let entries: Vec<ArchiveEntry> = gpg.list_archive(output.clone(), option).unwrap();
+        assert_eq!(entries.len(), 4);
+        assert!(entries.iter().any(|e| e.path == "test_dir/sub" && e.directory));
+        assert!(entries.iter().any(|e| e.path == "test_dir/sub/b.txt" && !e.directory && e.size == "testing directory b".len() as u64));
+
+        let dest_dir: PathBuf = PathBuf::from(get_output_dir(name)).join("extracted");
+        let option: DecryptOption = DecryptOption::default(None, None, key_result[0].keyid.clone(), None, None);
+        let result: Result<CmdResult, GPGError> = gpg.decrypt_archive(output, option, dest_dir.to_string_lossy().to_string());
+        assert_eq!(result.unwrap().is_success(), true);
+        assert_eq!(std::fs::read_to_string(dest_dir.join("test_dir").join("a.txt")).unwrap(), "testing directory a");
+        assert_eq!(std::fs::read_to_string(dest_dir.join("test_dir").join("sub").join("b.txt")).unwrap(), "testing directory b");
+
+        cleanup_after_tests(name);
+    }
+
+    #[test]
+    fn test_encrypt_decrypt_directory_with_passphrase(){
+        // test encrypting a directory symmetrically and failing to list it with wrong passphrase
+
+        let name:String  = generate_random_string();
+        let name: &str = name.as_str();
+
+        let gpg: GPG = get_gpg_init(name);
+        let dir: PathBuf = PathBuf::from(get_output_dir(name)).join("test_dir");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("a.txt"), "testing directory").unwrap();
+
+        let output: String = PathBuf::from(get_output_dir(name)).join("test_dir.gpg").to_string_lossy().to_string();
+        let option: EncryptOption = EncryptOption::with_symmetric(None, None, None, "pass1234".to_string(), Some(output.clone()));
+        let result: Result<CmdResult, GPGError> = gpg.encrypt_directory(dir.to_string_lossy().to_string(), option);
+        assert_eq!(result.unwrap().is_success(), true);
+
+        let option: DecryptOption = DecryptOption::with_symmetric(None, None, "wrong_pass".to_string(), None);
+        let result: Result<Vec<ArchiveEntry>, GPGError> = gpg.list_archive(output.clone(), option);
+        assert!(result.is_err());
+
+        let dest_dir: PathBuf = PathBuf::from(get_output_dir(name)).join("extracted");
+        let option: DecryptOption = DecryptOption::with_symmetric(None, None, "pass1234".to_string(), None);
+        let result: Result<CmdResult, GPGError> = gpg.decrypt_archive(output, option, dest_dir.to_string_lossy().to_string());
+        assert_eq!(result.unwrap().is_success(), true);
+        assert_eq!(std::fs::read_to_string(dest_dir.join("test_dir").join("a.txt")).unwrap(), "testing directory");
+
+        cleanup_after_tests(name);
+    }
+
+    #[test]
+    fn test_decrypt_directory_tampered(){
+        // test that nothing was left behind when the integrity check of the archive failed
+
+        let name:String  = generate_random_string();
+        let name: &str = name.as_str();
+
+        let gpg: GPG = get_gpg_init(name);
+        let dir: PathBuf = PathBuf::from(get_output_dir(name)).join("test_dir");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("a.txt"), "testing directory").unwrap();
+        // incompressible data so the tampered byte was reached after a.txt was written
+        let data: Vec<u8> = (0..1_000_000).map(|_| rand::random::<u8>()).collect();
+        std::fs::write(dir.join("b.bin"), data).unwrap();
+
+        let output: String = PathBuf::from(get_output_dir(name)).join("test_dir.gpg").to_string_lossy().to_string();
+        let option: EncryptOption = EncryptOption::with_symmetric(None, None, None, "pass1234".to_string(), Some(output.clone()));
+        let result: Result<CmdResult, GPGError> = gpg.encrypt_directory(dir.to_string_lossy().to_string(), option);
+        assert_eq!(result.unwrap().is_success(), true);
+
+        let mut archive: Vec<u8> = std::fs::read(&output).unwrap();
+        let index: usize = archive.len() - 100;
+        archive[index] ^= 0xff;
+        std::fs::write(&output, archive).unwrap();
+
+        let extract_dir: PathBuf = PathBuf::from(get_output_dir(name)).join("extract");
+        std::fs::create_dir_all(&extract_dir).unwrap();
+        let dest_dir: PathBuf = extract_dir.join("extracted");
+        let option: DecryptOption = DecryptOption::with_symmetric(None, None, "pass1234".to_string(), None);
+        let result: Result<CmdResult, GPGError> = gpg.decrypt_archive(output, option, dest_dir.to_string_lossy().to_string());
+        assert!(result.is_err());
+        assert_eq!(dest_dir.exists(), false);
+        assert_eq!(std::fs::read_dir(&extract_dir).unwrap().count(), 0);
+
+        cleanup_after_tests(name);
+    }
+
+    #[test]
+    fn test_decrypt_directory_existing_entry(){
+        // test extracting into a directory that already hold an entry of the archive
+
+        let name:String  = generate_random_string();
+        let name: &str = name.as_str();
+
+        let gpg: GPG = get_gpg_init(name);
+        let dir: PathBuf = PathBuf::from(get_output_dir(name)).join("test_dir");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("a.txt"), "testing directory").unwrap();
+
+        let output: String = PathBuf::from(get_output_dir(name)).join("test_dir.gpg").to_string_lossy().to_string();
+        let option: EncryptOption = EncryptOption::with_symmetric(None, None, None, "pass1234".to_string(), Some(output.clone()));
+        let result: Result<CmdResult, GPGError> = gpg.encrypt_directory(dir.to_string_lossy().to_string(), option);
+        assert_eq!(result.unwrap().is_success(), true);
+
+        // the entries were moved into an existing directory
+        let dest_dir: PathBuf = PathBuf::from(get_output_dir(name)).join("extracted");
+        std::fs::create_dir_all(&dest_dir).unwrap();
+        std::fs::write(dest_dir.join("other.txt"), "other").unwrap();
+        let option: DecryptOption = DecryptOption::with_symmetric(None, None, "pass1234".to_string(), None);
+        let result: Result<CmdResult, GPGError> = gpg.decrypt_archive(output.clone(), option, dest_dir.to_string_lossy().to_string());
+        assert_eq!(result.unwrap().is_success(), true);
+        assert_eq!(std::fs::read_to_string(dest_dir.join("test_dir").join("a.txt")).unwrap(), "testing directory");
+
+        // but an entry that already exist will not be overwritten
+        std::fs::write(dest_dir.join("test_dir").join("a.txt"), "changed").unwrap();
+        let option: DecryptOption = DecryptOption::with_symmetric(None, None, "pass1234".to_string(), None);
+        let result: Result<CmdResult, GPGError> = gpg.decrypt_archive(output, option, dest_dir.to_string_lossy().to_string());
+        assert!(matches!(result.unwrap_err().error_type, GPGErrorType::OutputDirError(_)));
+        assert_eq!(std::fs::read_to_string(dest_dir.join("test_dir").join("a.txt")).unwrap(), "changed");
+        assert_eq!(std::fs::read_dir(PathBuf::from(get_output_dir(name))).unwrap().filter(|e| e.as_ref().unwrap().file_name().to_string_lossy().contains(".partial_")).count(), 0);
+
+        cleanup_after_tests(name);
+    }
+
+    #[test]
+    fn test_encrypt_directory_not_found(){
+        // test encrypting a directory that do not exist
+
+        let name:String  = generate_random_string();
+        let name: &str = name.as_str();
+
+        let gpg: GPG = get_gpg_init(name);
+        let dir: PathBuf = PathBuf::from(get_output_dir(name)).join("not_exist");
+        let option: EncryptOption = EncryptOption::with_symmetric(None, None, None, "pass1234".to_string(), None);
+        let result: Result<CmdResult, GPGError> = gpg.encrypt_directory(dir.to_string_lossy().to_string(), option);
+        assert!(matches!(result.unwrap_err().error_type, GPGErrorType::FileNotFoundError(_)));
+
+        cleanup_after_tests(name);
+    }
+
+    #[test]
+    fn test_reencrypt_keep_signature(){
+        // test re-encrypting a signed file to another key and keeping the signature and filename
+
+        let name:String  = generate_random_string();
+        let name: &str = name.as_str();
+
+        let gpg: GPG = get_gpg_init(name);
+        gen_unprotected_key_with_email(gpg.clone(), "old@example.com");
+        let old_key: ListKeyResult = list_keys(gpg.clone(), true, false)[0].clone();
+        gen_unprotected_key_with_email(gpg.clone(), "new@example.com");
+        let new_key: ListKeyResult = list_keys(gpg.clone(), true, false).into_iter().find(|k| k.fingerprint != old_key.fingerprint).unwrap();
+
+        let mut file = tempfile().unwrap();
+        write!(file, "testing re-encryption").unwrap();
+        file.flush().unwrap();
+        let output: String = PathBuf::from(get_output_dir(name)).join("test_encrypt.txt").to_string_lossy().to_string();
+        let mut option: EncryptOption = gen_encrypt_default_option(file, vec![old_key.keyid.clone()], Some(output.clone()));
+        option.sign = true;
+        option.sign_key = Some(old_key.keyid.clone());
+        option.extra_args = Some(vec!["--set-filename".to_string(), "plain.txt".to_string()]);
+        let result: Result<CmdResult, GPGError> = gpg.encrypt(option);
+        assert_eq!(result.unwrap().is_success(), true);
+
+        let reencrypt_output: String = PathBuf::from(get_output_dir(name)).join("test_reencrypt.txt").to_string_lossy().to_string();
+        let option: DecryptOption = DecryptOption::default(None, None, old_key.keyid.clone(), None, None);
+        let result: Result<CmdResult, GPGError> = gpg.reencrypt(output, option, vec![new_key.keyid.clone()], Some(reencrypt_output.clone()));
+        assert_eq!(result.unwrap().is_success(), true);
+
+        let info: MessageInfo = gpg.inspect_message(None, Some(reencrypt_output.clone())).unwrap();
+        assert_eq!(info.armored, true);
+        assert_eq!(info.recipients.len(), 1);
+
+        let decrypt_output: String = PathBuf::from(get_output_dir(name)).join("test_decrypt.txt").to_string_lossy().to_string();
+        let option = gen_decrypt_default_option(reencrypt_output, new_key.keyid.clone(), None, Some(decrypt_output.clone()));
+        let result: DecryptResult = gpg.decrypt_with_result(option).unwrap();
+        assert_eq!(result.filename, Some("plain.txt".to_string()));
+        assert_eq!(result.signatures.len(), 1);
+        assert_eq!(result.signatures[0].status, "GOODSIG");
+        assert_eq!(result.signatures[0].primary_fingerprint, Some(old_key.fingerprint.clone()));
+        assert_eq!(std::fs::read_to_string(&decrypt_output).unwrap(), "testing re-encryption");
+
+        cleanup_after_tests(name);
+    }
+
+    #[test]
+    fn test_reencrypt_in_place_binary(){
+        // test re-encrypting a binary file in place and keeping it binary
+
+        let name:String  = generate_random_string();
+        let name: &str = name.as_str();
+
+        let gpg: GPG = get_gpg_init(name);
+        gen_unprotected_key(gpg.clone());
+        let key_result: Vec<ListKeyResult> = list_keys(gpg.clone(), true, false);
+
+        let mut file = tempfile().unwrap();
+        write!(file, "testing binary re-encryption").unwrap();
+        file.flush().unwrap();
+        let output: String = PathBuf::from(get_output_dir(name)).join("test_encrypt.gpg").to_string_lossy().to_string();
+        let mut gpg_binary: GPG = gpg.clone();
+        gpg_binary.armor = false;
+        let option: EncryptOption = EncryptOption::with_symmetric(Some(file), None, None, "pass1234".to_string(), Some(output.clone()));
+        let result: Result<CmdResult, GPGError> = gpg_binary.encrypt(option);
+        assert_eq!(result.unwrap().is_success(), true);
+
+        let option: DecryptOption = DecryptOption::with_symmetric(None, None, "pass1234".to_string(), None);
+        let result: Result<CmdResult, GPGError> = gpg.reencrypt(output.clone(), option, vec![key_result[0].keyid.clone()], Some(output.clone()));
+        assert_eq!(result.unwrap().is_success(), true);
+        assert!(!std::fs::read(&output).unwrap().starts_with(b"-----BEGIN PGP"));
+        assert_eq!(Path::new(&format!("{}.reencrypt", output)).exists(), false);
+
+        let decrypt_output: String = PathBuf::from(get_output_dir(name)).join("test_decrypt.txt").to_string_lossy().to_string();
+        let option = gen_decrypt_default_option(output, key_result[0].keyid.clone(), None, Some(decrypt_output.clone()));
+        let result: Result<CmdResult, GPGError> = gpg.decrypt(option);
+        assert_eq!(result.unwrap().is_success(), true);
+        assert_eq!(std::fs::read_to_string(&decrypt_output).unwrap(), "testing binary re-encryption");
+
+        cleanup_after_tests(name);
+    }
+
+    #[test]
+    fn test_reencrypt_directory(){
+        // test re-encrypting every file of a directory and reporting the file that failed
+
+        let name:String  = generate_random_string();
+        let name: &str = name.as_str();
+
+        let gpg: GPG = get_gpg_init(name);
+        gen_unprotected_key(gpg.clone());
+        let key_result: Vec<ListKeyResult> = list_keys(gpg.clone(), true, false);
+
+        let dir: PathBuf = PathBuf::from(get_output_dir(name)).join("encrypted");
+        std::fs::create_dir_all(dir.join("sub")).unwrap();
+        for (path, content) in [("a.gpg", "testing directory a"), ("sub/b.gpg", "testing directory b")] {
+            let mut file = tempfile().unwrap();
+            write!(file, "{}", content).unwrap();
+            file.flush().unwrap();
+            let option: EncryptOption = EncryptOption::with_symmetric(Some(file), None, None, "pass1234".to_string(), Some(dir.join(path).to_string_lossy().to_string()));
+            let result: Result<CmdResult, GPGError> = gpg.encrypt(option);
+            assert_eq!(result.unwrap().is_success(), true);
+        }
+        std::fs::write(dir.join("not_encrypted.txt"), "plain").unwrap();
+
+        let output_dir: PathBuf = PathBuf::from(get_output_dir(name)).join("reencrypted");
+        let option: DecryptOption = DecryptOption::with_symmetric(None, None, "pass1234".to_string(), None);
+        let results: Vec<ReencryptResult> = gpg.reencrypt_directory(dir.to_string_lossy().to_string(), option, vec![key_result[0].keyid.clone()], output_dir.to_string_lossy().to_string()).unwrap();
+        assert_eq!(results.len(), 3);
+        assert_eq!(results.iter().filter(|r| r.success).count(), 2);
+        assert!(results.iter().any(|r| r.input.ends_with("not_encrypted.txt") && !r.success && r.error.is_some()));
+        assert_eq!(output_dir.join("not_encrypted.txt").exists(), false);
+
+        let decrypt_output: String = PathBuf::from(get_output_dir(name)).join("test_decrypt.txt").to_string_lossy().to_string();
+        let option = gen_decrypt_default_option(output_dir.join("sub").join("b.gpg").to_string_lossy().to_string(), key_result[0].keyid.clone(), None, Some(decrypt_output.clone()));
+        let result: Result<CmdResult, GPGError> = gpg.decrypt(option);
+        assert_eq!(result.unwrap().is_success(), true);
+        assert_eq!(std::fs::read_to_string(&decrypt_output).unwrap(), "testing directory b");
+
+        cleanup_after_tests(name);
+    }
+
+    #[cfg(not(feature = "test_legacy"))]
+    #[test]
+    fn test_encrypt_decrypt_bytes_with_key(){
+        // test encrypting and decrypting bytes in memory with key
+
+        let name:String  = generate_random_string();
+        let name: &str = name.as_str();
+
+        let gpg: GPG = get_gpg_init(name);
+        gen_protected_key(gpg.clone());
+
+        let key_result: Vec<ListKeyResult> = list_keys(gpg.clone(), true, false);
+        let option: EncryptOption = EncryptOption::default(None, None, vec![key_result[0].keyid.clone()], None);
+        let result: Result<Vec<u8>, GPGError> = gpg.encrypt_bytes(b"testing bytes encryption", option);
+        let encrypted: Vec<u8> = result.unwrap();
+        assert!(encrypted.len() > 0);
+        assert_ne!(encrypted, b"testing bytes encryption".to_vec());
+
+        let option: DecryptOption = DecryptOption::default(None, None, key_result[0].keyid.clone(), Some(get_key_passphrass()), None);
+        let result: Result<Vec<u8>, GPGError> = gpg.decrypt_bytes(&encrypted, option);
+        assert_eq!(result.unwrap(), b"testing bytes encryption".to_vec());
+        // nothing should be written to the output dir
+        assert_eq!(std::fs::read_dir(get_output_dir(name)).unwrap().count(), 0);
+
+        cleanup_after_tests(name);
+    }
+
+    #[test]
+    fn test_encrypt_decrypt_bytes_with_passphrase(){
+        // test encrypting and decrypting bytes in memory with passphrase
+
+        let name:String  = generate_random_string();
+        let name: &str = name.as_str();
+
+        let gpg: GPG = get_gpg_init(name);
+
+        let data: Vec<u8> = vec![0, 159, 146, 150, 255, 10, 13, 0];
+        let option: EncryptOption = EncryptOption::with_symmetric(None, None, None, "pass1234".to_string(), None);
+        let result: Result<Vec<u8>, GPGError> = gpg.encrypt_bytes(&data, option);
+        let encrypted: Vec<u8> = result.unwrap();
+
+        let option: DecryptOption = DecryptOption::with_symmetric(None, None, "pass1234".to_string(), None);
+        let result: Result<Vec<u8>, GPGError> = gpg.decrypt_bytes(&encrypted, option);
+        assert_eq!(result.unwrap(), data);
+
+        let option: DecryptOption = DecryptOption::with_symmetric(None, None, "wrong_pass".to_string(), None);
+        let result: Result<Vec<u8>, GPGError> = gpg.decrypt_bytes(&encrypted, option);
+        assert!(matches!(result.unwrap_err().error_type, GPGErrorType::GPGProcessError(_)));
+
+        cleanup_after_tests(name);
+    }
+
+    #[cfg(not(feature = "test_legacy"))]
+    #[test]
+    fn test_decrypt_bytes_with_provider(){
+        // test decrypting bytes encrypted for 2 passphrase protected keys with a passphrase provider
+        // that only know the passphrase of the second key
+
+        let name:String  = generate_random_string();
+        let name: &str = name.as_str();
+
+        let gpg: GPG = get_gpg_init(name);
+        let _ = gpg.gen_key(Some("passphrase_a".to_string()), None);
+        let _ = gpg.gen_key(Some("passphrase_b".to_string()), None);
+        let key_list: Vec<ListKeyResult> = list_keys(gpg.clone(), true, false);
+        // the second key will be decrypted with its encryption subkey
+        let _ = gpg.add_subkey(key_list[1].fingerprint.clone(), Some("passphrase_b".to_string()), "rsa".to_string(), "encrypt".to_string(), "-".to_string());
+        let key_list: Vec<ListKeyResult> = list_keys(gpg.clone(), true, false);
+        assert_eq!(key_list[1].subkeys.len(), 1);
+        let recipients: Vec<String> = key_list.iter().map(|k| k.keyid.clone()).collect();
+
+        let option: EncryptOption = EncryptOption::default(None, None, recipients, None);
+        let encrypted: Vec<u8> = gpg.encrypt_bytes(b"testing provider", option).unwrap();
+
+        let known_key: String = key_list[1].keyid.clone();
+        let requests: Arc<Mutex<Vec<PassphraseRequest>>> = Arc::new(Mutex::new(vec![]));
+        let requests_clone: Arc<Mutex<Vec<PassphraseRequest>>> = Arc::clone(&requests);
+        let provider: Arc<dyn PassphraseProvider> = Arc::new(move |request: &PassphraseRequest| {
+            requests_clone.lock().unwrap().push(request.clone());
+            if request.main_keyid == known_key && request.attempt == 1 {
+                return Some("passphrase_b".to_string());
+            }
+            return None;
+        });
+        let option: DecryptOption = DecryptOption::with_provider(None, None, provider, None);
+        let result: Result<Vec<u8>, GPGError> = gpg.decrypt_bytes(&encrypted, option);
+        assert_eq!(result.unwrap(), b"testing provider".to_vec());
+
+        // the keygrip of the primary key or the subkey prompted should be passed along to the provider
+        let requests: Vec<PassphraseRequest> = requests.lock().unwrap().clone();
+        let subkey_request: &PassphraseRequest = requests.iter().find(|r| r.keyid != r.main_keyid).unwrap();
+        assert_eq!(subkey_request.main_keyid, key_list[1].keyid);
+        assert_eq!(subkey_request.keyid, key_list[1].subkeys[0].keyid);
+        assert_eq!(subkey_request.keygrip, Some(key_list[1].subkeys[0].keygrip.clone()));
+        for request in requests.iter().filter(|r| r.keyid == r.main_keyid) {
+            let key: &ListKeyResult = key_list.iter().find(|k| k.keyid == request.keyid).unwrap();
+            assert_eq!(request.keygrip, Some(key.keygrip.clone()));
+        }
+
+        cleanup_after_tests(name);
+    }
+
+    #[test]
+    fn test_decrypt_skipped_passphrase_other_failure(){
+        // test that a decryption with one of the keys only recover the failure of a key skipped due to its passphrase,
+        // any other failure reported before the decryption was still a failure
+
+        let mut result: CmdResult = CmdResult::init(Operation::Decrypt);
+        result.handle_status("BAD_PASSPHRASE", "bad passphrase".to_string());
+        result.handle_status("DECRYPTION_OKAY", "".to_string());
+        result.set_return_code(0);
+        assert_eq!(result.is_success(), true);
+        assert!(result.problem.is_none());
+
+        let mut result: CmdResult = CmdResult::init(Operation::Decrypt);
+        result.handle_status("BAD_PASSPHRASE", "bad passphrase".to_string());
+        result.handle_status("FAILURE", "decrypt 33554443".to_string());
+        result.handle_status("DECRYPTION_OKAY", "".to_string());
+        result.set_return_code(0);
+        assert_eq!(result.is_success(), false);
+
+        let mut result: CmdResult = CmdResult::init(Operation::Decrypt);
+        result.handle_status("BAD_PASSPHRASE", "bad passphrase".to_string());
+        result.set_return_code(2);
+        assert_eq!(result.is_success(), false);
+        assert_eq!(result.success, false);
+    }
+
+    #[test]
+    fn test_decrypt_bytes_corrupted_armor(){
+        // test decrypting bytes whose armor checksum was corrupted, gpg only report it through its exit code
+
+        let name:String  = generate_random_string();
+        let name: &str = name.as_str();
+
+        let gpg: GPG = get_gpg_init(name);
+
+        let mut option: EncryptOption = EncryptOption::with_symmetric(None, None, None, "pass1234".to_string(), None);
+        option.armor = Some(true);
+        let encrypted: Vec<u8> = gpg.encrypt_bytes(b"testing corrupted armor", option).unwrap();
+
+        // flip one character of the checksum line ( "=XXXX" ) right before the armor footer
+        let mut corrupted: Vec<u8> = encrypted;
+        let index: usize = corrupted.windows(2).rposition(|w| w == b"\n=").unwrap() + 2;
+        corrupted[index] = if corrupted[index] == b'A' { b'B' } else { b'A' };
+
+        let option: DecryptOption = DecryptOption::with_symmetric(None, None, "pass1234".to_string(), None);
+        let result: Result<Vec<u8>, GPGError> = gpg.decrypt_bytes(&corrupted, option);
+        assert!(result.is_err());
+
+        cleanup_after_tests(name);
+    }
+
+    #[test]
+    fn test_encrypt_decrypt_stream(){
+        // test streaming encryption and decryption from reader into writer
+
+        let name:String  = generate_random_string();
+        let name: &str = name.as_str();
+
+        let gpg: GPG = get_gpg_init(name);
+        gen_unprotected_key(gpg.clone());
+
+        let data: Vec<u8> = (0..1024 * 1024).map(|x| (x % 251) as u8).collect();
+        let key_result: Vec<ListKeyResult> = list_keys(gpg.clone(), true, false);
+        let option: EncryptOption = EncryptOption::default(None, None, vec![key_result[0].keyid.clone()], None);
+        let mut encrypted: Vec<u8> = Vec::new();
+        let result: Result<CmdResult, GPGError> = gpg.encrypt_stream(Cursor::new(data.clone()), &mut encrypted, option);
+        assert_eq!(result.unwrap().is_success(), true);
+
+        let option: DecryptOption = DecryptOption::default(None, None, key_result[0].keyid.clone(), None, None);
+        let mut decrypted: Vec<u8> = Vec::new();
+        let result: Result<CmdResult, GPGError> = gpg.decrypt_stream(Cursor::new(encrypted), &mut decrypted, option);
+        assert_eq!(result.unwrap().is_success(), true);
+        assert_eq!(decrypted, data);
+
+        cleanup_after_tests(name);
+    }
+
+    #[test]
+    fn test_decrypt_stream_corrupted_armor(){
+        // test streaming decryption from a borrowed reader of data whose armor checksum was corrupted,
+        // gpg only report it through its exit code
+
+        let name:String  = generate_random_string();
+        let name: &str = name.as_str();
+
+        let gpg: GPG = get_gpg_init(name);
+
+        let data: Vec<u8> = b"testing corrupted armor".to_vec();
+        let mut option: EncryptOption = EncryptOption::with_symmetric(None, None, None, "pass1234".to_string(), None);
+        option.armor = Some(true);
+        let mut encrypted: Vec<u8> = Vec::new();
+        let result: Result<CmdResult, GPGError> = gpg.encrypt_stream(data.as_slice(), &mut encrypted, option);
+        assert_eq!(result.unwrap().is_success(), true);
+
+        // flip one character of the checksum line ( "=XXXX" ) right before the armor footer
+        let index: usize = encrypted.windows(2).rposition(|w| w == b"\n=").unwrap() + 2;
+        encrypted[index] = if encrypted[index] == b'A' { b'B' } else { b'A' };
+
+        let option: DecryptOption = DecryptOption::with_symmetric(None, None, "pass1234".to_string(), None);
+        let mut decrypted: Vec<u8> = Vec::new();
+        let result: Result<CmdResult, GPGError> = gpg.decrypt_stream(&encrypted[..], &mut decrypted, option);
+        assert!(matches!(result.unwrap_err().error_type, GPGErrorType::GPGProcessError(_)));
+
+        cleanup_after_tests(name);
+    }
+
+    #[test]
+    fn test_encrypt_stream_input_fail(){
+        // test streaming encryption from a reader that fail in the middle
+
+        struct FailingReader { sent: bool }
+        impl Read for FailingReader {
+            fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+                if self.sent {
+                    return Err(std::io::Error::new(std::io::ErrorKind::Other, "connection reset"));
+                }
+                self.sent = true;
+                buf[0] = b'a';
+                return Ok(1);
+            }
+        }
+
+        let name:String  = generate_random_string();
+        let name: &str = name.as_str();
+
+        let gpg: GPG = get_gpg_init(name);
+
+        let option: EncryptOption = EncryptOption::with_symmetric(None, None, None, "pass1234".to_string(), None);
+        let mut encrypted: Vec<u8> = Vec::new();
+        let result: Result<CmdResult, GPGError> = gpg.encrypt_stream(FailingReader { sent: false }, &mut encrypted, option);
+        assert!(matches!(result.unwrap_err().error_type, GPGErrorType::GPGProcessError(_)));
+
+        cleanup_after_tests(name);
+    }
+
+    #[test]
+    fn test_encrypt_writer_decrypt_reader(){
+        // test encrypting through GpgEncryptWriter and decrypting through GpgDecryptReader
+
+        let name:String  = generate_random_string();
+        let name: &str = name.as_str();
+
+        let gpg: GPG = get_gpg_init(name);
+
+        let option: EncryptOption = EncryptOption::with_symmetric(None, None, None, "pass1234".to_string(), None);
+        let mut writer: GpgEncryptWriter<Vec<u8>> = gpg.encrypt_writer(Vec::new(), option).unwrap();
+        for _ in 0..100 {
+            writer.write_all(b"testing stream encryption\n").unwrap();
+        }
+        let (encrypted, result) = writer.finish().unwrap();
+        assert_eq!(result.is_success(), true);
+
+        let option: DecryptOption = DecryptOption::with_symmetric(None, None, "pass1234".to_string(), None);
+        let mut reader: GpgDecryptReader = gpg.decrypt_reader(Cursor::new(encrypted.clone()), option).unwrap();
+        let mut decrypted: String = String::new();
+        reader.read_to_string(&mut decrypted).unwrap();
+        assert_eq!(decrypted, "testing stream encryption\n".repeat(100));
+        let result: DecryptResult = reader.finish().unwrap();
+        assert_eq!(result.symmetric, true);
+        assert_eq!(result.integrity_protected, true);
+
+        // decrypting with a wrong passphrase should end the reader with an error
+        let option: DecryptOption = DecryptOption::with_symmetric(None, None, "wrong_pass".to_string(), None);
+        let mut reader: GpgDecryptReader = gpg.decrypt_reader(Cursor::new(encrypted.clone()), option).unwrap();
+        let mut decrypted: Vec<u8> = Vec::new();
+        assert_eq!(reader.read_to_end(&mut decrypted).is_err(), true);
+
+        // a corrupted armor was reported by gpg without any status
+        let mut corrupted: Vec<u8> = encrypted;
+        let index: usize = corrupted.len() / 2;
+        corrupted[index] = if corrupted[index] == b'A' { b'B' } else { b'A' };
+        let option: DecryptOption = DecryptOption::with_symmetric(None, None, "pass1234".to_string(), None);
+        let reader: GpgDecryptReader = gpg.decrypt_reader(Cursor::new(corrupted), option).unwrap();
+        assert!(reader.finish().is_err());
+
+        // a tampered message ( failing the MDC at the end ) was only reported once the data was fully read
+        let mut binary_gpg: GPG = gpg.clone();
+        binary_gpg.armor = false;
+        let option: EncryptOption = EncryptOption::with_symmetric(None, None, None, "pass1234".to_string(), None);
+        let mut writer: GpgEncryptWriter<Vec<u8>> = binary_gpg.encrypt_writer(Vec::new(), option).unwrap();
+        writer.write_all(b"testing stream encryption\n").unwrap();
+        let (mut tampered, _) = writer.finish().unwrap();
+        let index: usize = tampered.len() - 5;
+        tampered[index] ^= 0xff;
+        let option: DecryptOption = DecryptOption::with_symmetric(None, None, "pass1234".to_string(), None);
+        let reader: GpgDecryptReader = gpg.decrypt_reader(Cursor::new(tampered), option).unwrap();
+        assert!(reader.finish().is_err());
 
         cleanup_after_tests(name);
     }
@@ -1401,6 +3432,129 @@ mod tests {
         cleanup_after_tests(name);
     }
 
+    #[test]
+    fn test_sign_file_armor_and_textmode_override(){
+        // test signing file with binary detached signature in text mode while gpg was set to armor
+
+        let name:String  = generate_random_string();
+        let name: &str = name.as_str();
+
+        let gpg: GPG = get_gpg_init(name);
+        gen_unprotected_key(gpg.clone());
+
+        let mut file = tempfile().unwrap();
+        write!(file, "testing signing\r\nin text mode\n").unwrap();
+        file.flush().unwrap();
+
+        let key_result: Vec<ListKeyResult> = list_keys(gpg.clone(), true, false);
+        let mut option: SignOption = gen_sign_detached_option(file, key_result[0].keyid.clone(), None, None);
+        option.armor = Some(false);
+        option.textmode = true;
+
+        let result: Result<CmdResult, GPGError> = gpg.sign(option);
+        assert_eq!(result.unwrap().is_success(), true);
+        let signatures: Vec<PathBuf> = std::fs::read_dir(get_output_dir(name)).unwrap().map(|e| e.unwrap().path()).collect();
+        assert_eq!(signatures.len(), 1);
+        assert_eq!(signatures[0].extension().unwrap(), "sig");
+
+        let info: MessageInfo = gpg.inspect_message(None, Some(signatures[0].to_string_lossy().to_string())).unwrap();
+        assert_eq!(info.armored, false);
+        assert_eq!(info.packets[0].packet_type, PacketType::Signature);
+        // signature class 0x01 is a signature of a canonical text document
+        assert!(info.packets[0].details.iter().any(|d| d.contains("sigclass 0x01")));
+
+        cleanup_after_tests(name);
+    }
+
+    #[test]
+    fn test_sign_file_modes(){
+        // test signing file with each sign mode, digest algorithm and signature expiry
+
+        let name:String  = generate_random_string();
+        let name: &str = name.as_str();
+
+        let gpg: GPG = get_gpg_init(name);
+        gen_unprotected_key(gpg.clone());
+        let key_result: Vec<ListKeyResult> = list_keys(gpg.clone(), true, false);
+
+        let modes: Vec<(SignMode, &str, &str, &str)> = vec![
+            (SignMode::Inline, "S", "00", "embedded_sign_"),
+            (SignMode::Clear, "C", "01", "clear_sign_"),
+            (SignMode::Detached, "D", "00", "detach_sign_"),
+            (SignMode::DetachedText, "D", "01", "detach_text_sign_"),
+        ];
+        for (mode, sig_type, sig_class, prefix) in modes {
+            let mut file = tempfile().unwrap();
+            write!(file, "testing sign mode").unwrap();
+            file.flush().unwrap();
+            let mut option: SignOption = gen_sign_detached_option(file, key_result[0].keyid.clone(), None, None);
+            option.mode = mode.clone();
+            option.armor = Some(false);
+            option.digest_algo = Some(DigestAlgo::SHA512);
+            option.sig_expire = Some("2d".to_string());
+            let result: Vec<SignResult> = gpg.sign_with_result(option).unwrap();
+            assert_eq!(result.len(), 1);
+            assert_eq!(result[0].sig_type, sig_type);
+            assert_eq!(result[0].sig_class, sig_class);
+            assert_eq!(result[0].hash_algo, Some(DigestAlgo::SHA512));
+
+            let output: Vec<PathBuf> = std::fs::read_dir(get_output_dir(name)).unwrap()
+                .map(|e| e.unwrap().path())
+                .filter(|p| p.file_name().unwrap().to_string_lossy().starts_with(prefix))
+                .collect();
+            assert_eq!(output.len(), 1);
+            let extension: &str = match mode {
+                SignMode::Inline => "gpg",
+                SignMode::Clear => "asc",
+                SignMode::Detached | SignMode::DetachedText => "sig",
+            };
+            assert_eq!(output[0].extension().unwrap(), extension);
+
+            if mode == SignMode::Inline || mode == SignMode::Clear {
+                let result: Vec<VerifyResult> = gpg.verify_file_with_result(None, Some(output[0].to_string_lossy().to_string()), None, None).unwrap();
+                assert_eq!(result[0].status, "GOODSIG");
+                assert!(result[0].expire_timestamp.is_some());
+            }
+        }
+
+        cleanup_after_tests(name);
+    }
+
+    #[test]
+    fn test_encrypt_armor_and_textmode_override(){
+        // test encrypting with the armor override in both direction
+
+        let name:String  = generate_random_string();
+        let name: &str = name.as_str();
+
+        let gpg: GPG = get_gpg_init(name);
+        let mut option: EncryptOption = EncryptOption::with_symmetric(None, None, None, "pass1234".to_string(), None);
+        option.armor = Some(false);
+        option.textmode = true;
+        let encrypted: Vec<u8> = gpg.encrypt_bytes(b"testing armor override", option).unwrap();
+        assert!(!encrypted.starts_with(b"-----BEGIN PGP"));
+
+        let mut binary_gpg: GPG = gpg.clone();
+        binary_gpg.armor = false;
+        let mut file = tempfile().unwrap();
+        write!(file, "testing armor override").unwrap();
+        file.flush().unwrap();
+        let mut option: EncryptOption = EncryptOption::with_symmetric(Some(file), None, None, "pass1234".to_string(), None);
+        option.armor = Some(true);
+        let result: Result<CmdResult, GPGError> = binary_gpg.encrypt(option);
+        assert_eq!(result.unwrap().is_success(), true);
+        let encrypted: Vec<PathBuf> = std::fs::read_dir(get_output_dir(name)).unwrap().map(|e| e.unwrap().path()).collect();
+        assert_eq!(encrypted.len(), 1);
+        assert_eq!(encrypted[0].extension().unwrap(), "asc");
+        assert!(std::fs::read_to_string(&encrypted[0]).unwrap().starts_with("-----BEGIN PGP MESSAGE-----"));
+
+        let option: DecryptOption = DecryptOption::with_symmetric(None, None, "pass1234".to_string(), None);
+        let result: Result<Vec<u8>, GPGError> = gpg.decrypt_bytes(&std::fs::read(&encrypted[0]).unwrap(), option);
+        assert_eq!(result.unwrap(), b"testing armor override".to_vec());
+
+        cleanup_after_tests(name);
+    }
+
     #[cfg(not(feature = "test_legacy"))]
     #[test]
     fn test_sign_file_detached_signature_fail(){
@@ -1483,6 +3637,477 @@ mod tests {
         cleanup_after_tests(name);
     }
 
+    #[test]
+    fn test_sign_sig_expire(){
+        // test signing with valid and invalid signature expiry
+
+        let name:String  = generate_random_string();
+        let name: &str = name.as_str();
+
+        let gpg: GPG = get_gpg_init(name);
+        gen_unprotected_key(gpg.clone());
+        let key_result: Vec<ListKeyResult> = list_keys(gpg.clone(), true, false);
+
+        for sig_expire in ["0", "2w", "1Y", "2099-12-31", "20991231T000000"] {
+            let mut file = tempfile().unwrap();
+            write!(file, "testing sig expire").unwrap();
+            file.flush().unwrap();
+            let mut option: SignOption = gen_sign_detached_option(file, key_result[0].keyid.clone(), None, None);
+            option.sig_expire = Some(sig_expire.to_string());
+            let result: Result<CmdResult, GPGError> = gpg.sign(option);
+            assert_eq!(result.unwrap().is_success(), true);
+        }
+
+        // rejected before gpg was called
+        for sig_expire in ["", "2x", "d", "-1", "tomorrow", "2099-13-01"] {
+            let mut file = tempfile().unwrap();
+            write!(file, "testing sig expire").unwrap();
+            file.flush().unwrap();
+            let mut option: SignOption = gen_sign_detached_option(file, key_result[0].keyid.clone(), None, None);
+            option.sig_expire = Some(sig_expire.to_string());
+            let result: Result<CmdResult, GPGError> = gpg.sign(option);
+            assert!(matches!(result.unwrap_err().error_type, GPGErrorType::InvalidArgumentError(_)));
+        }
+
+        cleanup_after_tests(name);
+    }
+
+    #[test]
+    fn test_verify_file_extra_args(){
+        // test verify file with detached signature and extra args ( passed before the file(s) to verify )
+
+        let name:String  = generate_random_string();
+        let name: &str = name.as_str();
+
+        let gpg: GPG = get_gpg_init(name);
+        gen_unprotected_key(gpg.clone());
+        let key_result: Vec<ListKeyResult> = list_keys(gpg.clone(), true, false);
+
+        let data: String = PathBuf::from(get_output_dir(name)).join("data.txt").to_string_lossy().to_string();
+        std::fs::write(&data, "testing verify extra args").unwrap();
+        let signature: String = PathBuf::from(get_output_dir(name)).join("data.txt.sig").to_string_lossy().to_string();
+        let option: SignOption = SignOption::detached(None, Some(data.clone()), key_result[0].keyid.clone(), None, Some(signature.clone()));
+        let result: Result<CmdResult, GPGError> = gpg.sign(option);
+        assert_eq!(result.unwrap().is_success(), true);
+
+        // gpg would try to hash --verify-options and show-notations as the signed data if they were passed after the file(s)
+        let extra_args: Vec<String> = vec!["--verify-options".to_string(), "show-notations".to_string()];
+        let result: Vec<VerifyResult> = gpg.verify_file_with_result(None, Some(data), Some(signature), Some(extra_args)).unwrap();
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].status, "GOODSIG");
+
+        cleanup_after_tests(name);
+    }
+
+    #[cfg(not(feature = "test_legacy"))]
+    #[test]
+    fn test_sign_with_multiple_signers(){
+        // test signing with 2 passphrase protected keys in 1 pass with a passphrase provider
+
+        let name:String  = generate_random_string();
+        let name: &str = name.as_str();
+
+        let gpg: GPG = get_gpg_init(name);
+        let _ = gpg.gen_key(Some("passphrase_a".to_string()), None);
+        let _ = gpg.gen_key(Some("passphrase_b".to_string()), None);
+        let key_list: Vec<ListKeyResult> = list_keys(gpg.clone(), true, false);
+        let signers: Vec<String> = key_list.iter().map(|k| k.keyid.clone()).collect();
+
+        let passphrases: HashMap<String, String> = HashMap::from([
+            (key_list[0].keyid.clone(), "passphrase_a".to_string()),
+            (key_list[1].keyid.clone(), "passphrase_b".to_string()),
+        ]);
+        let provider: Arc<dyn PassphraseProvider> = Arc::new(move |request: &PassphraseRequest| {
+            return passphrases.get(&request.main_keyid).cloned();
+        });
+
+        let data: String = PathBuf::from(get_output_dir(name)).join("data.txt").to_string_lossy().to_string();
+        std::fs::write(&data, "testing multiple signers").unwrap();
+        let signature: String = PathBuf::from(get_output_dir(name)).join("data.txt.asc").to_string_lossy().to_string();
+        let option: SignOption = SignOption::with_signers(None, Some(data.clone()), signers.clone(), Some(provider.clone()), SignMode::Detached, Some(signature.clone()));
+        let result: Vec<SignResult> = gpg.sign_with_result(option).unwrap();
+        assert_eq!(result.len(), 2);
+        assert!(result.iter().all(|r| r.sig_type == "D"));
+        for key in key_list.iter() {
+            assert!(result.iter().any(|r| r.fingerprint == key.fingerprint));
+        }
+
+        let result: Vec<VerifyResult> = gpg.verify_file_with_result(None, Some(data.clone()), Some(signature), None).unwrap();
+        assert_eq!(result.len(), 2);
+        assert!(result.iter().all(|r| r.status == "GOODSIG"));
+
+        let output: String = PathBuf::from(get_output_dir(name)).join("data.txt.signed.asc").to_string_lossy().to_string();
+        let option: SignOption = SignOption::with_signers(None, Some(data), signers, Some(provider), SignMode::Clear, Some(output.clone()));
+        let result: Vec<SignResult> = gpg.sign_with_result(option).unwrap();
+        assert_eq!(result.len(), 2);
+        assert!(result.iter().all(|r| r.sig_type == "C"));
+
+        let result: Vec<VerifyResult> = gpg.verify_file_with_result(None, Some(output), None, None).unwrap();
+        assert_eq!(result.len(), 2);
+
+        cleanup_after_tests(name);
+    }
+
+    #[test]
+    fn test_sign_with_notations(){
+        // test adding notations, policy url and keyserver url to a signature and retrieving them on verification
+
+        let name:String  = generate_random_string();
+        let name: &str = name.as_str();
+
+        let gpg: GPG = get_gpg_init(name);
+        gen_unprotected_key(gpg.clone());
+        let key_result: Vec<ListKeyResult> = list_keys(gpg.clone(), true, false);
+
+        let data: String = PathBuf::from(get_output_dir(name)).join("data.txt").to_string_lossy().to_string();
+        std::fs::write(&data, "testing notations").unwrap();
+        let signature: String = PathBuf::from(get_output_dir(name)).join("data.txt.sig").to_string_lossy().to_string();
+        let mut option: SignOption = SignOption::detached(None, Some(data.clone()), key_result[0].keyid.clone(), None, Some(signature.clone()));
+        option.notations = vec![
+            ("build@crab-gnupg.test".to_string(), "build 42 = 100%".to_string(), false),
+            ("release@crab-gnupg.test".to_string(), "1".to_string(), true),
+        ];
+        option.policy_url = Some("https://crab-gnupg.test/policy".to_string());
+        option.keyserver_url = Some("hkps://keys.crab-gnupg.test".to_string());
+        let result: Result<CmdResult, GPGError> = gpg.sign(option);
+        assert_eq!(result.unwrap().is_success(), true);
+
+        // a critical notation unknown to gpg will make the signature bad
+        let result: Result<Vec<VerifyResult>, GPGError> = gpg.verify_file_with_result(None, Some(data.clone()), Some(signature.clone()), None);
+        assert!(result.is_err());
+
+        let known_notation: Vec<String> = vec!["--known-notation".to_string(), "release@crab-gnupg.test".to_string()];
+        let result: Vec<VerifyResult> = gpg.verify_file_with_result(None, Some(data), Some(signature), Some(known_notation)).unwrap();
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].status, "GOODSIG");
+        assert_eq!(result[0].notations, vec![
+            ("build@crab-gnupg.test".to_string(), "build 42 = 100%".to_string(), false),
+            ("release@crab-gnupg.test".to_string(), "1".to_string(), true),
+        ]);
+        assert_eq!(result[0].policy_url, Some("https://crab-gnupg.test/policy".to_string()));
+        assert_eq!(result[0].keyserver_url, Some("hkps://keys.crab-gnupg.test".to_string()));
+
+        let mut option: SignOption = SignOption::detached(None, Some(get_output_dir(name)), key_result[0].keyid.clone(), None, None);
+        option.notations = vec![("build id".to_string(), "1".to_string(), false)];
+        let result: Result<CmdResult, GPGError> = gpg.sign(option);
+        assert!(matches!(result.unwrap_err().error_type, GPGErrorType::InvalidArgumentError(_)));
+
+        cleanup_after_tests(name);
+    }
+
+    #[test]
+    fn test_encrypt_sign_with_notations(){
+        // test adding a notation to the signature while encrypting and retrieving it on decryption
+
+        let name:String  = generate_random_string();
+        let name: &str = name.as_str();
+
+        let gpg: GPG = get_gpg_init(name);
+        gen_unprotected_key(gpg.clone());
+        let key_result: Vec<ListKeyResult> = list_keys(gpg.clone(), true, false);
+
+        let mut option: EncryptOption = EncryptOption::default(None, None, vec![key_result[0].keyid.clone()], None);
+        option.sign = true;
+        option.sign_key = Some(key_result[0].keyid.clone());
+        option.notations = vec![("build@crab-gnupg.test".to_string(), "42".to_string(), false)];
+        option.policy_url = Some("https://crab-gnupg.test/policy".to_string());
+        let encrypted: Vec<u8> = gpg.encrypt_bytes(b"testing notations", option).unwrap();
+
+        let mut file = tempfile().unwrap();
+        file.write_all(&encrypted).unwrap();
+        file.flush().unwrap();
+        file.rewind().unwrap();
+        let decrypt_output: String = PathBuf::from(get_output_dir(name)).join("test_decrypt.txt").to_string_lossy().to_string();
+        let option: DecryptOption = DecryptOption::default(Some(file), None, key_result[0].keyid.clone(), None, Some(decrypt_output));
+        let result: DecryptResult = gpg.decrypt_with_result(option).unwrap();
+        assert_eq!(result.signatures.len(), 1);
+        assert_eq!(result.signatures[0].notations, vec![("build@crab-gnupg.test".to_string(), "42".to_string(), false)]);
+        assert_eq!(result.signatures[0].policy_url, Some("https://crab-gnupg.test/policy".to_string()));
+
+        cleanup_after_tests(name);
+    }
+
+    #[test]
+    fn test_verify_file_with_result(){
+        // test retrieving the result of a good signature made with a trusted key
+
+        let name:String  = generate_random_string();
+        let name: &str = name.as_str();
+
+        let gpg: GPG = get_gpg_init(name);
+        gen_unprotected_key(gpg.clone());
+        let key_result: Vec<ListKeyResult> = list_keys(gpg.clone(), true, false);
+
+        let data: String = PathBuf::from(get_output_dir(name)).join("data.txt").to_string_lossy().to_string();
+        std::fs::write(&data, "testing verification result").unwrap();
+        let signature: String = PathBuf::from(get_output_dir(name)).join("data.txt.sig").to_string_lossy().to_string();
+        let option: SignOption = SignOption::detached(None, Some(data.clone()), key_result[0].keyid.clone(), None, Some(signature.clone()));
+        let result: Result<CmdResult, GPGError> = gpg.sign(option);
+        assert_eq!(result.unwrap().is_success(), true);
+
+        let result: Vec<VerifyResult> = gpg.verify_file_with_result(None, Some(data), Some(signature), None).unwrap();
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].status, "GOODSIG");
+        assert_eq!(result[0].valid, true);
+        assert_eq!(result[0].trusted, true);
+        assert_eq!(result[0].trust_level, Some(TrustLevel::Ultimate));
+        assert_eq!(result[0].primary_fingerprint, Some(key_result[0].fingerprint.clone()));
+        assert!(result[0].fingerprint.is_some());
+        assert!(result[0].timestamp.is_some());
+        assert!(result[0].hash_algo.is_some());
+        assert!(result[0].pubkey_algo.is_some());
+
+        cleanup_after_tests(name);
+    }
+
+    #[test]
+    fn test_verify_file_with_result_untrusted_key(){
+        // test a valid signature made with a key that is not trusted is not reported as trusted
+
+        let name:String  = generate_random_string();
+        let name: &str = name.as_str();
+        let other_name:String  = generate_random_string();
+        let other_name: &str = other_name.as_str();
+
+        let gpg: GPG = get_gpg_init(name);
+        gen_unprotected_key(gpg.clone());
+        let key_result: Vec<ListKeyResult> = list_keys(gpg.clone(), true, false);
+
+        let data: String = PathBuf::from(get_output_dir(name)).join("data.txt").to_string_lossy().to_string();
+        std::fs::write(&data, "testing untrusted signature").unwrap();
+        let signature: String = PathBuf::from(get_output_dir(name)).join("data.txt.sig").to_string_lossy().to_string();
+        let option: SignOption = SignOption::detached(None, Some(data.clone()), key_result[0].keyid.clone(), None, Some(signature.clone()));
+        let result: Result<CmdResult, GPGError> = gpg.sign(option);
+        assert_eq!(result.unwrap().is_success(), true);
+        let public_key: String = PathBuf::from(get_output_dir(name)).join("public_key.asc").to_string_lossy().to_string();
+        let result: Result<CmdResult, GPGError> = gpg.export_public_key(None, Some(public_key.clone()), None);
+        assert_eq!(result.unwrap().is_success(), true);
+
+        // the other homedir only hold the public key without any trust
+        let other_gpg: GPG = get_gpg_init(other_name);
+        let result: Result<CmdResult, GPGError> = other_gpg.import_key(None, Some(public_key), false, None);
+        assert_eq!(result.unwrap().is_success(), true);
+        let result: Vec<VerifyResult> = other_gpg.verify_file_with_result(None, Some(data), Some(signature), None).unwrap();
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].status, "GOODSIG");
+        assert_eq!(result[0].valid, true);
+        assert_eq!(result[0].trusted, false);
+        assert_eq!(result[0].trust_level, Some(TrustLevel::Undefined));
+
+        cleanup_after_tests(name);
+        cleanup_after_tests(other_name);
+    }
+
+    #[test]
+    fn test_verify_file_with_result_bad_signature(){
+        // test retrieving the result of a bad signature from the error
+
+        let name:String  = generate_random_string();
+        let name: &str = name.as_str();
+
+        let gpg: GPG = get_gpg_init(name);
+        gen_unprotected_key(gpg.clone());
+        let key_result: Vec<ListKeyResult> = list_keys(gpg.clone(), true, false);
+
+        let data: String = PathBuf::from(get_output_dir(name)).join("data.txt").to_string_lossy().to_string();
+        std::fs::write(&data, "testing bad signature").unwrap();
+        let signature: String = PathBuf::from(get_output_dir(name)).join("data.txt.sig").to_string_lossy().to_string();
+        let option: SignOption = SignOption::detached(None, Some(data.clone()), key_result[0].keyid.clone(), None, Some(signature.clone()));
+        let result: Result<CmdResult, GPGError> = gpg.sign(option);
+        assert_eq!(result.unwrap().is_success(), true);
+        std::fs::write(&data, "testing tampered data").unwrap();
+
+        let error: GPGError = gpg.verify_file_with_result(None, Some(data), Some(signature), None).unwrap_err();
+        let result: Vec<VerifyResult> = decode_verify_result(&error.cmd_result.unwrap());
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].status, "BADSIG");
+        assert_eq!(result[0].valid, false);
+        assert_eq!(result[0].trusted, false);
+        assert_eq!(result[0].primary_fingerprint, Some(key_result[0].fingerprint.clone()));
+
+        cleanup_after_tests(name);
+    }
+
+    #[test]
+    fn test_verify_detached_from_memory(){
+        // test verifying data from a reader against a detached signature held in memory
+
+        let name:String  = generate_random_string();
+        let name: &str = name.as_str();
+
+        let gpg: GPG = get_gpg_init(name);
+        gen_unprotected_key(gpg.clone());
+        let key_result: Vec<ListKeyResult> = list_keys(gpg.clone(), true, false);
+
+        let data: String = PathBuf::from(get_output_dir(name)).join("data.txt").to_string_lossy().to_string();
+        std::fs::write(&data, "testing verification from memory").unwrap();
+        let signature: String = PathBuf::from(get_output_dir(name)).join("data.txt.asc").to_string_lossy().to_string();
+        let option: SignOption = SignOption::detached(None, Some(data.clone()), key_result[0].keyid.clone(), None, Some(signature.clone()));
+        let result: Result<CmdResult, GPGError> = gpg.sign(option);
+        assert_eq!(result.unwrap().is_success(), true);
+        let signature: Vec<u8> = std::fs::read(&signature).unwrap();
+
+        let result: StreamVerifyResult = gpg.verify_detached(Cursor::new(b"testing verification from memory".to_vec()), &signature).unwrap();
+        assert_eq!(result.verified, true);
+        assert_eq!(result.signatures.len(), 1);
+        assert_eq!(result.signatures[0].status, "GOODSIG");
+        assert_eq!(result.signatures[0].primary_fingerprint, Some(key_result[0].fingerprint.clone()));
+
+        // a borrowed reader ( ex: a request body ) do not need to outlive the call
+        let body: Vec<u8> = b"testing verification from memory".to_vec();
+        let result: StreamVerifyResult = gpg.verify_detached(body.as_slice(), &signature).unwrap();
+        assert_eq!(result.verified, true);
+
+        // a bad signature was returned with the result of the signature
+        let result: StreamVerifyResult = gpg.verify_detached(Cursor::new(b"testing tampered data".to_vec()), &signature).unwrap();
+        assert_eq!(result.verified, false);
+        assert_eq!(result.signatures.len(), 1);
+        assert_eq!(result.signatures[0].status, "BADSIG");
+        assert_eq!(result.signatures[0].valid, false);
+
+        // no signature could be checked
+        let result: Result<StreamVerifyResult, GPGError> = gpg.verify_detached(Cursor::new(b"testing".to_vec()), b"not a signature");
+        assert!(result.is_err());
+
+        cleanup_after_tests(name);
+    }
+
+    #[test]
+    fn test_verify_inline(){
+        // test verifying a clearsigned message from a reader
+
+        let name:String  = generate_random_string();
+        let name: &str = name.as_str();
+
+        let gpg: GPG = get_gpg_init(name);
+        gen_unprotected_key(gpg.clone());
+        let key_result: Vec<ListKeyResult> = list_keys(gpg.clone(), true, false);
+
+        let mut file = tempfile().unwrap();
+        write!(file, "testing inline verification").unwrap();
+        file.flush().unwrap();
+        let output: String = PathBuf::from(get_output_dir(name)).join("test_sign.asc").to_string_lossy().to_string();
+        let option: SignOption = gen_sign_default_option(file, key_result[0].keyid.clone(), None, Some(output.clone()));
+        let result: Result<CmdResult, GPGError> = gpg.sign(option);
+        assert_eq!(result.unwrap().is_success(), true);
+
+        let result: StreamVerifyResult = gpg.verify_inline(File::open(&output).unwrap()).unwrap();
+        assert_eq!(result.verified, true);
+        assert_eq!(result.signatures.len(), 1);
+        assert_eq!(result.signatures[0].status, "GOODSIG");
+        assert_eq!(result.signatures[0].trusted, true);
+
+        // a tampered clearsigned message was returned with the bad signature
+        let message: String = std::fs::read_to_string(&output).unwrap().replace("testing inline verification", "testing tampered verification");
+        let result: StreamVerifyResult = gpg.verify_inline(message.as_bytes()).unwrap();
+        assert_eq!(result.verified, false);
+        assert_eq!(result.signatures[0].status, "BADSIG");
+
+        let result: Result<StreamVerifyResult, GPGError> = gpg.verify_inline(Cursor::new(b"not a signed message".to_vec()));
+        assert!(result.is_err());
+
+        cleanup_after_tests(name);
+    }
+
+    #[test]
+    fn test_verify_with_policy(){
+        // test verifying a file signed by 2 signers against a 2 of 3 policy
+
+        let name:String  = generate_random_string();
+        let name: &str = name.as_str();
+
+        let gpg: GPG = get_gpg_init(name);
+        gen_unprotected_key(gpg.clone());
+        gen_unprotected_key(gpg.clone());
+        gen_unprotected_key(gpg.clone());
+        let key_result: Vec<ListKeyResult> = list_keys(gpg.clone(), true, false);
+        assert_eq!(key_result.len(), 3);
+
+        let data: String = PathBuf::from(get_output_dir(name)).join("data.txt").to_string_lossy().to_string();
+        std::fs::write(&data, "testing verification policy").unwrap();
+        let mut signatures: Vec<String> = vec![];
+        for (i, key) in key_result.iter().enumerate() {
+            let signature: String = PathBuf::from(get_output_dir(name)).join(format!("data.txt.{}.sig", i)).to_string_lossy().to_string();
+            let option: SignOption = SignOption::detached(None, Some(data.clone()), key.keyid.clone(), None, Some(signature.clone()));
+            let result: Result<CmdResult, GPGError> = gpg.sign(option);
+            assert_eq!(result.unwrap().is_success(), true);
+            signatures.push(signature);
+        }
+
+        // only the first 2 signers are allowed, the third signature will not be counted
+        let allowed_signers: Vec<String> = vec![key_result[0].fingerprint.clone(), key_result[1].fingerprint.clone()];
+        let policy: VerifyPolicy = VerifyPolicy::new(allowed_signers.clone(), 2);
+        let result: PolicyResult = gpg.verify_with_policy(data.clone(), signatures.clone(), policy).unwrap();
+        assert_eq!(result.passed, true);
+        assert_eq!(result.accepted_signers, allowed_signers);
+        assert_eq!(result.signatures.len(), 3);
+        assert_eq!(result.rejected.len(), 1);
+        assert_eq!(result.rejected[0].reason, PolicyRejection::NotAllowedSigner);
+        assert_eq!(result.rejected[0].primary_fingerprint, Some(key_result[2].fingerprint.clone()));
+
+        // the same signer is only counted once
+        let policy: VerifyPolicy = VerifyPolicy::new(allowed_signers.clone(), 2);
+        let result: PolicyResult = gpg.verify_with_policy(data.clone(), vec![signatures[0].clone(), signatures[0].clone()], policy).unwrap();
+        assert_eq!(result.passed, false);
+        assert_eq!(result.accepted_signers.len(), 1);
+
+        // all the keys were generated locally, so they are ultimately trusted
+        let mut policy: VerifyPolicy = VerifyPolicy::new(allowed_signers.clone(), 2);
+        policy.min_trust_level = Some(TrustLevel::Fully);
+        let result: PolicyResult = gpg.verify_with_policy(data.clone(), signatures.clone(), policy).unwrap();
+        assert_eq!(result.passed, true);
+
+        cleanup_after_tests(name);
+    }
+
+    #[test]
+    fn test_verify_with_policy_fail(){
+        // test verifying a tampered file and a missing signature against a policy
+
+        let name:String  = generate_random_string();
+        let name: &str = name.as_str();
+
+        let gpg: GPG = get_gpg_init(name);
+        gen_unprotected_key(gpg.clone());
+        gen_unprotected_key(gpg.clone());
+        let key_result: Vec<ListKeyResult> = list_keys(gpg.clone(), true, false);
+
+        let data: String = PathBuf::from(get_output_dir(name)).join("data.txt").to_string_lossy().to_string();
+        std::fs::write(&data, "testing verification policy").unwrap();
+        let mut signatures: Vec<String> = vec![];
+        for (i, key) in key_result.iter().enumerate() {
+            let signature: String = PathBuf::from(get_output_dir(name)).join(format!("data.txt.{}.sig", i)).to_string_lossy().to_string();
+            let option: SignOption = SignOption::detached(None, Some(data.clone()), key.keyid.clone(), None, Some(signature.clone()));
+            let result: Result<CmdResult, GPGError> = gpg.sign(option);
+            assert_eq!(result.unwrap().is_success(), true);
+            signatures.push(signature);
+        }
+        std::fs::write(&data, "testing verification policy tampered").unwrap();
+
+        let allowed_signers: Vec<String> = key_result.iter().map(|key| key.fingerprint.clone()).collect();
+        let policy: VerifyPolicy = VerifyPolicy::new(allowed_signers.clone(), 1);
+        let result: PolicyResult = gpg.verify_with_policy(data.clone(), signatures.clone(), policy).unwrap();
+        assert_eq!(result.passed, false);
+        assert_eq!(result.accepted_signers.len(), 0);
+        assert_eq!(result.rejected.len(), 2);
+        assert!(result.rejected.iter().all(|rejected| rejected.reason == PolicyRejection::BadSignature));
+
+        let missing: String = PathBuf::from(get_output_dir(name)).join("missing.sig").to_string_lossy().to_string();
+        let policy: VerifyPolicy = VerifyPolicy::new(allowed_signers.clone(), 1);
+        let result: Result<PolicyResult, GPGError> = gpg.verify_with_policy(data.clone(), vec![missing], policy);
+        assert!(result.is_err());
+
+        // a policy that would pass without any good signature
+        let policy: VerifyPolicy = VerifyPolicy::new(allowed_signers.clone(), 0);
+        let result: Result<PolicyResult, GPGError> = gpg.verify_with_policy(data.clone(), signatures.clone(), policy);
+        assert!(matches!(result.unwrap_err().error_type, GPGErrorType::InvalidArgumentError(_)));
+        let policy: VerifyPolicy = VerifyPolicy::new(vec![], 1);
+        let result: Result<PolicyResult, GPGError> = gpg.verify_with_policy(data, signatures, policy);
+        assert!(matches!(result.unwrap_err().error_type, GPGErrorType::InvalidArgumentError(_)));
+
+        cleanup_after_tests(name);
+    }
+
     #[test]
     fn test_verify_file_fail(){
         // test verify file fail (provide a file without signature)